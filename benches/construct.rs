@@ -0,0 +1,51 @@
+//! Making a large `Tasks` from timestamps that are already parsed, against
+//! parsing them from strings first.
+
+use std::collections::HashMap;
+use std::hint::black_box;
+
+use chrono::Duration;
+use chrono::NaiveDateTime;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BatchSize;
+use criterion::Criterion;
+use loago::format_timestamp;
+use loago::Tasks;
+
+const TASKS: usize = 10_000;
+
+fn timestamps() -> HashMap<String, NaiveDateTime> {
+    (0..TASKS)
+        .map(|task| {
+            let timestamp = NaiveDateTime::UNIX_EPOCH
+                + Duration::seconds(task as i64 * 3_600);
+            (format!("task-{task}"), timestamp)
+        })
+        .collect()
+}
+
+fn construct(c: &mut Criterion) {
+    let timestamps = timestamps();
+    let strings: HashMap<String, String> = timestamps
+        .iter()
+        .map(|(task, timestamp)| (task.clone(), format_timestamp(*timestamp)))
+        .collect();
+    c.bench_function("from timestamps", |b| {
+        b.iter_batched(
+            || timestamps.clone(),
+            |timestamps| Tasks::from(black_box(timestamps)),
+            BatchSize::LargeInput,
+        )
+    });
+    c.bench_function("try from strings", |b| {
+        b.iter_batched(
+            || strings.clone(),
+            |strings| Tasks::try_from(black_box(strings)).unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+criterion_group!(benches, construct);
+criterion_main!(benches);
@@ -0,0 +1,36 @@
+//! Rendering a large list of tasks, like a status bar that renders every task
+//! in a tight loop would.
+
+use std::hint::black_box;
+
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::Criterion;
+use loago::OutputOptions;
+use loago::OutputTasks;
+
+const TASKS: usize = 10_000;
+
+fn output() -> OutputTasks {
+    (0..TASKS)
+        .map(|task| {
+            (format!("task-{}", "x".repeat(task % 40)), task.to_string())
+        })
+        .collect::<Vec<_>>()
+        .into()
+}
+
+fn render(c: &mut Criterion) {
+    let output = output();
+    c.bench_function("display", |b| b.iter(|| black_box(&output).to_string()));
+    let grouped = OutputOptions {
+        group_digits: true,
+        ..Default::default()
+    };
+    c.bench_function("render grouped", |b| {
+        b.iter(|| black_box(&output).render(&grouped))
+    });
+}
+
+criterion_group!(benches, render);
+criterion_main!(benches);
@@ -1,105 +1,2013 @@
-use std::collections::HashMap;
-use std::error::Error;
-use std::fs::OpenOptions;
-use std::io::Write;
-use std::path::Path;
+use std::convert::Infallible;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::mem;
+use std::path::PathBuf;
+use std::slice;
 
+use chrono::Duration;
+use chrono::Local;
+use chrono::NaiveDate;
+use chrono::NaiveDateTime;
+use chrono::NaiveTime;
+use chrono::Offset;
+use chrono::TimeZone;
+use clap::builder::NonEmptyStringValueParser;
+use clap::builder::RangedU64ValueParser;
 use clap::Parser;
 use clap::Subcommand;
+use clap::ValueEnum;
+use loago::days;
+use loago::days_hours_minutes;
+use loago::days_hours_minutes_seconds;
+use loago::days_rounded;
+use loago::days_tenths;
+use loago::days_with_unit;
+use loago::errors::Error;
+use loago::format_duration;
+use loago::now;
+use loago::parse_date;
+use loago::parse_date_end;
+use loago::parse_duration;
+use loago::parse_relative_day;
+use loago::parse_signed_duration;
+use loago::parse_timestamp;
+use loago::weeks;
+use loago::MergeStrategy;
+use loago::OutputOptions;
+use loago::OutputTasks;
+use loago::Task;
+use loago::TaskAge;
 use loago::Tasks;
+use loago::NEVER;
+use regex::Regex;
+use serde::Deserialize;
 
-const HOURS_IN_DAY: i64 = 24;
-const MINUTES_IN_HOUR: i64 = 60;
+#[cfg(feature = "color")]
+use crate::color::red as paint_overdue;
+#[cfg(feature = "color")]
+use crate::color::ColorChoice;
+use crate::format;
+use crate::format::Format;
+use crate::format::Template;
+use crate::history::Entry;
+use crate::history::History;
+use crate::repo::normalize_name;
+use crate::repo::Data;
+use crate::repo::Indent;
+use crate::repo::JsonRepo;
+use crate::repo::TimestampFormat;
+use crate::APP_NAME;
 
 #[derive(Parser)]
 #[command(author, version, about)]
 pub struct Args {
     #[command(subcommand)]
-    pub action: Action,
+    pub action:           Action,
+    /// Whether to color overdue tasks [default: auto]
+    #[cfg(feature = "color")]
+    #[arg(long, global = true, value_enum)]
+    pub color:            Option<ColorChoice>,
+    /// Don't print anything but the requested output and errors.
+    #[arg(short, long, global = true)]
+    pub quiet:            bool,
+    /// Confirm what every command changed.
+    #[arg(short, long, global = true, conflicts_with = "quiet")]
+    pub verbose:          bool,
+    /// Spaces to indent the data file with, 0 for none, or "tab" [default: 2]
+    #[arg(long, global = true, value_name = "N")]
+    pub json_indent:      Option<Indent>,
+    /// How to write timestamps to the data file, both are always read
+    #[arg(long, global = true, value_enum, default_value_t)]
+    pub timestamps:       TimestampFormat,
+    /// Print what would change, without changing anything.
+    #[arg(long, global = true)]
+    pub dry_run:          bool,
+    /// Let a command remove every task, which otherwise fails in case it
+    /// removed more than was meant to.
+    #[arg(long, global = true)]
+    pub allow_empty:      bool,
+    /// Fail if the directory the data file goes in doesn't exist, instead of
+    /// creating it.
+    #[arg(long, global = true)]
+    pub no_create_dirs:   bool,
+    /// Print errors as json objects instead, `{"error": "message", "code":
+    /// N}`, where `code` is the exit code. `view` prints the tasks as json
+    /// too, like with `--format json`, unless it's asked for another output.
+    #[arg(long, global = true)]
+    pub json:             bool,
+    /// Use this data file instead of the one in the data directory, or
+    /// `$LOAGO_DATA`.
+    #[arg(long, visible_alias = "file", global = true, value_name = "PATH")]
+    pub data_file:        Option<PathBuf>,
+    /// Fail on fields in the data file that loago doesn't know, like typos
+    /// in hand edits, or that it has more than once, instead of ignoring
+    /// them.
+    #[arg(long, global = true)]
+    pub strict_parse:     bool,
+    /// Treat tasks without an interval as if they had this one, like `7d`,
+    /// when telling which are overdue.
+    #[arg(long, global = true, value_name = "DURATION", value_parser = parse_duration)]
+    pub default_interval: Option<Duration>,
+}
+
+/// What the global flags decided, for the actions to go by.
+pub struct Options {
+    pub color:            bool,
+    pub verbosity:        Verbosity,
+    /// Nothing gets written, see [`JsonRepo::with_dry_run`].
+    pub dry_run:          bool,
+    /// The unit to display how long ago tasks were done in, when no flag says
+    /// otherwise.
+    pub unit:             Unit,
+    /// The order `view` displays the tasks in, when no flag says otherwise.
+    pub sort:             Sort,
+    /// Whether `view` displays the tasks in the opposite order, unless
+    /// `--no-reverse` says otherwise.
+    pub reverse:          bool,
+    /// Tasks done longer ago than this are red too, like the overdue ones.
+    pub red_after:        Option<Duration>,
+    /// How many tasks `do` and `remove` take at once without `--force`.
+    pub max_tasks:        usize,
+    /// Whether to create the directories the data file goes in when writing
+    /// it, if they don't exist.
+    pub create_dirs:      bool,
+    /// The interval of the tasks that don't have their own, when telling
+    /// which are overdue.
+    pub default_interval: Option<Duration>,
+    /// The sections of `view --group-by age`, from the shortest duration to
+    /// the longest.
+    pub age_buckets:      Vec<(String, Duration)>,
+    /// Whether errors are printed as json, and so `view`'s tasks too.
+    pub json:             bool,
+}
+
+/// How much `loago` says about what it's doing, besides the output that was
+/// asked for.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Only errors.
+    Quiet,
+    /// Warnings and confirmations too.
+    Normal,
+    /// What exactly every command changed too.
+    Verbose,
+}
+
+impl Options {
+    /// Give the tasks without an interval the default one, for commands that
+    /// only read them, so that it never ends up in the data file.
+    pub fn apply_default_interval(&self, tasks: &mut Tasks) {
+        if let Some(interval) = self.default_interval {
+            tasks.fill_intervals(interval);
+        }
+    }
+
+    /// Print a confirmation of what was done, unless quiet.
+    pub fn info(&self, message: impl fmt::Display) {
+        if self.verbosity >= Verbosity::Normal {
+            println!("{message}");
+        }
+    }
+
+    /// Print the details of what was done, if verbose.
+    pub fn detail(&self, message: impl fmt::Display) {
+        if self.verbosity >= Verbosity::Verbose {
+            println!("{message}");
+        }
+    }
+
+    /// Print a warning about something that's wrong, but not wrong enough to
+    /// fail on, unless quiet.
+    pub fn warn(&self, message: impl fmt::Display) {
+        if self.verbosity >= Verbosity::Normal {
+            eprintln!("{APP_NAME}: {message}");
+        }
+    }
 }
 
 #[derive(Subcommand)]
 pub enum Action {
     /// Update tasks' dates to now.
     /// Creates tasks that didn't exist before.
+    /// Doing a group does every task in it instead.
     #[command(visible_alias = "add")]
     #[command(visible_alias = "new")]
     #[command(visible_alias = "update")]
     #[command(visible_alias = "reset")]
-    Do { tasks: Vec<String> },
+    Do {
+        /// Update every existing task instead, without creating any.
+        #[arg(short, long, conflicts_with = "tasks")]
+        all:       bool,
+        /// Mark the tasks as never done instead, so that they're always the
+        /// oldest ones.
+        #[arg(short, long, conflicts_with = "all")]
+        never:     bool,
+        /// Fail without updating anything if any of the tasks don't exist,
+        /// instead of creating them. Guards against typos.
+        #[arg(long, conflicts_with = "all")]
+        no_create: bool,
+        /// Update every existing task with any of these tags instead,
+        /// without creating any.
+        #[arg(
+            short,
+            long,
+            conflicts_with_all = ["tasks", "all", "no_create"]
+        )]
+        tag:       Option<Vec<String>>,
+        /// Update every existing task with a name that matches this pattern
+        /// instead, without creating any. `*` matches anything, and `?` any
+        /// single character.
+        #[arg(
+            long,
+            value_name = "PATTERN",
+            conflicts_with_all = ["tasks", "all", "no_create", "tag"]
+        )]
+        glob:      Option<String>,
+        /// Take more tasks at once than the config file's `max_tasks`, 50 by
+        /// default, which is likely a shell glob gone wrong otherwise.
+        #[arg(long)]
+        force:     bool,
+        /// Record why the tasks were done, like `guests coming`, for
+        /// `loago history` to show.
+        #[arg(long, value_name = "TEXT")]
+        note:      Option<String>,
+        /// Also set the tasks' interval, like `loago every` does, so that
+        /// `loago do vacuum --every 7d` both does vacuuming and makes it
+        /// weekly. A plain number is in days.
+        #[arg(
+            long,
+            value_name = "DURATION",
+            value_parser = parse_duration,
+            conflicts_with_all = ["all", "tag", "glob"]
+        )]
+        every:     Option<Duration>,
+        /// Update the tasks named in this plain text file instead, one per
+        /// line. Blank lines and lines starting with `#` are skipped. There's
+        /// no limit to how many it can name.
+        #[arg(
+            long,
+            value_name = "FILE",
+            conflicts_with_all = ["tasks", "all", "tag", "glob"]
+        )]
+        from:      Option<PathBuf>,
+        /// Same as `--from`, but read the task names from stdin.
+        #[arg(
+            long,
+            conflicts_with_all = ["tasks", "all", "tag", "glob", "from"]
+        )]
+        stdin:     bool,
+        #[arg(value_parser = task_name)]
+        tasks:     Vec<String>,
+    },
     /// View all (default) or specified tasks, with how many days (and
-    /// optionally, hours and minutes) ago you last did them.
+    /// optionally, hours, minutes and seconds) ago you last did them.
+    /// Tasks with an interval show when they're due instead.
     #[command(visible_alias = "list")]
     #[command(visible_alias = "look")]
     #[command(visible_alias = "see")]
-    View {
-        /// Show hours and minutes too, in this format: `{days}d {hours}h
-        /// {minutes}m`
+    View(ViewArgs),
+    /// View the tasks and keep refreshing the view, to see what other
+    /// `loago`s do. Takes the same options as `view`. Stop with Ctrl-C.
+    Watch {
+        /// How many seconds to wait between refreshes.
+        #[arg(long, default_value_t = 60, value_name = "SECONDS")]
+        interval: u64,
+        #[command(flatten)]
+        view:     ViewArgs,
+    },
+    /// View the tasks with names that contain the query, ignoring case.
+    Search {
+        query:         String,
+        /// Treat the query as a regular expression instead.
         #[arg(short, long)]
-        minutes: bool,
-        /// Don't display these provided tasks.
+        regex:         bool,
+        /// Print how many tasks match instead.
         #[arg(short, long)]
-        except:  Option<Vec<String>>,
-        tasks:   Option<Vec<String>>,
+        count_only:    bool,
+        /// Exit with 1 if no tasks match, for scripts.
+        #[arg(long)]
+        fail_if_empty: bool,
     },
     /// Remove specified tasks from the list.
     #[command(visible_alias = "delete")]
-    Remove { tasks: Vec<String> },
+    Remove {
+        /// Fail without removing anything if any of the tasks don't exist.
+        #[arg(long)]
+        strict:     bool,
+        /// Remove every task except the specified ones instead.
+        #[arg(short, long, requires = "tasks")]
+        except:     bool,
+        /// Remove every task last done longer ago than this instead, like
+        /// `90d`.
+        #[arg(
+            long,
+            value_parser = parse_duration,
+            conflicts_with_all = ["tasks", "except", "strict"]
+        )]
+        older_than: Option<Duration>,
+        /// Print the tasks `--older-than` removed to stdout instead of
+        /// stderr, to pipe them somewhere.
+        #[arg(long, requires = "older_than")]
+        porcelain:  bool,
+        /// Take more tasks at once than the config file's `max_tasks`, 50 by
+        /// default, which is likely a shell glob gone wrong otherwise.
+        #[arg(long)]
+        force:      bool,
+        #[arg(value_parser = task_name)]
+        tasks:      Vec<String>,
+    },
+    /// Remove every task. Groups are kept.
+    Clear,
+    /// Print the most recently done task, and how long ago that was.
+    Last {
+        #[command(flatten)]
+        unit: UnitFlags,
+    },
+    /// Print the task that was done the longest ago, which is probably the one
+    /// to do next, and how long ago that was.
+    #[command(visible_alias = "oldest")]
+    Next {
+        #[command(flatten)]
+        unit: UnitFlags,
+    },
+    /// Print how many tasks there are, how many have an interval, and how
+    /// many of those are overdue.
+    Stats {
+        /// Print how every task with an interval is keeping up with it
+        /// instead, furthest behind first. Every line is
+        /// `{standing}\t{percent}%\t{name}`, where `standing` is `on-track` or
+        /// `behind`, and `percent` is how much of the interval has passed.
+        #[arg(long)]
+        streaks: bool,
+    },
+    /// Print how many tasks there are.
+    Count {
+        /// Only count the tasks last done longer ago than this, like `90d`.
+        #[arg(long, value_parser = parse_duration)]
+        older_than:    Option<Duration>,
+        /// Exit with 1 if there are no tasks to count, for scripts.
+        #[arg(long)]
+        fail_if_empty: bool,
+    },
+    /// Add tags to a task, to filter tasks by later.
+    Tag {
+        #[arg(value_parser = task_name)]
+        task: String,
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+    /// Remove tags from a task, or all of its tags if none are specified.
+    Untag {
+        #[arg(value_parser = task_name)]
+        task: String,
+        tags: Vec<String>,
+    },
+    /// Set how often a task is meant to be done, or clear it if no interval
+    /// is specified.
+    Every {
+        #[arg(value_parser = task_name)]
+        task:     String,
+        /// Like `7d`, `12h` or `1d12h30m`. Plain numbers are days.
+        #[arg(value_parser = parse_duration)]
+        interval: Option<Duration>,
+    },
+    /// Move when a task was last done, for when it was logged at the wrong
+    /// time.
+    Adjust {
+        #[arg(value_parser = task_name)]
+        task:  String,
+        /// Like `-2h` for 2 hours earlier, or `+1d` for a day later.
+        #[arg(allow_hyphen_values = true, value_parser = parse_signed_duration)]
+        delta: Duration,
+    },
+    /// Rename a task, or with `--prefix`, every task starting with `OLD`. A
+    /// task renamed to one that exists collapses into it, keeping whichever
+    /// was done more recently.
+    Rename {
+        #[arg(value_parser = task_name)]
+        old:    String,
+        #[arg(value_parser = task_name)]
+        new:    String,
+        /// Rename every task starting with `OLD` to start with `NEW`
+        /// instead, like `rename --prefix home- house-`.
+        #[arg(long)]
+        prefix: bool,
+    },
+    /// Exchange the names of two tasks, for when they were named the wrong
+    /// way around. Everything about them is kept, like their timestamps.
+    Swap {
+        #[arg(value_parser = task_name)]
+        a: String,
+        #[arg(value_parser = task_name)]
+        b: String,
+    },
+    /// Put off a task, so that it's only due after this long from now, or
+    /// push it this much later if it doesn't have an interval.
+    Snooze {
+        #[arg(value_parser = task_name)]
+        task:     String,
+        /// Like `2d` or `12h`. Plain numbers are days.
+        #[arg(value_parser = parse_duration)]
+        duration: Duration,
+    },
+    /// Restore the data from a backup of the data file, or create tasks from
+    /// a list of them.
+    Import {
+        /// A copy of a data file, to replace the current data with. It's
+        /// checked to be valid before anything is replaced.
+        #[arg(required_unless_present = "names", conflicts_with = "names")]
+        file:     Option<PathBuf>,
+        /// Merge the backup into the current data instead of replacing it.
+        /// Tasks in both keep the more recently done version, unless
+        /// `--strategy` says otherwise.
+        #[arg(short, long, requires = "file")]
+        merge:    bool,
+        /// Which version of a task in both wins when merging.
+        #[arg(long, value_enum, default_value_t, requires = "merge")]
+        strategy: Strategy,
+        /// A plain text file with a task name on every line, to create tasks
+        /// with the current date from. Blank lines and lines starting with
+        /// `#` are skipped, and tasks that already exist are left as is.
+        #[arg(long, value_name = "FILE")]
+        names:    Option<PathBuf>,
+    },
+    /// Manage groups of tasks that get done together.
+    Group {
+        #[command(subcommand)]
+        action: GroupAction,
+    },
+    /// Open the data file in `$EDITOR`, or `$VISUAL`, and check that it's
+    /// still valid afterwards.
+    Edit,
+    /// Check that the data file can be trusted, printing how many tasks it
+    /// has and every one with an invalid timestamp, and failing if there are
+    /// any.
+    #[command(visible_alias = "check")]
+    Verify,
+    /// Print where the data file is, without reading or creating it.
+    Path,
+    /// Check for what commonly goes wrong with the data file, like a
+    /// directory it can't be written to, or it being invalid, and print
+    /// what it looks like otherwise. Fails if anything is broken. Nothing is
+    /// changed.
+    Doctor,
+    /// List the most recent commands that changed the data file, oldest
+    /// first.
+    #[command(visible_alias = "log")]
+    History {
+        /// How many commands to list.
+        #[arg(short = 'n', long, default_value_t = 10, value_name = "N")]
+        limit: usize,
+        /// Only list the commands that were given this task, like to see the
+        /// notes of every time it was done.
+        #[arg(value_parser = task_name)]
+        task:  Option<String>,
+    },
+    /// Rewrite the data file the way loago writes it, with sorted keys, the
+    /// chosen indentation and the current format version, without changing
+    /// any of the tasks. Says whether that changed the file.
+    Tidy,
+    /// Trim the history of the commands that changed the data file down to
+    /// the most recent ones. It's trimmed to the last 1000 automatically.
+    #[command(visible_alias = "prune")]
+    Gc {
+        /// How many of the most recent commands to keep.
+        #[arg(long, default_value_t = 100, value_name = "N")]
+        keep: usize,
+    },
+    /// Show a desktop notification listing the overdue tasks, if there are
+    /// any. Meant to be run on a timer.
+    #[cfg(feature = "notify")]
+    Notify,
+    /// Print a completion script for the shell, to be sourced by it.
+    #[cfg(feature = "completions")]
+    Completions { shell: clap_complete::Shell },
+    /// Browse the tasks in the terminal, doing or removing the highlighted
+    /// one with a key.
+    #[cfg(feature = "tui")]
+    Tui,
+    /// Print the JSON Schema of the data file, for editors to validate and
+    /// complete it with.
+    #[cfg(feature = "schema")]
+    Schema,
+}
+
+/// The order `view` displays the tasks in.
+#[derive(Clone, Copy, Default, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Sort {
+    /// The most recently done first.
+    #[default]
+    #[value(alias = "age")]
+    #[serde(alias = "age")]
+    Recent,
+    /// The ones done the longest ago first.
+    Stale,
+    /// Alphabetically.
+    Name,
+    /// The ones furthest past their interval first, then the ones without an
+    /// interval, the most recently done first.
+    Overdue,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum GroupBy {
+    /// A section for every tag, and a last one for tasks without tags.
+    Tag,
+    /// A section for how long ago the tasks were done, like `today` or `this
+    /// week`, as the config file's `age_buckets` say, and a last one for the
+    /// tasks done longer ago than that.
+    Age,
+}
+
+#[derive(Subcommand)]
+pub enum GroupAction {
+    /// Add tasks to a group, creating it if it didn't exist before.
+    #[command(visible_alias = "new")]
+    Add {
+        #[arg(value_parser = task_name)]
+        group: String,
+        #[arg(required = true, value_parser = task_name)]
+        tasks: Vec<String>,
+    },
+    /// Remove tasks from a group, or the whole group if no tasks are
+    /// specified. The tasks themselves are not removed.
+    #[command(visible_alias = "delete")]
+    Remove {
+        #[arg(value_parser = task_name)]
+        group: String,
+        #[arg(value_parser = task_name)]
+        tasks: Vec<String>,
+    },
+    /// View all groups and their tasks.
+    #[command(visible_alias = "view")]
+    List,
 }
 
 impl Action {
+    /// Whether executing this action writes to the data file.
+    pub fn mutates(&self) -> bool {
+        match self {
+            Self::Do { .. }
+            | Self::Remove { .. }
+            | Self::Clear
+            | Self::Tag { .. }
+            | Self::Untag { .. }
+            | Self::Every { .. }
+            | Self::Adjust { .. }
+            | Self::Snooze { .. }
+            | Self::Rename { .. }
+            | Self::Swap { .. }
+            | Self::Import { .. }
+            | Self::Edit
+            | Self::Tidy
+            | Self::Gc { .. } => true,
+            Self::View(_)
+            | Self::Watch { .. }
+            | Self::Search { .. }
+            | Self::Count { .. }
+            | Self::Stats { .. }
+            | Self::Last { .. }
+            | Self::Next { .. }
+            | Self::History { .. }
+            | Self::Verify
+            | Self::Path
+            | Self::Doctor => false,
+            #[cfg(feature = "notify")]
+            Self::Notify => false,
+            #[cfg(feature = "completions")]
+            Self::Completions { .. } => false,
+            // It locks the data file for every change itself, see `tui::run`.
+            #[cfg(feature = "tui")]
+            Self::Tui => false,
+            #[cfg(feature = "schema")]
+            Self::Schema => false,
+            Self::Group { action } => !matches!(action, GroupAction::List),
+        }
+    }
+
+    /// The only tasks this action needs loaded, if it doesn't need all of
+    /// them. See [`ViewArgs::only_tasks`].
+    pub fn only_tasks(&self) -> Option<&[String]> {
+        match self {
+            Self::View(view) => view.only_tasks(),
+            _ => None,
+        }
+    }
+
+    /// What to record in the history about this action, if it's one that
+    /// changes the data file.
+    pub fn history_entry(&self) -> Option<Entry> {
+        let (command, args) = match self {
+            Self::Do { all: true, .. } => ("do --all", Vec::new()),
+            Self::Do {
+                tag: Some(tags), ..
+            } => ("do --tag", tags.clone()),
+            Self::Do {
+                glob: Some(glob), ..
+            } => ("do --glob", vec![glob.clone()]),
+            Self::Do {
+                from: Some(from), ..
+            } => ("do --from", vec![from.display().to_string()]),
+            Self::Do { stdin: true, .. } => ("do --stdin", Vec::new()),
+            Self::Do {
+                never: true, tasks, ..
+            } => ("do --never", tasks.clone()),
+            Self::Do { tasks, .. } => ("do", tasks.clone()),
+            Self::Remove {
+                older_than: Some(cutoff),
+                ..
+            } => ("remove --older-than", vec![format_duration(*cutoff)]),
+            Self::Remove {
+                except: true,
+                tasks,
+                ..
+            } => ("remove --except", tasks.clone()),
+            Self::Remove { tasks, .. } => ("remove", tasks.clone()),
+            Self::Clear => ("clear", Vec::new()),
+            Self::Tag { task, tags } => {
+                ("tag", [slice::from_ref(task), tags].concat())
+            },
+            Self::Untag { task, tags } => {
+                ("untag", [slice::from_ref(task), tags].concat())
+            },
+            Self::Every { task, interval } => (
+                "every",
+                [task.clone()]
+                    .into_iter()
+                    .chain(interval.map(format_duration))
+                    .collect(),
+            ),
+            Self::Adjust { task, delta } => {
+                ("adjust", vec![task.clone(), format_duration(*delta)])
+            },
+            Self::Snooze { task, duration } => {
+                ("snooze", vec![task.clone(), format_duration(*duration)])
+            },
+            Self::Swap { a, b } => ("swap", vec![a.clone(), b.clone()]),
+            Self::Rename { old, new, prefix } => {
+                (if *prefix { "rename --prefix" } else { "rename" }, vec![
+                    old.clone(),
+                    new.clone(),
+                ])
+            },
+            Self::Import {
+                file: Some(file),
+                merge,
+                ..
+            } => (if *merge { "import --merge" } else { "import" }, vec![file
+                .display()
+                .to_string()]),
+            Self::Import {
+                names: Some(names), ..
+            } => ("import --names", vec![names.display().to_string()]),
+            Self::Group {
+                action: GroupAction::Add { group, tasks },
+            } => ("group add", [slice::from_ref(group), tasks].concat()),
+            Self::Group {
+                action: GroupAction::Remove { group, tasks },
+            } => ("group remove", [slice::from_ref(group), tasks].concat()),
+            Self::Edit => ("edit", Vec::new()),
+            Self::Tidy => ("tidy", Vec::new()),
+            _ => return None,
+        };
+        let mut entry = Entry::new(command, args);
+        if let Self::Do { note, .. } = self {
+            entry.note.clone_from(note);
+        }
+        Some(entry)
+    }
+
     pub fn execute(
         self,
-        path: impl AsRef<Path>,
-        mut tasks: Tasks,
-    ) -> Result<(), Box<dyn Error>> {
+        repo: &JsonRepo,
+        mut data: Data,
+        options: &Options,
+    ) -> Result<(), Error> {
+        let tasks = &mut data.tasks;
         match self {
-            Self::Do { tasks: provided } => {
-                tasks.update_multiple(provided);
-                save(tasks, path)
+            Self::Do {
+                all,
+                never,
+                no_create,
+                tag,
+                glob,
+                force,
+                note: _,
+                every,
+                from,
+                stdin,
+                tasks: provided,
+            } => {
+                if tag.is_some() || glob.is_some() {
+                    let glob = glob.as_deref().map(glob_regex);
+                    let selected = select(tasks, tag.as_deref(), glob.as_ref());
+                    let count = selected.len();
+                    options
+                        .detail(format_args!("updated: {}", list(&selected)));
+                    if never {
+                        tasks.never_done(selected);
+                    } else {
+                        tasks.update_multiple(selected);
+                    }
+                    save(repo, data, options)?;
+                    let noun = if count == 1 { "task" } else { "tasks" };
+                    options.info(format_args!("{count} {noun} matched"));
+                    return Ok(());
+                }
+                let listed = match (from, stdin) {
+                    (Some(from), _) => Some(fs::read_to_string(from)?),
+                    (None, true) => Some(io::read_to_string(io::stdin())?),
+                    (None, false) => None,
+                };
+                let provided = match &listed {
+                    Some(contents) => {
+                        task_names(contents).map(normalize_name).collect()
+                    },
+                    None => {
+                        ensure_not_too_many(&provided, force, options)?;
+                        provided
+                    },
+                };
+                if all {
+                    tasks.update_all();
+                    let mut updated: Vec<&str> =
+                        tasks.iter().map(|(task, _)| task).collect();
+                    updated.sort_unstable();
+                    options.detail(format_args!("updated: {}", list(&updated)));
+                    return save(repo, data, options);
+                }
+                let provided = data.groups.expand(provided);
+                if no_create {
+                    ensure_all_exist(tasks, &provided)?;
+                }
+                let count = provided.len();
+                if never {
+                    options.detail(format_args!(
+                        "marked as never done: {}",
+                        list(&provided)
+                    ));
+                    tasks.never_done(provided.iter().cloned());
+                    set_intervals(tasks, &provided, every);
+                    save(repo, data, options)?;
+                } else {
+                    let now = now();
+                    let mut created = Vec::new();
+                    let mut last_done = Vec::new();
+                    for task in &provided {
+                        match tasks.update_at(task.clone(), now) {
+                            Some(previous) => {
+                                last_done.push((task, now - previous))
+                            },
+                            None => created.push(task),
+                        }
+                    }
+                    set_intervals(tasks, &provided, every);
+                    save(repo, data, options)?;
+                    let mut message = format!("updated: {}", list(&provided));
+                    if !created.is_empty() {
+                        let verb =
+                            if created.len() == 1 { "was" } else { "were" };
+                        message +=
+                            &format!(" ({} {verb} created)", list(&created));
+                    }
+                    options.detail(message);
+                    for (task, ago) in last_done {
+                        options.detail(format_args!(
+                            "{task} was last done {} ago",
+                            days_with_unit(ago)
+                        ));
+                    }
+                }
+                // Nothing on the command line says how many there were.
+                if listed.is_some() {
+                    let noun = if count == 1 { "task" } else { "tasks" };
+                    options.info(format_args!("updated {count} {noun}"));
+                }
+                Ok(())
             },
-            Self::Remove { tasks: provided } => {
-                tasks.remove_multiple(&provided);
-                save(tasks, path)
+            Self::Remove {
+                older_than: Some(cutoff),
+                porcelain,
+                ..
+            } => {
+                let mut removed = tasks.remove_older_than(cutoff, now());
+                save(repo, data, options)?;
+                // Without naming the tasks, there's no telling which ones
+                // were removed otherwise.
+                removed.sort_unstable();
+                for task in removed {
+                    if porcelain {
+                        println!("{task}");
+                    } else if options.verbosity >= Verbosity::Normal {
+                        eprintln!("{task}");
+                    }
+                }
+                Ok(())
             },
-            Self::View {
-                minutes,
+            Self::Remove {
+                strict,
                 except,
+                force,
                 tasks: provided,
+                ..
+            } => {
+                ensure_not_too_many(&provided, force, options)?;
+                if strict {
+                    ensure_all_exist(tasks, &provided)?;
+                }
+                if except {
+                    let removed = tasks.keep_multiple(provided);
+                    save(repo, data, options)?;
+                    options.detail(format_args!("removed: {}", list(&removed)));
+                    return Ok(());
+                }
+                let removed = tasks.remove_multiple(&provided);
+                save(repo, data, options)?;
+                let missing: Vec<&String> = provided
+                    .iter()
+                    .filter(|task| !removed.contains(task))
+                    .collect();
+                let mut message = format!("removed: {}", list(&removed));
+                if !missing.is_empty() {
+                    message += &format!(" ({} didn't exist)", list(&missing));
+                }
+                options.detail(message);
+                Ok(())
+            },
+            Self::Clear => {
+                let mut removed: Vec<&str> =
+                    tasks.iter().map(|(task, _)| task).collect();
+                removed.sort_unstable();
+                options.detail(format_args!("removed: {}", list(&removed)));
+                tasks.retain(|_, _| false);
+                save(repo, data, options)
+            },
+            Self::View(view) => view.execute(data, options),
+            Self::Search {
+                query,
+                regex,
+                count_only,
+                fail_if_empty,
             } => {
-                if let Some(provided) = provided {
-                    tasks.keep_multiple(provided);
-                }
-                if let Some(excluded) = except {
-                    tasks.remove_multiple(&excluded);
-                }
-                if minutes {
-                    print!(
-                        "{}",
-                        tasks.output(|timestamp| {
-                            let days = timestamp.num_days();
-                            let total_hours = timestamp.num_hours();
-                            let total_minutes = timestamp.num_minutes();
-                            let hours = total_hours - (days * HOURS_IN_DAY);
-                            let minutes =
-                                total_minutes - (total_hours * MINUTES_IN_HOUR);
-                            format!("{days}d {hours}h {minutes}m")
-                        })
-                    )
+                if regex {
+                    let regex = Regex::new(&query).map_err(|error| {
+                        Error::InvalidRegex(error.to_string())
+                    })?;
+                    tasks.retain(|task, _| regex.is_match(task));
+                } else {
+                    tasks.keep_matching(&query);
+                }
+                let empty = tasks.is_empty();
+                if count_only {
+                    println!("{}", tasks.iter().count());
                 } else {
-                    print!("{}", tasks.output_days());
+                    let format = |task: &Task, elapsed| {
+                        options.unit.format(
+                            task,
+                            elapsed,
+                            options.color,
+                            options.red_after,
+                        )
+                    };
+                    print!("{}", data.tasks.output_records(format));
                 }
+                fail_if(fail_if_empty && empty)
+            },
+            Self::Last { unit } => {
+                print_single(
+                    data.tasks.ages().into_iter().next(),
+                    &unit,
+                    options,
+                );
+                Ok(())
+            },
+            Self::Next { unit } => {
+                // Of the tasks done equally long ago, the first one by name.
+                let oldest =
+                    data.tasks.ages().into_iter().reduce(|oldest, age| {
+                        if age.elapsed > oldest.elapsed {
+                            age
+                        } else {
+                            oldest
+                        }
+                    });
+                print_single(oldest, &unit, options);
                 Ok(())
             },
+            Self::Stats { streaks: true } => {
+                options.apply_default_interval(tasks);
+                for standing in data.tasks.standings() {
+                    let verdict = if standing.on_track() {
+                        "on-track"
+                    } else {
+                        "behind"
+                    };
+                    let percent = (standing.ratio() * 100.0).round();
+                    println!("{verdict}\t{percent}%\t{}", standing.name);
+                }
+                Ok(())
+            },
+            Self::Stats { streaks: false } => {
+                options.apply_default_interval(tasks);
+                let count = tasks.iter().count();
+                let standings = data.tasks.standings();
+                let overdue = standings
+                    .iter()
+                    .filter(|standing| !standing.on_track())
+                    .count();
+                println!("tasks: {count}");
+                println!("with an interval: {}", standings.len());
+                println!("overdue: {overdue}");
+                Ok(())
+            },
+            Self::Count {
+                older_than,
+                fail_if_empty,
+            } => {
+                if let Some(cutoff) = older_than {
+                    tasks.older_than(cutoff, now());
+                }
+                println!("{}", tasks.iter().count());
+                fail_if(fail_if_empty && tasks.is_empty())
+            },
+            Self::Tag { task, tags } => {
+                if !tasks.tag(&task, tags) {
+                    return Err(Error::TaskNotFound(vec![task]));
+                }
+                save(repo, data, options)
+            },
+            Self::Untag { task, tags } => {
+                if !tasks.untag(&task, &tags) {
+                    return Err(Error::TaskNotFound(vec![task]));
+                }
+                save(repo, data, options)
+            },
+            Self::Every { task, interval } => {
+                if !tasks.set_interval(&task, interval) {
+                    return Err(Error::TaskNotFound(vec![task]));
+                }
+                save(repo, data, options)
+            },
+            Self::Adjust { task, delta } => {
+                if !tasks.adjust(&task, delta) {
+                    return Err(Error::TaskNotFound(vec![task]));
+                }
+                save(repo, data, options)
+            },
+            Self::Snooze { task, duration } => {
+                if !tasks.snooze(&task, duration, now()) {
+                    return Err(Error::TaskNotFound(vec![task]));
+                }
+                save(repo, data, options)
+            },
+            Self::Rename {
+                old,
+                new,
+                prefix: true,
+            } => {
+                let renamed = tasks.rename_prefix(&old, &new);
+                if renamed == 0 {
+                    options.warn(format_args!("no tasks start with {old}"));
+                    return Ok(());
+                }
+                data.groups.rename_prefix(&old, &new);
+                options.detail(format_args!(
+                    "renamed {renamed} task{}",
+                    if renamed == 1 { "" } else { "s" }
+                ));
+                save(repo, data, options)
+            },
+            Self::Rename { old, new, .. } => {
+                if !tasks.rename(&old, new.clone()) {
+                    return Err(Error::TaskNotFound(vec![old]));
+                }
+                data.groups.rename_member(&old, &new);
+                save(repo, data, options)
+            },
+            Self::Swap { a, b } => {
+                if !tasks.swap(&a, &b) {
+                    let missing = tasks.missing([a.as_str(), b.as_str()]);
+                    return Err(Error::TaskNotFound(
+                        missing.into_iter().map(str::to_owned).collect(),
+                    ));
+                }
+                data.groups.swap_members(&a, &b);
+                save(repo, data, options)
+            },
+            Self::Import {
+                file: Some(file),
+                merge,
+                strategy,
+                ..
+            } => {
+                let backup = JsonRepo::new(file).load()?;
+                let merge = merge.then_some(strategy.into());
+                save(repo, import(data, backup, merge), options)
+            },
+            Self::Import {
+                names: Some(names), ..
+            } => {
+                let contents = fs::read_to_string(names)?;
+                let mut added: Vec<String> = Vec::new();
+                for task in task_names(&contents).map(normalize_name) {
+                    if !tasks.contains(&task) && !added.contains(&task) {
+                        added.push(task);
+                    }
+                }
+                let count = added.len();
+                tasks.update_multiple(added);
+                save(repo, data, options)?;
+                let noun = if count == 1 { "task" } else { "tasks" };
+                options.info(format_args!("added {count} {noun}"));
+                Ok(())
+            },
+            Self::Import { .. } => {
+                unreachable!("clap requires either a file or names")
+            },
+            Self::Group { action } => action.execute(repo, data, options),
+            #[cfg(feature = "completions")]
+            Self::Completions { .. } => {
+                unreachable!("completions are printed before loading")
+            },
+            #[cfg(feature = "tui")]
+            Self::Tui => unreachable!("the tui loads the data file itself"),
+            #[cfg(feature = "schema")]
+            Self::Schema => {
+                unreachable!("the schema is printed before loading")
+            },
+            #[cfg(feature = "notify")]
+            Self::Notify => {
+                options.apply_default_interval(tasks);
+                let overdue: Vec<String> = data
+                    .tasks
+                    .ages()
+                    .into_iter()
+                    .filter(|age| age.task.is_overdue(age.elapsed))
+                    .map(|age| {
+                        let due = options.unit.format(
+                            &age.task,
+                            age.elapsed,
+                            false,
+                            None,
+                        );
+                        format!("{} — {due}", age.name)
+                    })
+                    .collect();
+                if overdue.is_empty() {
+                    return Ok(());
+                }
+                crate::notify::overdue(&overdue)
+            },
+            Self::Edit => {
+                unreachable!("the data file is edited before loading")
+            },
+            Self::Path => unreachable!("the path is printed before loading"),
+            Self::Verify => {
+                unreachable!("verifying loads the data file itself")
+            },
+            Self::Doctor => {
+                unreachable!("the doctor loads the data file itself")
+            },
+            Self::History { limit, task } => {
+                let mut entries = History::of(repo.path()).entries()?;
+                if entries.is_empty() {
+                    options.warn("nothing has been changed yet");
+                    return Ok(());
+                }
+                if let Some(task) = task {
+                    entries.retain(|entry| entry.args.contains(&task));
+                    if entries.is_empty() {
+                        options.warn(format_args!(
+                            "nothing has been recorded for \"{task}\" yet"
+                        ));
+                        return Ok(());
+                    }
+                }
+                let skip = entries.len().saturating_sub(limit);
+                for entry in &entries[skip..] {
+                    println!("{}", history_line(entry));
+                }
+                Ok(())
+            },
+            Self::Tidy => {
+                let report = repo.save(data)?;
+                options.info(match (report.changed, report.written) {
+                    (false, _) => "the data file is already tidy",
+                    (true, true) => "tidied the data file",
+                    (true, false) => "the data file would be tidied",
+                });
+                Ok(())
+            },
+            Self::Gc { keep } => {
+                let history = History::of(repo.path());
+                let dropped = if options.dry_run {
+                    history.entries()?.len().saturating_sub(keep)
+                } else {
+                    history.prune(keep)?
+                };
+                let noun = if dropped == 1 { "entry" } else { "entries" };
+                options.info(format_args!("dropped {dropped} history {noun}"));
+                Ok(())
+            },
+            Self::Watch { .. } => {
+                unreachable!("watching loads the data file on every refresh")
+            },
+        }
+    }
+}
+
+/// What to view, and how, shared between `view` and `watch`.
+#[derive(Clone, clap::Args)]
+pub struct ViewArgs {
+    #[command(flatten)]
+    unit:           UnitFlags,
+    /// Don't display these provided tasks.
+    #[arg(short, long, value_parser = task_name)]
+    except:         Option<Vec<String>>,
+    /// Only display tasks that have any of these tags.
+    #[arg(short, long)]
+    tag:            Option<Vec<String>>,
+    /// Only display tasks done at most this long ago, like `7d`, so that
+    /// `--after 30d` means done within the last 30 days. Combine with
+    /// `--before` for the tasks done somewhere in between.
+    #[arg(long, visible_alias = "after", value_parser = parse_duration)]
+    newer_than:     Option<Duration>,
+    /// Only display tasks last done longer ago than this, like `30d` or
+    /// `12h`, the same as with `count` and `remove`, so that `--before 7d`
+    /// means not done within the last 7 days. A plain number is in days.
+    #[arg(
+        long,
+        visible_alias = "before",
+        value_name = "DURATION",
+        value_parser = parse_duration
+    )]
+    older_than:     Option<Duration>,
+    /// Only display tasks last done on this date or after it, like
+    /// `2023-12-20` or `2023-12-20T18:30:00+02:00`. Plain dates are in
+    /// UTC. Phrases like `yesterday`, `3 days ago`, `2 weeks ago` or `last
+    /// monday` work too, and go by the local calendar day.
+    #[arg(long, value_parser = since)]
+    since:          Option<NaiveDateTime>,
+    /// Only display tasks last done on this date or before it, in the
+    /// same format as `--since`.
+    #[arg(long, value_parser = until)]
+    until:          Option<NaiveDateTime>,
+    /// Only display tasks last done today, going by the local calendar day
+    /// rather than the last 24 hours.
+    #[arg(long, conflicts_with_all = ["since", "until"])]
+    today:          bool,
+    /// Display how long before or after this task every task was done,
+    /// instead of how long ago, negative for the ones done after it. The
+    /// filters that take a duration go by it too.
+    #[arg(long, value_name = "TASK", value_parser = task_name)]
+    relative_to:    Option<String>,
+    /// Only display tasks that are overdue, which tasks without an interval
+    /// never are.
+    #[arg(long)]
+    only_overdue:   bool,
+    /// What to sort the tasks by, `recent` unless the config file's `sort`
+    /// says otherwise.
+    #[arg(long, value_enum, conflicts_with = "group_by")]
+    sort:           Option<Sort>,
+    /// Display the tasks in the opposite order, like the ones that were done
+    /// the longest ago first.
+    #[arg(short, long, overrides_with = "no_reverse")]
+    reverse:        bool,
+    /// Display the tasks in the usual order, even if the config file's
+    /// `reverse` says otherwise.
+    #[arg(long, overrides_with = "reverse")]
+    no_reverse:     bool,
+    /// Display the tasks in sections with a header each.
+    #[arg(short, long)]
+    group_by:       Option<GroupBy>,
+    /// Only display this many tasks, in every section.
+    #[arg(short, long)]
+    limit:          Option<usize>,
+    /// Display when every task was first done too, like `(since
+    /// 2023-12-20)`.
+    #[arg(long, conflicts_with = "names_only")]
+    created:        bool,
+    /// Make the task name column at least this wide, so that it doesn't
+    /// shift around as the tasks change.
+    #[arg(long, value_name = "N", conflicts_with = "names_only")]
+    width:          Option<usize>,
+    /// Shorten task names wider than this many columns, ending them with
+    /// `…`. At least 1, for the `…` to fit.
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..),
+        conflicts_with = "names_only"
+    )]
+    truncate:       Option<usize>,
+    /// Separate the thousands in how long ago tasks were done, like `1,234`.
+    #[arg(long)]
+    grouped:        bool,
+    /// How to show the days that aren't whole. Only changes how long ago
+    /// tasks were done in days, not the other units or when tasks are due.
+    #[arg(long, value_enum, default_value_t)]
+    precision:      Precision,
+    /// Only display the task names, one per line, for scripting.
+    #[arg(short, long, conflicts_with = "group_by")]
+    names_only:     bool,
+    /// Display the tasks in a format for scripts that won't change across
+    /// releases. Every line is `{timestamp}\t{days}\t{name}`, where
+    /// `timestamp` is when the task was last done, in seconds since the
+    /// unix epoch, and `days` is how many whole days ago that was.
+    #[arg(long, conflicts_with_all = ["group_by", "names_only"])]
+    porcelain:      bool,
+    /// Separate the `--porcelain` fields with this instead of a tab. Names
+    /// are the last field, so they can contain it.
+    #[arg(
+        long,
+        requires = "porcelain",
+        default_value = format::PORCELAIN_DELIMITER,
+        hide_default_value = true,
+        value_name = "STR"
+    )]
+    delimiter:      String,
+    /// Display the tasks in a machine-readable format instead.
+    #[arg(
+            short,
+            long,
+            conflicts_with_all = ["group_by", "names_only", "porcelain"]
+        )]
+    format:         Option<Format>,
+    /// Leave out the header row of `--format csv` and `markdown`, for adding
+    /// to what's already there.
+    #[arg(long, requires = "format")]
+    no_header:      bool,
+    /// Display the tasks as a tree of the parts of their names, so that
+    /// `home/kitchen/sink` is under `kitchen`, which is under `home`.
+    #[arg(
+        long,
+        conflicts_with_all = ["group_by", "names_only", "porcelain", "format"]
+    )]
+    tree:           bool,
+    /// What separates the parts of task names in `--tree`.
+    #[arg(
+        long,
+        default_value = format::TREE_SEPARATOR,
+        value_name = "STR",
+        value_parser = NonEmptyStringValueParser::new(),
+        requires = "tree"
+    )]
+    tree_separator: String,
+    /// Display every task on a line of this format instead, like
+    /// `'{name}: {days}d ({date})'`. The placeholders are `{name}`, `{days}`,
+    /// `{hours}` and `{minutes}` (each in total), `{date}`, `{iso}` and
+    /// `{epoch}`, and `{{` and `}}` are literal braces.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "group_by", "names_only", "porcelain", "format", "tree"
+        ]
+    )]
+    template:       Option<Template>,
+    /// Write what would be displayed to this file instead, replacing it.
+    #[arg(long, value_name = "PATH")]
+    out:            Option<PathBuf>,
+    /// View the tasks in these data files too, as if they were all one. Tasks
+    /// in several keep the more recently done version. Nothing is written
+    /// to any of them.
+    #[arg(long, value_name = "PATH")]
+    also:           Vec<PathBuf>,
+    /// Exit with 1 if no tasks match, for scripts. What's displayed stays the
+    /// same.
+    #[arg(long)]
+    fail_if_empty:  bool,
+    #[arg(value_parser = task_name)]
+    tasks:          Option<Vec<String>>,
+}
+
+impl ViewArgs {
+    /// The tasks to view, when they're specified. Those are all that need to
+    /// be loaded then.
+    pub fn only_tasks(&self) -> Option<&[String]> {
+        // The task to go by is needed even if it's not one of them.
+        if self.relative_to.is_some() {
+            return None;
+        }
+        self.tasks.as_deref()
+    }
+
+    /// Print the tasks, or write them to the `--out` file.
+    pub fn execute(
+        mut self,
+        mut data: Data,
+        options: &Options,
+    ) -> Result<(), Error> {
+        for path in mem::take(&mut self.also) {
+            // Unlike the data file, these were asked for, so they have to be
+            // there.
+            crate::ensure_regular_file(&path)?;
+            if !path.exists() {
+                return Err(Error::Io(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("{} doesn't exist", path.display()),
+                )));
+            }
+            let repo = JsonRepo::new(path);
+            let _lock = crate::shared_lock(repo.path())?;
+            let other = crate::load_lenient(&repo, self.only_tasks(), options)?;
+            data.tasks.merge(other.tasks, MergeStrategy::Newer);
+        }
+        options.apply_default_interval(&mut data.tasks);
+        let now = match &self.relative_to {
+            Some(task) => data
+                .tasks
+                .timestamps()
+                .find(|(name, _)| name == task)
+                .map(|(_, timestamp)| timestamp)
+                .ok_or_else(|| Error::TaskNotFound(vec![task.clone()]))?,
+            None => now(),
+        };
+        let had_tasks = !data.tasks.is_empty();
+        self.filter(&mut data.tasks, now);
+        let empty = data.tasks.is_empty();
+        let fail_if_empty = self.fail_if_empty;
+        match self.out.take() {
+            // Colors are for terminals, not for files.
+            Some(path) => fs::write(
+                path,
+                self.render(data, now, had_tasks, options, false),
+            )?,
+            None => {
+                let color = options.color;
+                print!("{}", self.render(data, now, had_tasks, options, color));
+            },
+        }
+        fail_if(fail_if_empty && empty)
+    }
+
+    /// Only keep the tasks that the options ask for, with the durations going
+    /// back from `now`.
+    fn filter(&self, tasks: &mut Tasks, now: NaiveDateTime) {
+        if let Some(provided) = &self.tasks {
+            tasks.keep_multiple(provided);
+        }
+        if let Some(excluded) = &self.except {
+            tasks.remove_multiple(excluded);
+        }
+        if let Some(tags) = &self.tag {
+            tasks.keep_tagged(tags);
+        }
+        // Bounds that contradict each other rightfully leave no tasks.
+        if let Some(cutoff) = self.newer_than {
+            tasks.newer_than(cutoff, now);
+        }
+        if let Some(cutoff) = self.older_than {
+            tasks.older_than(cutoff, now);
+        }
+        if self.since.is_some() || self.until.is_some() {
+            tasks.done_between(self.since, self.until);
+        }
+        if self.only_overdue {
+            tasks.keep_overdue(now);
+        }
+        if self.today {
+            let (start, end) = local_day(Local::now().date_naive());
+            tasks.done_between(Some(start), Some(end));
+        }
+    }
+
+    /// Display the already filtered tasks, with how long before `now` they
+    /// were done. `had_tasks` is whether there were any before filtering.
+    fn render(
+        self,
+        data: Data,
+        now: NaiveDateTime,
+        had_tasks: bool,
+        options: &Options,
+        color: bool,
+    ) -> String {
+        let Self {
+            unit,
+            except: _,
+            tag: _,
+            newer_than: _,
+            older_than: _,
+            since: _,
+            until: _,
+            today: _,
+            relative_to: _,
+            only_overdue: _,
+            sort,
+            reverse,
+            no_reverse,
+            group_by,
+            limit,
+            created,
+            width,
+            truncate,
+            grouped,
+            precision,
+            names_only,
+            porcelain,
+            delimiter,
+            format: machine_format,
+            no_header,
+            tree,
+            tree_separator,
+            template,
+            out: _,
+            also: _,
+            fail_if_empty: _,
+            tasks: provided,
+        } = self;
+        let provided_any = provided.is_some();
+        // Scripts that want json errors want json tasks too, as long as they
+        // didn't ask for some other output.
+        let other_output = group_by.is_some()
+            || names_only
+            || porcelain
+            || tree
+            || template.is_some();
+        let machine_format = machine_format
+            .or((options.json && !other_output).then_some(Format::Json));
+        let tasks = data.tasks;
+        // Machine-readable output is rightfully empty, but people would be
+        // left wondering whether anything happened.
+        let human = !(porcelain
+            || names_only
+            || machine_format.is_some()
+            || template.is_some());
+        if human && tasks.iter().next().is_none() {
+            // Without the other tasks loaded, there's no telling whether
+            // there were any.
+            options.warn(if had_tasks || provided_any {
+                "no tasks match"
+            } else {
+                "no tasks yet"
+            });
+            return String::new();
+        }
+        let unit = unit.or(options.unit);
+        let sort = sort.unwrap_or(options.sort);
+        let reverse = reverse || (options.reverse && !no_reverse);
+        let format = |task: &Task, elapsed| {
+            let display = unit.format_with(
+                task,
+                elapsed,
+                color,
+                options.red_after,
+                precision,
+            );
+            if created {
+                format!("{display} (since {})", task.created.format("%Y-%m-%d"))
+            } else {
+                display
+            }
+        };
+        let limit = limit.unwrap_or(usize::MAX);
+        let output_options = OutputOptions {
+            min_width: width.unwrap_or_default(),
+            max_width: truncate,
+            group_digits: grouped,
+            ..Default::default()
+        };
+        if let Some(group_by) = group_by {
+            let mut output = match group_by {
+                GroupBy::Tag => tasks.output_by_tag_when(now, format),
+                GroupBy::Age => {
+                    tasks.output_by_age_when(now, &options.age_buckets, format)
+                },
+            };
+            if reverse {
+                output.reverse_sections();
+            }
+            output.truncate_sections(limit);
+            return output.render(&output_options);
+        }
+        let mut ages = tasks.ages_when(now);
+        match sort {
+            Sort::Recent => {},
+            Sort::Stale => ages.sort_by(TaskAge::cmp_staleness),
+            Sort::Name => ages.sort_by(TaskAge::cmp_name),
+            Sort::Overdue => ages.sort_by(TaskAge::cmp_urgency),
+        }
+        if reverse {
+            ages.reverse();
+        }
+        ages.truncate(limit);
+        if let Some(machine_format) = machine_format {
+            return machine_format.render(&ages, !no_header, unit);
+        }
+        if let Some(template) = template {
+            return ages
+                .iter()
+                .map(|age| template.render(age) + "\n")
+                .collect();
+        }
+        if porcelain {
+            return ages
+                .iter()
+                .map(|age| format::porcelain(age, &delimiter) + "\n")
+                .collect();
+        }
+        if names_only {
+            return ages.iter().map(|age| format!("{}\n", age.name)).collect();
+        }
+        let output: Vec<(String, String)> = ages
+            .into_iter()
+            .map(|age| {
+                let display = format(&age.task, age.elapsed);
+                (age.name, display)
+            })
+            .collect();
+        if tree {
+            return format::tree(
+                &output,
+                &tree_separator,
+                &output_options.separator,
+            );
+        }
+        OutputTasks::from(output).render(&output_options)
+    }
+}
+
+/// Flags for the unit to display how long ago tasks were done in.
+#[derive(Clone, clap::Args)]
+pub struct UnitFlags {
+    /// Show hours and minutes too, in this format: `{days}d {hours}h
+    /// {minutes}m`
+    #[arg(short, long)]
+    minutes: bool,
+    /// Show hours, minutes and seconds too, in this format: `{days}d
+    /// {hours}h {minutes}m {seconds}s`. Takes priority over `--minutes`.
+    #[arg(short, long)]
+    seconds: bool,
+    /// Show whole weeks instead of days.
+    #[arg(short, long, conflicts_with_all = ["minutes", "seconds"])]
+    weeks:   bool,
+    /// Show whole days, even if the config file says otherwise.
+    #[arg(
+        short,
+        long,
+        conflicts_with_all = ["minutes", "seconds", "weeks"]
+    )]
+    days:    bool,
+}
+
+impl UnitFlags {
+    /// The unit that the flags pick, or `default` if none of them are passed.
+    fn or(&self, default: Unit) -> Unit {
+        if self.seconds {
+            Unit::Seconds
+        } else if self.minutes {
+            Unit::Minutes
+        } else if self.weeks {
+            Unit::Weeks
+        } else if self.days {
+            Unit::Days
+        } else {
+            default
+        }
+    }
+}
+
+/// Which version of a task wins when `import --merge` finds it in both the
+/// data file and the backup, see [`MergeStrategy`].
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum Strategy {
+    /// The one that was done more recently.
+    #[default]
+    Newer,
+    /// The one in the data file.
+    PreferSelf,
+    /// The one in the backup.
+    PreferOther,
+}
+
+impl From<Strategy> for MergeStrategy {
+    fn from(value: Strategy) -> Self {
+        match value {
+            Strategy::Newer => Self::Newer,
+            Strategy::PreferSelf => Self::PreferSelf,
+            Strategy::PreferOther => Self::PreferOther,
+        }
+    }
+}
+
+/// The unit that `view` displays how long ago tasks were done in.
+#[derive(Clone, Copy, Default, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Unit {
+    #[default]
+    Days,
+    Weeks,
+    Minutes,
+    Seconds,
+}
+
+/// How `view` shows the days that aren't whole.
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum Precision {
+    /// Whole days, rounded down, so that 1.6 days is `1`.
+    #[default]
+    Truncate,
+    /// Whole days, rounded to the nearest one, so that 1.6 days is `2`.
+    Round,
+    /// Days with one decimal place, like `1.6`.
+    Tenths,
+}
+
+impl Unit {
+    /// Tasks with an interval show when they're due instead, and tasks that
+    /// were never done show just that. With `color`, overdue tasks are red,
+    /// and so are the ones done longer ago than `red_after`.
+    pub fn format(
+        self,
+        task: &Task,
+        elapsed: Duration,
+        color: bool,
+        red_after: Option<Duration>,
+    ) -> String {
+        self.format_with(task, elapsed, color, red_after, Precision::Truncate)
+    }
+
+    /// Same as [`Unit::format`], with how long ago tasks were done in days
+    /// shown with this `precision`.
+    pub fn format_with(
+        self,
+        task: &Task,
+        elapsed: Duration,
+        color: bool,
+        red_after: Option<Duration>,
+        precision: Precision,
+    ) -> String {
+        if task.timestamp == NEVER {
+            return String::from("never");
+        }
+        let display = task.due_or_ago(
+            elapsed,
+            |elapsed| match (self, precision) {
+                (Self::Days, Precision::Round) => days_rounded(elapsed),
+                (Self::Days, Precision::Tenths) => days_tenths(elapsed),
+                _ => self.ago_format()(elapsed),
+            },
+            self.due_format(),
+        );
+        let red = task.is_overdue(elapsed)
+            || red_after.is_some_and(|red_after| elapsed > red_after);
+        if color && red {
+            paint_overdue(&display)
+        } else {
+            display
+        }
+    }
+
+    fn ago_format(self) -> fn(Duration) -> String {
+        match self {
+            Self::Days => days,
+            Self::Weeks => weeks,
+            Self::Minutes => days_hours_minutes,
+            Self::Seconds => days_hours_minutes_seconds,
+        }
+    }
+
+    /// Bare days read fine as an "ago" column, but not after "due in", so
+    /// those get their unit. Being due in 0 weeks isn't very telling, so weeks
+    /// are shown as days too.
+    fn due_format(self) -> fn(Duration) -> String {
+        match self {
+            Self::Days | Self::Weeks => days_with_unit,
+            Self::Minutes | Self::Seconds => self.ago_format(),
+        }
+    }
+}
+
+/// Without the `color` feature, overdue tasks look the same as the rest.
+#[cfg(not(feature = "color"))]
+fn paint_overdue(display: &str) -> String {
+    display.to_owned()
+}
+
+/// Print a single task the way `view` would, or warn that there are no tasks
+/// to pick from.
+fn print_single(age: Option<TaskAge>, unit: &UnitFlags, options: &Options) {
+    let Some(age) = age else {
+        options.warn("no tasks yet");
+        return;
+    };
+    let unit = unit.or(options.unit);
+    let display =
+        unit.format(&age.task, age.elapsed, options.color, options.red_after);
+    println!("{} — {display}", age.name);
+}
+
+/// `2023-12-20 18:30:00  do dust mop`, with the time in UTC.
+fn history_line(entry: &Entry) -> String {
+    let at = match parse_timestamp(&entry.at) {
+        Ok(at) => at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        Err(_) => entry.at.clone(),
+    };
+    let mut line = format!("{at}  {}", entry.command);
+    for arg in &entry.args {
+        line += " ";
+        line += arg;
+    }
+    if let Some(note) = &entry.note {
+        line += " — ";
+        line += note;
+    }
+    line
+}
+
+/// Task names are stored in Unicode normalization form C, so that the same
+/// visible name is always the same task, however it was typed. See
+/// [`normalize_name`].
+fn task_name(input: &str) -> Result<String, Infallible> {
+    Ok(normalize_name(input))
+}
+
+/// Fail with every one of the `provided` tasks that doesn't exist, if any
+/// don't.
+fn ensure_all_exist(tasks: &Tasks, provided: &[String]) -> Result<(), Error> {
+    let missing = tasks.missing(provided.iter().map(String::as_str));
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::TaskNotFound(
+            missing.into_iter().map(str::to_owned).collect(),
+        ))
+    }
+}
+
+/// Give every one of the `provided` tasks the interval `do --every` set, if it
+/// did.
+fn set_intervals(
+    tasks: &mut Tasks,
+    provided: &[String],
+    every: Option<Duration>,
+) {
+    if let Some(interval) = every {
+        for task in provided {
+            tasks.set_interval(task, Some(interval));
+        }
+    }
+}
+
+/// The existing tasks that have any of the `tags` and match the `glob`, sorted
+/// by name.
+fn select(
+    tasks: &Tasks,
+    tags: Option<&[String]>,
+    glob: Option<&Regex>,
+) -> Vec<String> {
+    let mut selected: Vec<String> = tasks
+        .iter()
+        .filter(|(name, task)| {
+            let tagged = tags.is_none_or(|tags| {
+                tags.iter().any(|tag| task.tags.contains(tag))
+            });
+            tagged && glob.is_none_or(|glob| glob.is_match(name))
+        })
+        .map(|(name, _)| name.to_owned())
+        .collect();
+    selected.sort_unstable();
+    selected
+}
+
+/// A shell-like glob, `*` matching anything and `?` any single character, as a
+/// regular expression matching whole task names.
+fn glob_regex(glob: &str) -> Regex {
+    let mut pattern = String::from("^");
+    for character in glob.chars() {
+        match character {
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            character => pattern
+                .push_str(&regex::escape(character.encode_utf8(&mut [0; 4]))),
+        }
+    }
+    pattern.push('$');
+    Regex::new(&pattern).expect("escaped globs are valid regular expressions")
+}
+
+/// The start of a date for `--since`, or of the local day a phrase like
+/// `yesterday` means.
+fn since(input: &str) -> Result<NaiveDateTime, Error> {
+    parse_date(input).or_else(|error| {
+        relative_day(input).map(|day| local_day(day).0).ok_or(error)
+    })
+}
+
+/// The end of a date for `--until`, or of the local day a phrase like
+/// `yesterday` means.
+fn until(input: &str) -> Result<NaiveDateTime, Error> {
+    parse_date_end(input).or_else(|error| {
+        relative_day(input).map(|day| local_day(day).1).ok_or(error)
+    })
+}
+
+fn relative_day(input: &str) -> Option<NaiveDate> {
+    parse_relative_day(input, Local::now().date_naive())
+}
+
+/// When the local calendar day `date` starts and ends, in the UTC that
+/// timestamps are stored in. Around daylight saving time changes, a day can
+/// be shorter or longer than 24 hours.
+fn local_day(date: NaiveDate) -> (NaiveDateTime, NaiveDateTime) {
+    let start_of = |date: NaiveDate| {
+        let midnight = date.and_time(NaiveTime::MIN);
+        // Midnight can happen twice around a daylight saving time change, or
+        // be skipped, when the day starts once the clocks jump forward.
+        match midnight.and_local_timezone(Local).earliest() {
+            Some(midnight) => midnight.naive_utc(),
+            None => {
+                let offset = Local.offset_from_utc_datetime(&midnight).fix();
+                midnight - Duration::seconds(offset.local_minus_utc().into())
+            },
         }
+    };
+    let tomorrow = date.succ_opt().unwrap_or(NaiveDate::MAX);
+    (
+        start_of(date),
+        start_of(tomorrow) - Duration::nanoseconds(1),
+    )
+}
+
+/// Fail with [`Error::Empty`] if nothing matched, and `--fail-if-empty` asks
+/// to fail then.
+fn fail_if(empty: bool) -> Result<(), Error> {
+    if empty {
+        Err(Error::Empty)
+    } else {
+        Ok(())
     }
 }
 
-fn save(tasks: Tasks, path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
-    let map: HashMap<String, String> = tasks.into();
-    let json = serde_json::to_string_pretty(&map)?;
-    let mut data_file =
-        OpenOptions::new().write(true).truncate(true).open(path)?;
-    data_file.write_all(json.as_bytes())?;
+/// Save the data, saying how much was written to where, if verbose. A dry
+/// run already says that nothing was.
+fn save(repo: &JsonRepo, data: Data, options: &Options) -> Result<(), Error> {
+    let report = repo.save(data)?;
+    if report.written {
+        options.detail(report);
+    }
     Ok(())
 }
+
+/// Shell globs that match more files than expected make for a lot of junk
+/// tasks, so taking more than [`Options::max_tasks`] has to be forced.
+fn ensure_not_too_many(
+    provided: &[String],
+    force: bool,
+    options: &Options,
+) -> Result<(), Error> {
+    if force || provided.len() <= options.max_tasks {
+        return Ok(());
+    }
+    Err(Error::TooManyTasks {
+        count: provided.len(),
+        max:   options.max_tasks,
+    })
+}
+
+/// Task names for a message, or "none" without any.
+fn list(tasks: &[impl AsRef<str>]) -> String {
+    if tasks.is_empty() {
+        return String::from("none");
+    }
+    let tasks: Vec<&str> = tasks.iter().map(AsRef::as_ref).collect();
+    tasks.join(", ")
+}
+
+/// The data after importing a `backup` into it, replacing it unless merging.
+fn import(mut data: Data, backup: Data, merge: Option<MergeStrategy>) -> Data {
+    let Some(strategy) = merge else {
+        return backup;
+    };
+    data.tasks.merge(backup.tasks, strategy);
+    data.groups.merge(backup.groups);
+    data
+}
+
+/// The task names in a plain text file of them, one per line, without blank
+/// lines and `#` comments. Every task is only returned once.
+fn task_names(contents: &str) -> impl Iterator<Item = &str> {
+    let mut seen = Vec::new();
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter(move |line| {
+            if seen.contains(line) {
+                return false;
+            }
+            seen.push(*line);
+            true
+        })
+}
+
+impl GroupAction {
+    fn execute(
+        self,
+        repo: &JsonRepo,
+        mut data: Data,
+        options: &Options,
+    ) -> Result<(), Error> {
+        match self {
+            Self::Add { group, tasks } => {
+                data.groups.add(group, tasks);
+                save(repo, data, options)
+            },
+            Self::Remove { group, tasks } => {
+                data.groups.remove(&group, &tasks);
+                save(repo, data, options)
+            },
+            Self::List => {
+                let groups: Vec<(String, String)> = data
+                    .groups
+                    .iter()
+                    .map(|(group, members)| {
+                        let members: Vec<&str> =
+                            members.iter().map(String::as_str).collect();
+                        (group.to_owned(), members.join(", "))
+                    })
+                    .collect();
+                print!("{}", OutputTasks::from(groups));
+                Ok(())
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod import {
+    use std::collections::HashMap;
+
+    use chrono::NaiveDateTime;
+    use loago::Groups;
+    use loago::MergeStrategy;
+    use loago::Tasks;
+
+    use super::import;
+    use super::task_names;
+    use crate::repo::Data;
+
+    fn data(tasks: &[(&str, &str)]) -> Data {
+        let tasks: HashMap<String, NaiveDateTime> = tasks
+            .iter()
+            .map(|(key, timestamp)| {
+                (key.to_string(), timestamp.parse().unwrap())
+            })
+            .collect();
+        Data {
+            tasks:  Tasks::from(tasks),
+            groups: Groups::default(),
+        }
+    }
+
+    fn timestamps(data: Data) -> HashMap<String, String> {
+        data.tasks.into()
+    }
+
+    #[test]
+    fn replace() {
+        let current = data(&[("dust", "2023-12-20T00:00:00")]);
+        let backup = data(&[("vacuum", "2023-12-01T00:00:00")]);
+        let imported = timestamps(import(current, backup, None));
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported["vacuum"], "2023-12-01T00:00:00");
+    }
+
+    #[test]
+    fn merge() {
+        let current = data(&[
+            ("dust", "2023-12-20T00:00:00"),
+            ("vacuum", "2023-12-01T00:00:00"),
+        ]);
+        let backup = data(&[
+            ("dust", "2023-12-10T00:00:00"),
+            ("vacuum", "2023-12-15T00:00:00"),
+            ("mop", "2023-11-01T00:00:00"),
+        ]);
+        let imported = timestamps(import(
+            current.clone(),
+            backup.clone(),
+            Some(MergeStrategy::Newer),
+        ));
+        assert_eq!(imported.len(), 3);
+        assert_eq!(imported["dust"], "2023-12-20T00:00:00");
+        assert_eq!(imported["vacuum"], "2023-12-15T00:00:00");
+        assert_eq!(imported["mop"], "2023-11-01T00:00:00");
+        let imported = timestamps(import(
+            current,
+            backup,
+            Some(MergeStrategy::PreferOther),
+        ));
+        assert_eq!(imported["dust"], "2023-12-10T00:00:00");
+        assert_eq!(imported["vacuum"], "2023-12-15T00:00:00");
+    }
+
+    #[test]
+    fn task_names_skip_comments() {
+        let contents = "dust\n\n  # weekly\n  vacuum  \n#mop\ndust\n";
+        let names: Vec<&str> = task_names(contents).collect();
+        assert_eq!(names, ["dust", "vacuum"]);
+    }
+}
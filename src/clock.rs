@@ -0,0 +1,42 @@
+use chrono::NaiveDateTime;
+
+/// Where the "now" that tasks get updated to comes from.
+///
+/// The methods of [`crate::Tasks`] that update tasks to the current time,
+/// [`crate::Tasks::update`], [`crate::Tasks::update_multiple`] and
+/// [`crate::Tasks::update_all`], have a `_with` variant taking a [`Clock`], so
+/// that they can be tested at a fixed instant with [`FixedClock`]. The ones
+/// that only compare against the current time, like [`crate::Tasks::output`]
+/// and [`crate::Tasks::ages`], have a `_when` variant taking that "now"
+/// instead.
+pub trait Clock {
+    fn now(&self) -> NaiveDateTime;
+}
+
+/// The real clock, see [`crate::now`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> NaiveDateTime {
+        crate::now()
+    }
+}
+
+/// A clock that's stopped at the timestamp it holds.
+///
+/// ```
+/// use chrono::NaiveDateTime;
+/// use loago::Clock;
+/// use loago::FixedClock;
+/// let clock = FixedClock(NaiveDateTime::UNIX_EPOCH);
+/// assert_eq!(clock.now(), NaiveDateTime::UNIX_EPOCH);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedClock(pub NaiveDateTime);
+
+impl Clock for FixedClock {
+    fn now(&self) -> NaiveDateTime {
+        self.0
+    }
+}
@@ -0,0 +1,123 @@
+//! Deciding whether to color the output, and coloring it.
+
+use std::env;
+use std::io;
+use std::io::IsTerminal;
+
+use clap::ValueEnum;
+use serde::Deserialize;
+
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Clone, Copy, Default, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorChoice {
+    /// Color when printing to a terminal, unless the environment says
+    /// otherwise.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether to color the standard output.
+    pub fn enabled(self) -> bool {
+        let var =
+            |name: &str| env::var(name).ok().filter(|value| !value.is_empty());
+        self.resolve(var, io::stdout().is_terminal())
+    }
+
+    /// The flag takes precedence over the environment, which takes precedence
+    /// over whether the output is a terminal:
+    /// 1. `NO_COLOR`, set to anything, disables color
+    /// 2. `CLICOLOR_FORCE`, set to anything but `0`, enables it even when the
+    ///    output isn't a terminal
+    /// 3. `CLICOLOR=0` disables it
+    ///
+    /// `var` gets an environment variable, if it's set and isn't empty.
+    fn resolve(
+        self,
+        var: impl Fn(&str) -> Option<String>,
+        is_terminal: bool,
+    ) -> bool {
+        match self {
+            Self::Always => return true,
+            Self::Never => return false,
+            Self::Auto => {},
+        }
+        if var("NO_COLOR").is_some() {
+            return false;
+        }
+        if var("CLICOLOR_FORCE").is_some_and(|value| value != "0") {
+            return true;
+        }
+        if var("CLICOLOR").is_some_and(|value| value == "0") {
+            return false;
+        }
+        is_terminal
+    }
+}
+
+/// Color text red, for when something needs attention.
+pub fn red(text: &str) -> String {
+    format!("{RED}{text}{RESET}")
+}
+
+#[cfg(test)]
+mod resolution {
+    use std::collections::HashMap;
+
+    use super::ColorChoice;
+
+    fn resolve(
+        choice: ColorChoice,
+        vars: &[(&str, &str)],
+        is_terminal: bool,
+    ) -> bool {
+        let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+        choice.resolve(
+            |name| vars.get(name).map(|value| value.to_string()),
+            is_terminal,
+        )
+    }
+
+    #[test]
+    fn terminal_by_default() {
+        assert!(resolve(ColorChoice::Auto, &[], true));
+        assert!(!resolve(ColorChoice::Auto, &[], false));
+    }
+
+    #[test]
+    fn environment() {
+        assert!(!resolve(ColorChoice::Auto, &[("NO_COLOR", "1")], true));
+        assert!(!resolve(ColorChoice::Auto, &[("CLICOLOR", "0")], true));
+        assert!(resolve(ColorChoice::Auto, &[("CLICOLOR", "1")], true));
+        assert!(resolve(
+            ColorChoice::Auto,
+            &[("CLICOLOR_FORCE", "1")],
+            false
+        ));
+        assert!(!resolve(
+            ColorChoice::Auto,
+            &[("CLICOLOR_FORCE", "0")],
+            false
+        ));
+        assert!(!resolve(
+            ColorChoice::Auto,
+            &[("NO_COLOR", "1"), ("CLICOLOR_FORCE", "1")],
+            true
+        ));
+    }
+
+    #[test]
+    fn flag_takes_precedence() {
+        assert!(resolve(ColorChoice::Always, &[("NO_COLOR", "1")], false));
+        assert!(!resolve(
+            ColorChoice::Never,
+            &[("CLICOLOR_FORCE", "1")],
+            true
+        ));
+    }
+}
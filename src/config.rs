@@ -0,0 +1,310 @@
+//! The optional config file, with defaults for the command line flags.
+
+use std::collections::BTreeMap;
+#[cfg(feature = "toml")]
+use std::env;
+#[cfg(feature = "toml")]
+use std::fs;
+#[cfg(feature = "toml")]
+use std::io;
+#[cfg(feature = "toml")]
+use std::path::PathBuf;
+
+use chrono::Duration;
+use clap::ValueEnum;
+use loago::errors::Error;
+use loago::parse_duration;
+use serde::Deserialize;
+
+#[cfg(feature = "toml")]
+use crate::app_name;
+use crate::args::Sort;
+use crate::args::Unit;
+#[cfg(feature = "color")]
+use crate::color::ColorChoice;
+
+#[cfg(feature = "toml")]
+const CONFIG_FILE_NAME: &str = "config.toml";
+/// Overrides where the config file is.
+#[cfg(feature = "toml")]
+const CONFIG_ENV_VAR: &str = "LOAGO_CONFIG";
+/// Overrides the `unit` in the config file.
+pub const UNITS_ENV_VAR: &str = "LOAGO_UNITS";
+/// Every key the config file can have, to warn about the rest.
+#[cfg(feature = "toml")]
+const KNOWN_KEYS: [&str; 8] = [
+    "unit",
+    "sort",
+    "reverse",
+    "color",
+    "red_after",
+    "max_tasks",
+    "default_interval",
+    "age_buckets",
+];
+/// How many tasks `do` and `remove` take at once without `--force`, when the
+/// config file doesn't say.
+pub const DEFAULT_MAX_TASKS: usize = 50;
+/// The sections of `view --group-by age` when the config file doesn't have
+/// any, with how many days ago the tasks in each were done in at most.
+const DEFAULT_AGE_BUCKETS: [(&str, i64); 3] =
+    [("today", 1), ("this week", 7), ("this month", 30)];
+
+/// Defaults for the command line flags. Flags that are passed always win.
+///
+/// ```toml
+/// unit = "minutes"  # days, weeks, minutes or seconds
+/// sort = "stale"    # recent, stale, name or overdue
+/// reverse = true    # like view --reverse
+/// color = "never"   # auto, always or never
+/// red_after = "30d" # show tasks done longer ago than this red, like overdue
+/// max_tasks = 100   # how many tasks do and remove take without --force
+/// default_interval = "7d"  # for the tasks without their own interval
+///
+/// # The sections of view --group-by age, replacing the default ones
+/// [age_buckets]
+/// today = "1d"
+/// "this week" = "7d"
+/// "this month" = "30d"
+/// ```
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub unit:             Option<Unit>,
+    pub sort:             Option<Sort>,
+    pub reverse:          Option<bool>,
+    #[cfg(feature = "color")]
+    pub color:            Option<ColorChoice>,
+    pub red_after:        Option<String>,
+    pub max_tasks:        Option<usize>,
+    pub default_interval: Option<String>,
+    pub age_buckets:      Option<BTreeMap<String, String>>,
+}
+
+impl Config {
+    /// Read the config file from `$LOAGO_CONFIG`, or `loago/config.toml` in
+    /// the platform's config directory. Without a config file, nothing is
+    /// changed from the defaults.
+    ///
+    /// Also returns the keys that the config file has, but that aren't known,
+    /// to warn about.
+    #[cfg(feature = "toml")]
+    pub fn load() -> Result<(Self, Vec<String>), Error> {
+        let explicit = env::var_os(CONFIG_ENV_VAR).map(PathBuf::from);
+        let Some(path) = explicit.clone().or_else(default_path) else {
+            return Ok((Self::default(), Vec::new()));
+        };
+        match fs::read_to_string(&path) {
+            Ok(contents) => Self::parse(&contents),
+            // A config file that was asked for explicitly has to be there.
+            Err(error)
+                if error.kind() == io::ErrorKind::NotFound
+                    && explicit.is_none() =>
+            {
+                Ok((Self::default(), Vec::new()))
+            },
+            Err(error) => Err(Error::Config(format!(
+                "can't read {}: {error}",
+                path.display()
+            ))),
+        }
+    }
+
+    /// Without the `toml` feature, there's no config file to read.
+    #[cfg(not(feature = "toml"))]
+    pub fn load() -> Result<(Self, Vec<String>), Error> {
+        Ok((Self::default(), Vec::new()))
+    }
+
+    /// The unit to display in when no flag picks one: `$LOAGO_UNITS` with
+    /// its value passed in `env_value`, then the config file, then days.
+    ///
+    /// An invalid `$LOAGO_UNITS` is ignored, and returned to warn about.
+    pub fn unit(&self, env_value: Option<&str>) -> (Unit, Option<String>) {
+        let fallback = self.unit.unwrap_or_default();
+        match env_value {
+            None | Some("") => (fallback, None),
+            Some(value) => match Unit::from_str(value, true) {
+                Ok(unit) => (unit, None),
+                Err(_) => (fallback, Some(value.to_owned())),
+            },
+        }
+    }
+
+    /// The `default_interval`, parsed like `--default-interval`.
+    pub fn default_interval(&self) -> Result<Option<Duration>, Error> {
+        duration("default_interval", self.default_interval.as_deref())
+    }
+
+    /// The `red_after`, parsed like `--default-interval`.
+    pub fn red_after(&self) -> Result<Option<Duration>, Error> {
+        duration("red_after", self.red_after.as_deref())
+    }
+
+    /// The `age_buckets`, from the shortest duration to the longest, or the
+    /// default ones without any.
+    pub fn age_buckets(&self) -> Result<Vec<(String, Duration)>, Error> {
+        let Some(buckets) = &self.age_buckets else {
+            return Ok(DEFAULT_AGE_BUCKETS
+                .iter()
+                .map(|(header, days)| {
+                    (String::from(*header), Duration::days(*days))
+                })
+                .collect());
+        };
+        let mut buckets = buckets
+            .iter()
+            .map(|(header, value)| {
+                let up_to = parse_duration(value).map_err(|_| {
+                    Error::Config(format!(
+                        "invalid age bucket \"{header}\" = \"{value}\", \
+                         expected a duration like \"7d\""
+                    ))
+                })?;
+                Ok((header.clone(), up_to))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        buckets.sort_by_key(|(_, up_to)| *up_to);
+        Ok(buckets)
+    }
+
+    #[cfg(feature = "toml")]
+    fn parse(contents: &str) -> Result<(Self, Vec<String>), Error> {
+        let invalid = |error: toml::de::Error| {
+            Error::Config(error.to_string().trim_end().to_owned())
+        };
+        let table: toml::Table = toml::from_str(contents).map_err(invalid)?;
+        let unknown = table
+            .keys()
+            .filter(|key| !KNOWN_KEYS.contains(&key.as_str()))
+            .cloned()
+            .collect();
+        let config = table.try_into().map_err(invalid)?;
+        Ok((config, unknown))
+    }
+}
+
+/// The duration that the config file's `key` is `value`, if it has one.
+fn duration(key: &str, value: Option<&str>) -> Result<Option<Duration>, Error> {
+    value
+        .map(|value| {
+            parse_duration(value).map_err(|_| {
+                Error::Config(format!(
+                    "invalid {key} \"{value}\", expected a duration like \
+                     \"7d\""
+                ))
+            })
+        })
+        .transpose()
+}
+
+#[cfg(feature = "toml")]
+fn default_path() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join(app_name()).join(CONFIG_FILE_NAME))
+}
+
+#[cfg(all(test, feature = "toml", feature = "color"))]
+mod parsing {
+    use chrono::Duration;
+
+    use super::Config;
+    use crate::args::Sort;
+    use crate::args::Unit;
+    use crate::color::ColorChoice;
+
+    #[test]
+    fn known_keys() {
+        let (config, unknown) =
+            Config::parse("unit = \"minutes\"\ncolor = \"never\"").unwrap();
+        assert!(matches!(config.unit, Some(Unit::Minutes)));
+        assert!(matches!(config.color, Some(ColorChoice::Never)));
+        assert!(unknown.is_empty());
+    }
+
+    #[test]
+    fn max_tasks() {
+        let (config, unknown) = Config::parse("max_tasks = 100").unwrap();
+        assert_eq!(config.max_tasks, Some(100));
+        assert!(unknown.is_empty());
+        assert!(Config::parse("max_tasks = -1").is_err());
+    }
+
+    #[test]
+    fn default_interval() {
+        let (config, unknown) =
+            Config::parse("default_interval = \"7d\"").unwrap();
+        assert_eq!(config.default_interval().unwrap(), Some(Duration::days(7)));
+        assert!(unknown.is_empty());
+        assert_eq!(Config::default().default_interval().unwrap(), None);
+        let (config, _) =
+            Config::parse("default_interval = \"weekly\"").unwrap();
+        assert!(config.default_interval().is_err());
+    }
+
+    #[test]
+    fn view_defaults() {
+        let (config, unknown) = Config::parse(
+            "sort = \"age\"\nreverse = true\nred_after = \"30d\"",
+        )
+        .unwrap();
+        assert!(matches!(config.sort, Some(Sort::Recent)));
+        assert_eq!(config.reverse, Some(true));
+        assert_eq!(config.red_after().unwrap(), Some(Duration::days(30)));
+        assert!(unknown.is_empty());
+        assert!(Config::parse("sort = \"oldest\"").is_err());
+        let (config, _) = Config::parse("red_after = \"soon\"").unwrap();
+        assert!(config.red_after().is_err());
+    }
+
+    #[test]
+    fn age_buckets() {
+        let days =
+            |header: &str, days| (header.to_owned(), Duration::days(days));
+        assert_eq!(Config::default().age_buckets().unwrap(), [
+            days("today", 1),
+            days("this week", 7),
+            days("this month", 30)
+        ]);
+        let (config, unknown) = Config::parse(
+            "[age_buckets]\nrecent = \"3d\"\n\"this year\" = \"365d\"\nnow = \
+             \"1h\"",
+        )
+        .unwrap();
+        assert!(unknown.is_empty());
+        assert_eq!(config.age_buckets().unwrap(), [
+            ("now".to_owned(), Duration::hours(1)),
+            days("recent", 3),
+            days("this year", 365)
+        ]);
+        let (config, _) =
+            Config::parse("[age_buckets]\nrecent = \"soon\"").unwrap();
+        assert!(config.age_buckets().is_err());
+    }
+
+    #[test]
+    fn unknown_keys_ignored() {
+        let (config, unknown) =
+            Config::parse("descending = true\nunit = \"weeks\"").unwrap();
+        assert!(matches!(config.unit, Some(Unit::Weeks)));
+        assert_eq!(unknown, ["descending"]);
+    }
+
+    #[test]
+    fn invalid_values() {
+        assert!(Config::parse("unit = \"fortnights\"").is_err());
+        assert!(Config::parse("unit = ").is_err());
+    }
+
+    #[test]
+    fn unit_from_env() {
+        let (config, _) = Config::parse("unit = \"minutes\"").unwrap();
+        assert!(matches!(config.unit(None), (Unit::Minutes, None)));
+        assert!(matches!(config.unit(Some("")), (Unit::Minutes, None)));
+        assert!(matches!(config.unit(Some("weeks")), (Unit::Weeks, None)));
+        assert!(matches!(config.unit(Some("Days")), (Unit::Days, None)));
+        let (unit, invalid) = config.unit(Some("hours"));
+        assert!(matches!(unit, Unit::Minutes));
+        assert_eq!(invalid.as_deref(), Some("hours"));
+        assert!(matches!(Config::default().unit(None), (Unit::Days, None)));
+    }
+}
@@ -0,0 +1,244 @@
+use chrono::DateTime;
+use chrono::Datelike;
+use chrono::Days;
+use chrono::NaiveDate;
+use chrono::NaiveDateTime;
+use chrono::NaiveTime;
+use chrono::SecondsFormat;
+use chrono::Weekday;
+
+use crate::errors::Error;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+/// The format timestamps are stored in by default, in UTC without saying so.
+/// The fraction of a second has as many digits as it takes to keep every
+/// nanosecond, and none if it's zero.
+pub const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+/// Format a timestamp the way it's stored, in the [`TIMESTAMP_FORMAT`].
+/// [`parse_timestamp`] reads back the exact same timestamp.
+///
+/// ```
+/// use loago::format_timestamp;
+/// use loago::parse_timestamp;
+/// let timestamp = parse_timestamp("2023-12-20T18:30:00.000000001").unwrap();
+/// assert_eq!(format_timestamp(timestamp), "2023-12-20T18:30:00.000000001");
+/// ```
+pub fn format_timestamp(timestamp: NaiveDateTime) -> String {
+    timestamp.format(TIMESTAMP_FORMAT).to_string()
+}
+
+/// Format a timestamp as RFC 3339 in UTC, like `2023-12-20T18:30:00Z`, for
+/// when the stored data should say what timezone it's in. [`parse_timestamp`]
+/// reads this back just as exactly.
+pub fn format_timestamp_rfc3339(timestamp: NaiveDateTime) -> String {
+    timestamp
+        .and_utc()
+        .to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+/// Parse a timestamp that's either in the [`TIMESTAMP_FORMAT`], or RFC 3339
+/// with any offset, which gets converted to UTC.
+///
+/// ```
+/// use loago::parse_timestamp;
+/// assert_eq!(
+///     parse_timestamp("2023-12-20T18:30:00+02:00").unwrap(),
+///     parse_timestamp("2023-12-20T16:30:00").unwrap()
+/// );
+/// ```
+///
+/// # Errors
+/// If the input is in neither format.
+pub fn parse_timestamp(
+    input: &str,
+) -> Result<NaiveDateTime, chrono::format::ParseError> {
+    NaiveDateTime::parse_from_str(input, TIMESTAMP_FORMAT).or_else(|error| {
+        DateTime::parse_from_rfc3339(input)
+            .map(|timestamp| timestamp.naive_utc())
+            .map_err(|_| error)
+    })
+}
+
+/// Parse a date like `2023-12-20`, or a full RFC 3339 timestamp like
+/// `2023-12-20T18:30:00+02:00`, into the UTC time that timestamps are stored
+/// in. A plain date is the very start of that day in UTC.
+///
+/// # Errors
+/// [`Error::InvalidDate`] if the input is in neither format.
+///
+/// ```
+/// use chrono::Duration;
+/// use loago::parse_date;
+/// let day = parse_date("2023-12-20").unwrap();
+/// let evening = parse_date("2023-12-20T18:30:00+02:00").unwrap();
+/// assert_eq!(evening - day, Duration::minutes(16 * 60 + 30));
+/// ```
+pub fn parse_date(input: &str) -> Result<NaiveDateTime, Error> {
+    parse(input, NaiveTime::MIN)
+}
+
+/// Same as [`parse_date`], except that a plain date is the very end of that
+/// day instead, for when the whole day should be included.
+pub fn parse_date_end(input: &str) -> Result<NaiveDateTime, Error> {
+    let end_of_day = NaiveTime::from_hms_nano_opt(23, 59, 59, 999_999_999)
+        .expect("the last nanosecond of a day is a valid time");
+    parse(input, end_of_day)
+}
+
+/// The day that a phrase like `yesterday`, `3 days ago`, `2 weeks ago`,
+/// `last week` or `last monday` means, counting back from `today`. `today`
+/// itself works too. Case and extra spaces don't matter.
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use loago::parse_relative_day;
+/// let today = NaiveDate::from_ymd_opt(2023, 12, 20).unwrap();
+/// let monday = NaiveDate::from_ymd_opt(2023, 12, 18).unwrap();
+/// assert_eq!(parse_relative_day("last monday", today), Some(monday));
+/// assert_eq!(parse_relative_day("2 days ago", today), Some(monday));
+/// assert_eq!(parse_relative_day("last month", today), None);
+/// ```
+pub fn parse_relative_day(input: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let input = input.to_lowercase();
+    let words: Vec<&str> = input.split_whitespace().collect();
+    let days_back = match words.as_slice() {
+        ["today"] => 0,
+        ["yesterday"] => 1,
+        ["last", "week"] => 7,
+        ["last", weekday] => {
+            let weekday: Weekday = weekday.parse().ok()?;
+            let since = (today.weekday().num_days_from_monday() + 7
+                - weekday.num_days_from_monday())
+                % 7;
+            // Not today, but the one before it.
+            if since == 0 {
+                7
+            } else {
+                since.into()
+            }
+        },
+        [amount, unit, "ago"] => {
+            let amount: u64 = amount.parse().ok()?;
+            match *unit {
+                "day" | "days" => amount,
+                "week" | "weeks" => amount.checked_mul(7)?,
+                _ => return None,
+            }
+        },
+        _ => return None,
+    };
+    today.checked_sub_days(Days::new(days_back))
+}
+
+/// Plain dates become that date at `time`.
+fn parse(input: &str, time: NaiveTime) -> Result<NaiveDateTime, Error> {
+    let input = input.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(input, DATE_FORMAT) {
+        return Ok(date.and_time(time));
+    }
+    DateTime::parse_from_rfc3339(input)
+        .map(|timestamp| timestamp.naive_utc())
+        .map_err(|_| Error::InvalidDate(input.to_owned()))
+}
+
+#[cfg(test)]
+mod formats {
+    use chrono::NaiveDate;
+    use chrono::Timelike;
+
+    use super::format_timestamp;
+    use super::format_timestamp_rfc3339;
+    use super::parse_date;
+    use super::parse_date_end;
+    use super::parse_relative_day;
+    use super::parse_timestamp;
+
+    #[test]
+    fn plain_dates() {
+        let december = NaiveDate::from_ymd_opt(2023, 12, 20).unwrap();
+        assert_eq!(
+            parse_date("2023-12-20").unwrap(),
+            december.and_hms_opt(0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            parse_date_end("2023-12-20").unwrap(),
+            december.and_hms_nano_opt(23, 59, 59, 999_999_999).unwrap()
+        );
+    }
+
+    #[test]
+    fn timestamps_are_utc() {
+        let expected = NaiveDate::from_ymd_opt(2023, 12, 20)
+            .unwrap()
+            .and_hms_opt(16, 30, 0)
+            .unwrap();
+        assert_eq!(parse_date("2023-12-20T18:30:00+02:00").unwrap(), expected);
+        assert_eq!(parse_date_end("2023-12-20T16:30:00Z").unwrap(), expected);
+    }
+
+    #[test]
+    fn invalid() {
+        for input in ["", "2023-13-01", "20.12.2023", "2023-12-20T18:30"] {
+            assert!(parse_date(input).is_err(), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn relative_days() {
+        // A wednesday.
+        let today = NaiveDate::from_ymd_opt(2023, 12, 20).unwrap();
+        let day = |day| NaiveDate::from_ymd_opt(2023, 12, day).unwrap();
+        for (input, expected) in [
+            ("today", day(20)),
+            ("Yesterday", day(19)),
+            (" 1 day  ago ", day(19)),
+            ("10 days ago", day(10)),
+            ("2 weeks ago", day(6)),
+            ("last week", day(13)),
+            ("last monday", day(18)),
+            ("last Wed", day(13)),
+            ("last thursday", day(14)),
+        ] {
+            assert_eq!(parse_relative_day(input, today), Some(expected));
+        }
+        for input in ["", "tomorrow", "last", "-1 days ago", "3 months ago"] {
+            assert_eq!(parse_relative_day(input, today), None, "{input:?}");
+        }
+        assert_eq!(parse_relative_day("2 days ago", NaiveDate::MIN), None);
+    }
+
+    #[test]
+    fn timestamps_keep_nanoseconds() {
+        let timestamp = NaiveDate::from_ymd_opt(2023, 12, 20)
+            .unwrap()
+            .and_hms_nano_opt(18, 30, 5, 123_456_789)
+            .unwrap();
+        let formatted = format_timestamp(timestamp);
+        assert_eq!(formatted, "2023-12-20T18:30:05.123456789");
+        assert_eq!(parse_timestamp(&formatted).unwrap(), timestamp);
+        let whole = timestamp.with_nanosecond(0).unwrap();
+        assert_eq!(format_timestamp(whole), "2023-12-20T18:30:05");
+        assert_eq!(parse_timestamp("2023-12-20T18:30:05").unwrap(), whole);
+        let millis = timestamp.with_nanosecond(500_000_000).unwrap();
+        assert_eq!(parse_timestamp(&format_timestamp(millis)).unwrap(), millis);
+        assert!(parse_timestamp("2023-12-20").is_err());
+    }
+
+    #[test]
+    fn rfc3339_timestamps() {
+        let timestamp = NaiveDate::from_ymd_opt(2023, 12, 20)
+            .unwrap()
+            .and_hms_nano_opt(16, 30, 5, 123_456_789)
+            .unwrap();
+        let formatted = format_timestamp_rfc3339(timestamp);
+        assert_eq!(formatted, "2023-12-20T16:30:05.123456789Z");
+        assert_eq!(parse_timestamp(&formatted).unwrap(), timestamp);
+        assert_eq!(
+            parse_timestamp("2023-12-20T18:30:05.123456789+02:00").unwrap(),
+            timestamp
+        );
+        let whole = timestamp.with_nanosecond(0).unwrap();
+        assert_eq!(format_timestamp_rfc3339(whole), "2023-12-20T16:30:05Z");
+    }
+}
@@ -0,0 +1,138 @@
+//! `loago doctor`, for telling what's wrong with where the data file is kept
+//! and what's in it, without changing either. The only file it writes is
+//! the one that [`writable`] creates and removes right away, and it doesn't
+//! create the lock file.
+
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process;
+
+use loago::errors::AsErrStr;
+use loago::errors::Error;
+
+use crate::args::Options;
+use crate::ensure_regular_file;
+use crate::lock::DataLock;
+use crate::repo::JsonRepo;
+use crate::repo::CURRENT_VERSION;
+
+/// What the data file does as long as it doesn't exist.
+const NOT_YET: &str = "doesn't exist yet, it's created on the first change";
+
+/// Print a line about every part of the setup, from where the data file is to
+/// how many tasks it has, along with what the lenient loader would warn about.
+///
+/// # Errors
+/// [`Error::Unhealthy`] if anything is clearly broken, like a data directory
+/// that can't be written to, or a data file that can't be read.
+pub fn doctor(repo: &JsonRepo, options: &Options) -> Result<(), Error> {
+    let mut problems = 0;
+    let path = repo.path();
+    let dir = path.parent().unwrap_or(Path::new("."));
+    match fs::metadata(dir) {
+        Ok(metadata) if !metadata.is_dir() => problem(
+            &mut problems,
+            "data directory",
+            &format!("{} isn't one", dir.display()),
+        ),
+        Ok(_) if !writable(dir) => problem(
+            &mut problems,
+            "data directory",
+            &format!("{} can't be written to", dir.display()),
+        ),
+        Ok(_) => println!("data directory: {}", dir.display()),
+        Err(error) if error.kind() == ErrorKind::NotFound => {
+            println!("data directory: {} ({NOT_YET})", dir.display())
+        },
+        Err(error) => problem(
+            &mut problems,
+            "data directory",
+            &format!("can't read {}: {error}", dir.display()),
+        ),
+    }
+    if let Err(error) = ensure_regular_file(path) {
+        problem(&mut problems, "data file", &error.as_str());
+        return finish(problems, options);
+    }
+    if !path.exists() {
+        println!("data file: {} ({NOT_YET})", path.display());
+        return finish(problems, options);
+    }
+    if OpenOptions::new().append(true).open(path).is_err() {
+        problem(
+            &mut problems,
+            "data file",
+            &format!("{} can't be written to", path.display()),
+        );
+    } else {
+        println!("data file: {}", path.display());
+    }
+    let _lock = DataLock::shared_if_exists(path)?;
+    match repo.version() {
+        Ok(version) if version > CURRENT_VERSION => problem(
+            &mut problems,
+            "format version",
+            &format!(
+                "{version}, newer than the supported {CURRENT_VERSION}, \
+                 update loago"
+            ),
+        ),
+        Ok(CURRENT_VERSION) => {
+            println!("format version: {CURRENT_VERSION}, the current one")
+        },
+        Ok(version) => println!(
+            "format version: {version}, upgraded to {CURRENT_VERSION} on the \
+             next change"
+        ),
+        Err(error) => {
+            problem(&mut problems, "data file", &error.as_str());
+            return finish(problems, options);
+        },
+    }
+    match repo.load_lenient(None) {
+        Ok((data, mut invalid)) => {
+            println!("tasks: {}", data.tasks.iter().count() + invalid.len());
+            for key in repo.duplicates() {
+                println!(
+                    "warning: the data file has \"{key}\" more than once, \
+                     only the last one is kept"
+                );
+            }
+            invalid.sort_unstable_by(|(left, _), (right, _)| left.cmp(right));
+            for (task, error) in invalid {
+                println!(
+                    "warning: task \"{task}\" has an invalid timestamp: \
+                     {error}"
+                );
+            }
+        },
+        Err(error) => problem(&mut problems, "tasks", &error.as_str()),
+    }
+    finish(problems, options)
+}
+
+/// Print what's wrong with a part of the setup, counting it.
+fn problem(problems: &mut usize, label: &str, message: &str) {
+    println!("{label}: problem: {message}");
+    *problems += 1;
+}
+
+/// Whether files can be created in `dir`, like the lock file, as told by
+/// creating one and removing it right away.
+fn writable(dir: &Path) -> bool {
+    let probe = dir.join(format!(".loago-doctor-{}", process::id()));
+    match OpenOptions::new().write(true).create_new(true).open(&probe) {
+        Ok(_) => fs::remove_file(probe).is_ok(),
+        Err(_) => false,
+    }
+}
+
+fn finish(problems: usize, options: &Options) -> Result<(), Error> {
+    if problems > 0 {
+        return Err(Error::Unhealthy(problems));
+    }
+    options.info("no problems found");
+    Ok(())
+}
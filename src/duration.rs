@@ -0,0 +1,218 @@
+use chrono::Duration;
+
+use crate::errors::Error;
+
+const SECONDS_IN_MINUTE: i64 = 60;
+const SECONDS_IN_HOUR: i64 = 60 * SECONDS_IN_MINUTE;
+const SECONDS_IN_DAY: i64 = 24 * SECONDS_IN_HOUR;
+/// [`Duration::seconds`] panics past this.
+const MAX_SECONDS: i64 = i64::MAX / 1_000;
+
+/// Units, from biggest to smallest, with how many seconds are in each.
+const UNITS: [(char, i64); 4] = [
+    ('d', SECONDS_IN_DAY),
+    ('h', SECONDS_IN_HOUR),
+    ('m', SECONDS_IN_MINUTE),
+    ('s', 1),
+];
+
+/// Parse a human duration: one or more whole amounts, each followed by a unit,
+/// like `7d` or `1d12h30m`.
+///
+/// The units are `d` (days), `h` (hours), `m` (minutes) and `s` (seconds).
+/// They have to go from biggest to smallest, and each one can only be used
+/// once. A plain number without any unit is in days, since that's what loago
+/// counts in by default.
+///
+/// # Errors
+/// [`Error::InvalidDuration`] if the input is not in that format, or is too
+/// large to be a [`Duration`].
+///
+/// ```
+/// use chrono::Duration;
+/// use loago::parse_duration;
+/// assert_eq!(parse_duration("12h").unwrap(), Duration::hours(12));
+/// assert_eq!(
+///     parse_duration("1d12h").unwrap(),
+///     Duration::days(1) + Duration::hours(12)
+/// );
+/// assert_eq!(parse_duration("7").unwrap(), Duration::days(7));
+/// ```
+pub fn parse_duration(input: &str) -> Result<Duration, Error> {
+    let invalid = || Error::InvalidDuration(input.to_owned());
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return Err(invalid());
+    }
+    if trimmed.bytes().all(|byte| byte.is_ascii_digit()) {
+        return seconds_in(trimmed, SECONDS_IN_DAY)
+            .map(Duration::seconds)
+            .ok_or_else(invalid);
+    }
+    let mut total: i64 = 0;
+    // Units that are still allowed to come next, which is only the ones
+    // smaller than the last one used.
+    let mut units = UNITS.iter();
+    let mut rest = trimmed;
+    while !rest.is_empty() {
+        let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+        let amount = &rest[..digits];
+        let unit = rest[digits..].chars().next().ok_or_else(invalid)?;
+        let (_, unit_seconds) =
+            units.find(|(name, _)| *name == unit).ok_or_else(invalid)?;
+        let seconds = seconds_in(amount, *unit_seconds).ok_or_else(invalid)?;
+        total = total
+            .checked_add(seconds)
+            .filter(|total| *total <= MAX_SECONDS)
+            .ok_or_else(invalid)?;
+        rest = &rest[digits + unit.len_utf8()..];
+    }
+    Ok(Duration::seconds(total))
+}
+
+/// How many seconds `amount` of a unit with `unit_seconds` is, if `amount` is
+/// a valid number and the result is small enough to be a [`Duration`].
+fn seconds_in(amount: &str, unit_seconds: i64) -> Option<i64> {
+    if amount.is_empty() || !amount.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    amount
+        .parse::<i64>()
+        .ok()
+        .and_then(|amount| amount.checked_mul(unit_seconds))
+        .filter(|seconds| *seconds <= MAX_SECONDS)
+}
+
+/// Like [`parse_duration`], but with an optional `+` or `-` in front, for
+/// durations that can go either way, like `-2h`.
+///
+/// # Errors
+/// Same as [`parse_duration`].
+///
+/// ```
+/// use chrono::Duration;
+/// use loago::parse_signed_duration;
+/// assert_eq!(parse_signed_duration("-2h").unwrap(), Duration::hours(-2));
+/// assert_eq!(parse_signed_duration("+2h").unwrap(), Duration::hours(2));
+/// ```
+pub fn parse_signed_duration(input: &str) -> Result<Duration, Error> {
+    let trimmed = input.trim();
+    let parsed = match trimmed.strip_prefix('-') {
+        Some(magnitude) => parse_duration(magnitude).map(|duration| -duration),
+        None => parse_duration(trimmed.strip_prefix('+').unwrap_or(trimmed)),
+    };
+    parsed.map_err(|_| Error::InvalidDuration(input.to_owned()))
+}
+
+/// Format a [`Duration`] in the biggest unit that fits into it evenly, so that
+/// [`parse_duration`] reads back the same duration. Sub-second precision is
+/// truncated.
+///
+/// ```
+/// use chrono::Duration;
+/// use loago::format_duration;
+/// assert_eq!(format_duration(Duration::days(7)), "7d");
+/// assert_eq!(format_duration(Duration::hours(36)), "36h");
+/// ```
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.num_seconds();
+    let (unit, unit_seconds) = UNITS
+        .iter()
+        .find(|(_, unit_seconds)| seconds % unit_seconds == 0)
+        .expect("every amount of seconds is divisible by 1");
+    format!("{}{unit}", seconds / unit_seconds)
+}
+
+#[cfg(test)]
+mod parsing {
+    use chrono::Duration;
+
+    use super::format_duration;
+    use super::parse_duration;
+    use super::parse_signed_duration;
+
+    #[test]
+    fn units() {
+        assert_eq!(parse_duration("2d").unwrap(), Duration::days(2));
+        assert_eq!(parse_duration("3h").unwrap(), Duration::hours(3));
+        assert_eq!(parse_duration("4m").unwrap(), Duration::minutes(4));
+        assert_eq!(parse_duration("5s").unwrap(), Duration::seconds(5));
+        assert_eq!(parse_duration("6").unwrap(), Duration::days(6));
+    }
+
+    #[test]
+    fn plain_numbers_are_days() {
+        assert_eq!(parse_duration("0").unwrap(), Duration::zero());
+        assert_eq!(parse_duration("30").unwrap(), Duration::days(30));
+        assert_eq!(parse_duration(" 30 ").unwrap(), Duration::days(30));
+    }
+
+    #[test]
+    fn compound() {
+        let expected =
+            Duration::days(1) + Duration::hours(12) + Duration::minutes(30);
+        assert_eq!(parse_duration("1d12h30m").unwrap(), expected);
+        assert_eq!(
+            parse_duration("2h15s").unwrap(),
+            Duration::hours(2) + Duration::seconds(15)
+        );
+        assert_eq!(
+            parse_duration("1d1h1m1s").unwrap(),
+            Duration::seconds(24 * 60 * 60 + 60 * 60 + 60 + 1)
+        );
+    }
+
+    #[test]
+    fn amounts_may_overflow_their_unit() {
+        assert_eq!(parse_duration("36h").unwrap(), Duration::hours(36));
+        assert_eq!(parse_duration("0d90m").unwrap(), Duration::minutes(90));
+    }
+
+    #[test]
+    fn invalid() {
+        for input in [
+            "", " ", "d", "-1d", "+1d", "1x", "1.5d", "day", "1D", "1d 12h",
+            "1d12", "12h1d", "1h1h", "d1", "1dd", "1d-1h", "1日",
+        ] {
+            assert!(parse_duration(input).is_err(), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn too_large() {
+        // The biggest duration is a bit over 106751991167 days and 7 hours.
+        for input in
+            ["99999999999999999999", "106751991168d", "106751991167d8h"]
+        {
+            assert!(parse_duration(input).is_err(), "{input}");
+        }
+        assert!(parse_duration("106751991167d7h").is_ok());
+        let max = format!("{}s", i64::MAX / 1_000);
+        assert!(parse_duration(&max).is_ok());
+    }
+
+    #[test]
+    fn signed() {
+        assert_eq!(
+            parse_signed_duration("-1d12h").unwrap(),
+            Duration::hours(-36)
+        );
+        assert_eq!(
+            parse_signed_duration("+90m").unwrap(),
+            Duration::minutes(90)
+        );
+        assert_eq!(parse_signed_duration("7").unwrap(), Duration::days(7));
+        assert_eq!(format_duration(Duration::hours(-2)), "-2h");
+        for input in ["", "-", "--1d", "+-1d", "-+1d", "-1x"] {
+            assert!(parse_signed_duration(input).is_err(), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn format_round_trips() {
+        for input in ["7d", "36h", "90m", "61s", "0d"] {
+            let duration = parse_duration(input).unwrap();
+            assert_eq!(format_duration(duration), input);
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! Opening the data file in the user's editor.
+
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+use loago::errors::Error;
+
+/// Used when neither `EDITOR` nor `VISUAL` is set.
+#[cfg(windows)]
+const DEFAULT_EDITOR: &str = "notepad";
+#[cfg(not(windows))]
+const DEFAULT_EDITOR: &str = "vi";
+
+/// Open `path` in the user's editor, and wait for it to close.
+///
+/// # Errors
+/// [`Error::Editor`] if the editor couldn't be started, or exited
+/// unsuccessfully.
+pub fn edit(path: &Path) -> Result<(), Error> {
+    let var = |name: &str| {
+        env::var(name).ok().filter(|value| !value.trim().is_empty())
+    };
+    let editor = command(var);
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or(DEFAULT_EDITOR);
+    let status = Command::new(program)
+        .args(words)
+        .arg(path)
+        .status()
+        .map_err(|error| {
+            Error::Editor(format!("can't run {program}: {error}"))
+        })?;
+    if !status.success() {
+        return Err(Error::Editor(format!("{program} exited with {status}")));
+    }
+    Ok(())
+}
+
+/// The editor command: `EDITOR`, then `VISUAL`, then the platform default. It
+/// can have arguments of its own, like `code --wait`.
+///
+/// `var` gets an environment variable, if it's set and isn't blank.
+fn command(var: impl Fn(&str) -> Option<String>) -> String {
+    var("EDITOR")
+        .or_else(|| var("VISUAL"))
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_owned())
+}
+
+#[cfg(test)]
+mod choice {
+    use super::command;
+    use super::DEFAULT_EDITOR;
+
+    fn command_with(vars: &[(&str, &str)]) -> String {
+        command(|name| {
+            vars.iter()
+                .find(|(var, _)| *var == name)
+                .map(|(_, value)| value.to_string())
+        })
+    }
+
+    #[test]
+    fn editor_then_visual() {
+        assert_eq!(command_with(&[("EDITOR", "helix")]), "helix");
+        assert_eq!(command_with(&[("VISUAL", "code --wait")]), "code --wait");
+        assert_eq!(
+            command_with(&[("EDITOR", "helix"), ("VISUAL", "code")]),
+            "helix"
+        );
+        assert_eq!(command_with(&[]), DEFAULT_EDITOR);
+    }
+}
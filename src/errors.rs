@@ -0,0 +1,409 @@
+//! Errors that `loago` can run into, both in the library and the binary.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+use chrono::NaiveDateTime;
+
+/// Get the user-facing message of an error.
+///
+/// Unlike [`fmt::Debug`], the message is meant to be shown to the user as is.
+pub trait AsErrStr {
+    fn as_str(&self) -> Cow<'_, str>;
+}
+
+/// The platform's local data directory, that the data file is stored in by
+/// default, couldn't be determined.
+#[derive(Debug)]
+pub struct DataDirNotFoundError;
+
+impl AsErrStr for DataDirNotFoundError {
+    fn as_str(&self) -> Cow<'_, str> {
+        Cow::Borrowed("local data directory wasn't found")
+    }
+}
+
+impl fmt::Display for DataDirNotFoundError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::error::Error for DataDirNotFoundError {
+}
+
+/// Which of a task's timestamps a [`TimestampError`] is about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimestampField {
+    /// When the task was last done, its `timestamp`.
+    Timestamp,
+    /// When the task was first added, its `created`.
+    Created,
+}
+
+/// A task's timestamp isn't in a format [`crate::parse_timestamp`] reads.
+#[derive(Debug)]
+pub struct TimestampError {
+    /// The name of the task with the timestamp.
+    pub task:  String,
+    /// Which of the task's timestamps it is.
+    pub field: TimestampField,
+    /// The timestamp as it was given.
+    pub value: String,
+    /// Why it couldn't be parsed.
+    pub error: chrono::format::ParseError,
+}
+
+impl TimestampError {
+    /// [`crate::parse_timestamp`], failing with which task's timestamp, and
+    /// which of its timestamps, it was.
+    pub fn parse(
+        task: &str,
+        field: TimestampField,
+        value: String,
+    ) -> Result<NaiveDateTime, Self> {
+        crate::parse_timestamp(&value).map_err(|error| Self {
+            task: task.to_owned(),
+            field,
+            value,
+            error,
+        })
+    }
+}
+
+impl AsErrStr for TimestampError {
+    fn as_str(&self) -> Cow<'_, str> {
+        let field = match self.field {
+            TimestampField::Timestamp => "timestamp",
+            TimestampField::Created => "\"created\" date",
+        };
+        Cow::Owned(format!(
+            "failed to parse {field} for task \"{}\": \"{}\" ({})",
+            self.task, self.value, self.error
+        ))
+    }
+}
+
+impl fmt::Display for TimestampError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::error::Error for TimestampError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Every way `loago` can fail.
+///
+/// Each variant maps to its own stable process exit code, see
+/// [`Error::exit_code`].
+#[derive(Debug)]
+pub enum Error {
+    /// Reading or writing a file failed.
+    Io(io::Error),
+    /// The data file isn't valid json, or doesn't have the expected shape.
+    Json(serde_json::Error),
+    /// A stored timestamp isn't in the expected format.
+    Timestamp(TimestampError),
+    DataDirNotFound(DataDirNotFoundError),
+    /// Another `loago` process is holding the data file lock for too long.
+    Busy,
+    /// The data file was written by a newer `loago`, in a format version this
+    /// one doesn't know how to read.
+    UnsupportedVersion {
+        found:     u64,
+        supported: u64,
+    },
+    /// A command that only works on existing tasks got some that don't exist,
+    /// every one of which is listed.
+    TaskNotFound(Vec<String>),
+    /// A duration isn't in the format [`crate::parse_duration`] expects.
+    InvalidDuration(String),
+    /// A regular expression failed to compile, with the reason why.
+    InvalidRegex(String),
+    /// The config file isn't valid toml, or has invalid values, with the
+    /// reason why.
+    Config(String),
+    /// The editor to edit the data file in couldn't be started, or failed,
+    /// with the reason why.
+    Editor(String),
+    /// A date isn't in the format [`crate::parse_date`] expects, or a phrase
+    /// that [`crate::parse_relative_day`] understands.
+    InvalidDate(String),
+    /// A desktop notification couldn't be shown, with the reason why.
+    Notification(String),
+    /// Saving would remove every task there is, without `--allow-empty`
+    /// saying that's what was meant.
+    WouldEmpty,
+    /// A command got more tasks at once than it takes without `--force`.
+    TooManyTasks {
+        count: usize,
+        max:   usize,
+    },
+    /// Verifying the data file found this many tasks with invalid timestamps.
+    InvalidTasks(usize),
+    /// Something other than a regular file is where the data file goes, like
+    /// a directory or a dangling symlink.
+    NotAFile(PathBuf),
+    /// `loago doctor` found this many things wrong with the setup, which it
+    /// already described.
+    Unhealthy(usize),
+    /// No tasks matched, with `--fail-if-empty` asking to fail then. The
+    /// binary doesn't print anything about it, failing is the whole point.
+    Empty,
+}
+
+impl Error {
+    /// The exit code the binary exits with when failing with this error.
+    ///
+    /// `0` is success and `2` is left for invalid command line usage, as
+    /// reported by `clap`.
+    ///
+    /// | Code | Variant                        |
+    /// |------|--------------------------------|
+    /// | 1    | [`Error::Empty`]               |
+    /// | 3    | [`Error::Io`]                  |
+    /// | 4    | [`Error::DataDirNotFound`]     |
+    /// | 5    | [`Error::Json`]                |
+    /// | 6    | [`Error::Timestamp`]           |
+    /// | 7    | [`Error::Busy`]                |
+    /// | 8    | [`Error::UnsupportedVersion`]  |
+    /// | 9    | [`Error::TaskNotFound`]        |
+    /// | 10   | [`Error::InvalidDuration`]     |
+    /// | 11   | [`Error::InvalidRegex`]        |
+    /// | 12   | [`Error::Config`]              |
+    /// | 13   | [`Error::Editor`]              |
+    /// | 14   | [`Error::InvalidDate`]         |
+    /// | 15   | [`Error::Notification`]        |
+    /// | 16   | [`Error::WouldEmpty`]          |
+    /// | 17   | [`Error::TooManyTasks`]        |
+    /// | 18   | [`Error::InvalidTasks`]        |
+    /// | 19   | [`Error::NotAFile`]            |
+    /// | 20   | [`Error::Unhealthy`]           |
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Empty => 1,
+            Self::Io(_) => 3,
+            Self::DataDirNotFound(_) => 4,
+            Self::Json(_) => 5,
+            Self::Timestamp(_) => 6,
+            Self::Busy => 7,
+            Self::UnsupportedVersion { .. } => 8,
+            Self::TaskNotFound(_) => 9,
+            Self::InvalidDuration(_) => 10,
+            Self::InvalidRegex(_) => 11,
+            Self::Config(_) => 12,
+            Self::Editor(_) => 13,
+            Self::InvalidDate(_) => 14,
+            Self::Notification(_) => 15,
+            Self::WouldEmpty => 16,
+            Self::TooManyTasks { .. } => 17,
+            Self::InvalidTasks(_) => 18,
+            Self::NotAFile(_) => 19,
+            Self::Unhealthy(_) => 20,
+        }
+    }
+}
+
+impl AsErrStr for Error {
+    fn as_str(&self) -> Cow<'_, str> {
+        match self {
+            Self::Io(error) => Cow::Owned(error.to_string()),
+            Self::Json(error) => {
+                Cow::Owned(format!("data file is invalid: {error}"))
+            },
+            Self::Timestamp(error) => error.as_str(),
+            Self::DataDirNotFound(error) => error.as_str(),
+            Self::Busy => {
+                Cow::Borrowed("data file is busy: another loago is using it")
+            },
+            Self::UnsupportedVersion { found, supported } => {
+                Cow::Owned(format!(
+                    "data file format version {found} is newer than the \
+                     supported {supported}, update loago"
+                ))
+            },
+            Self::TaskNotFound(tasks) => {
+                let quoted: Vec<String> =
+                    tasks.iter().map(|task| format!("\"{task}\"")).collect();
+                let quoted = quoted.join(", ");
+                Cow::Owned(match tasks.len() {
+                    1 => format!("task {quoted} doesn't exist"),
+                    _ => format!("tasks {quoted} don't exist"),
+                })
+            },
+            Self::InvalidDuration(duration) => Cow::Owned(format!(
+                "invalid duration \"{duration}\", expected something like \
+                 7d, 12h or 1d12h30m"
+            )),
+            Self::InvalidRegex(error) => {
+                Cow::Owned(format!("invalid regex: {error}"))
+            },
+            Self::Config(error) => {
+                Cow::Owned(format!("config file is invalid: {error}"))
+            },
+            Self::Editor(error) => {
+                Cow::Owned(format!("editor failed: {error}"))
+            },
+            Self::InvalidDate(date) => Cow::Owned(format!(
+                "invalid date \"{date}\", expected something like \
+                 2023-12-20, 2023-12-20T18:30:00Z, yesterday, 3 days ago, \
+                 2 weeks ago or last monday"
+            )),
+            Self::Notification(error) => {
+                Cow::Owned(format!("couldn't notify: {error}"))
+            },
+            Self::WouldEmpty => Cow::Borrowed(
+                "refusing to remove every task, pass --allow-empty if that's \
+                 what you meant",
+            ),
+            Self::TooManyTasks { count, max } => Cow::Owned(format!(
+                "refusing to take {count} tasks at once, more than the \
+                 {max} allowed, pass --force if that's what you meant"
+            )),
+            Self::InvalidTasks(1) => {
+                Cow::Borrowed("1 task has an invalid timestamp")
+            },
+            Self::InvalidTasks(count) => {
+                Cow::Owned(format!("{count} tasks have invalid timestamps"))
+            },
+            Self::NotAFile(path) => Cow::Owned(format!(
+                "data file path is not a regular file: {}",
+                path.display()
+            )),
+            Self::Unhealthy(1) => Cow::Borrowed("found 1 problem"),
+            Self::Unhealthy(count) => {
+                Cow::Owned(format!("found {count} problems"))
+            },
+            Self::Empty => Cow::Borrowed("no tasks match"),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Json(error) => Some(error),
+            Self::Timestamp(error) => Some(error),
+            Self::DataDirNotFound(error) => Some(error),
+            Self::Busy
+            | Self::UnsupportedVersion { .. }
+            | Self::TaskNotFound(_)
+            | Self::InvalidDuration(_)
+            | Self::InvalidRegex(_)
+            | Self::Config(_)
+            | Self::Editor(_)
+            | Self::InvalidDate(_)
+            | Self::Notification(_)
+            | Self::WouldEmpty
+            | Self::TooManyTasks { .. }
+            | Self::InvalidTasks(_)
+            | Self::NotAFile(_)
+            | Self::Unhealthy(_)
+            | Self::Empty => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Json(value)
+    }
+}
+
+impl From<TimestampError> for Error {
+    fn from(value: TimestampError) -> Self {
+        Self::Timestamp(value)
+    }
+}
+
+impl From<DataDirNotFoundError> for Error {
+    fn from(value: DataDirNotFoundError) -> Self {
+        Self::DataDirNotFound(value)
+    }
+}
+
+#[cfg(test)]
+mod exit_codes {
+    use std::collections::HashSet;
+    use std::io;
+    use std::path::PathBuf;
+
+    use super::DataDirNotFoundError;
+    use super::Error;
+    use super::TimestampError;
+    use super::TimestampField;
+
+    #[test]
+    fn distinct_and_not_reserved() {
+        let timestamp = "nope".parse::<chrono::NaiveDateTime>().unwrap_err();
+        let json = serde_json::from_str::<u8>("nope").unwrap_err();
+        let errors = [
+            Error::Io(io::Error::other("nope")),
+            Error::DataDirNotFound(DataDirNotFoundError),
+            Error::Json(json),
+            Error::Timestamp(TimestampError {
+                task:  String::from("nope"),
+                field: TimestampField::Timestamp,
+                value: String::from("nope"),
+                error: timestamp,
+            }),
+            Error::Busy,
+            Error::UnsupportedVersion {
+                found:     2,
+                supported: 1,
+            },
+            Error::TaskNotFound(vec![String::from("nope")]),
+            Error::InvalidDuration(String::from("nope")),
+            Error::InvalidRegex(String::from("nope")),
+            Error::Config(String::from("nope")),
+            Error::Editor(String::from("nope")),
+            Error::InvalidDate(String::from("nope")),
+            Error::Notification(String::from("nope")),
+            Error::WouldEmpty,
+            Error::TooManyTasks { count: 2, max: 1 },
+            Error::InvalidTasks(1),
+            Error::NotAFile(PathBuf::from("nope")),
+            Error::Unhealthy(1),
+        ];
+        let codes: HashSet<i32> = errors.iter().map(Error::exit_code).collect();
+        assert_eq!(codes.len(), errors.len());
+        assert!(codes.iter().all(|code| *code > 2));
+        assert_eq!(Error::Empty.exit_code(), 1);
+    }
+}
+
+#[cfg(test)]
+mod messages {
+    use super::AsErrStr;
+    use super::Error;
+
+    #[test]
+    fn every_missing_task() {
+        let one = Error::TaskNotFound(vec![String::from("vaccum")]);
+        assert_eq!(one.as_str(), "task \"vaccum\" doesn't exist");
+        let several = Error::TaskNotFound(vec![
+            String::from("vaccum"),
+            String::from("dsut"),
+        ]);
+        assert_eq!(several.as_str(), "tasks \"vaccum\", \"dsut\" don't exist");
+    }
+}
@@ -0,0 +1,550 @@
+//! The machine-readable ways that `view` can display tasks in, and the ones
+//! of the user's own.
+
+use std::mem;
+use std::str::FromStr;
+
+use clap::ValueEnum;
+use loago::duration_parts;
+use loago::format_timestamp;
+use loago::format_timestamp_rfc3339;
+use loago::TaskAge;
+use serde::Serialize;
+use unicode_width::UnicodeWidthStr;
+
+use crate::args::Unit;
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Format {
+    /// A json array of `{"task", "days", "last_done"}` objects. With
+    /// `--minutes`, they have the `hours` and `minutes` past the days too,
+    /// and with `--seconds`, the `seconds` past those as well.
+    Json,
+    /// One json object per line, in the same shape as with `json`.
+    Ndjson,
+    /// `{name} {unix}` per line, where `unix` is when the task was last
+    /// done, in seconds since the unix epoch.
+    Epoch,
+    /// `task,days,last_done` rows, with the same fields as `json`, after a
+    /// header row of their names.
+    Csv,
+    /// A markdown table with the same columns as `csv`.
+    Markdown,
+    /// A yaml list of `{task, days, last_done}` mappings, like `json`.
+    #[cfg(feature = "yaml")]
+    Yaml,
+}
+
+/// What a task looks like in the json formats.
+#[derive(Serialize)]
+struct JsonTask<'a> {
+    task:      &'a str,
+    days:      i64,
+    /// In the same format as it's stored in the data file.
+    last_done: String,
+    /// Past the whole days, like the `{hours}h` of `--minutes`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hours:     Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    minutes:   Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seconds:   Option<i64>,
+}
+
+impl<'a> From<&'a TaskAge> for JsonTask<'a> {
+    fn from(value: &'a TaskAge) -> Self {
+        Self {
+            task:      &value.name,
+            days:      value.elapsed.num_days(),
+            last_done: format_timestamp(value.task.timestamp),
+            hours:     None,
+            minutes:   None,
+            seconds:   None,
+        }
+    }
+}
+
+impl<'a> JsonTask<'a> {
+    /// With the parts of a day that `unit` shows, the same ones it'd display.
+    fn in_unit(age: &'a TaskAge, unit: Unit) -> Self {
+        let parts = duration_parts(age.elapsed);
+        let minutes = matches!(unit, Unit::Minutes | Unit::Seconds);
+        let seconds = matches!(unit, Unit::Seconds);
+        Self {
+            hours: minutes.then_some(parts.hours),
+            minutes: minutes.then_some(parts.minutes),
+            seconds: seconds.then_some(parts.seconds),
+            ..Self::from(age)
+        }
+    }
+}
+
+impl Format {
+    /// Render the tasks, keeping their order. Without a `header`, the tabular
+    /// formats leave out the row that names the columns, for appending to
+    /// what they rendered before. The json-like formats go into as much
+    /// detail as `unit`.
+    pub fn render(self, ages: &[TaskAge], header: bool, unit: Unit) -> String {
+        let tasks = ages.iter().map(|age| JsonTask::in_unit(age, unit));
+        match self {
+            Self::Json => {
+                let tasks: Vec<JsonTask> = tasks.collect();
+                let mut json = to_json(&tasks);
+                json.push('\n');
+                json
+            },
+            Self::Ndjson => tasks.map(|task| to_json(&task) + "\n").collect(),
+            Self::Epoch => ages
+                .iter()
+                .map(|age| {
+                    format!(
+                        "{} {}\n",
+                        age.name,
+                        age.task.timestamp.and_utc().timestamp()
+                    )
+                })
+                .collect(),
+            Self::Csv => {
+                let rows = tasks.map(|task| {
+                    format!(
+                        "{},{},{}\n",
+                        csv_field(task.task),
+                        task.days,
+                        task.last_done
+                    )
+                });
+                header
+                    .then_some(String::from("task,days,last_done\n"))
+                    .into_iter()
+                    .chain(rows)
+                    .collect()
+            },
+            Self::Markdown => {
+                let rows = tasks.map(|task| {
+                    format!(
+                        "| {} | {} | {} |\n",
+                        task.task.replace('|', "\\|"),
+                        task.days,
+                        task.last_done
+                    )
+                });
+                header
+                    .then_some(String::from(
+                        "| task | days | last done |\n|---|--:|---|\n",
+                    ))
+                    .into_iter()
+                    .chain(rows)
+                    .collect()
+            },
+            #[cfg(feature = "yaml")]
+            Self::Yaml => {
+                let tasks: Vec<JsonTask> = tasks.collect();
+                serde_yaml::to_string(&tasks)
+                    .expect("strings and numbers always serialize")
+            },
+        }
+    }
+}
+
+fn to_json(value: &impl Serialize) -> String {
+    serde_json::to_string(value).expect("strings and numbers always serialize")
+}
+
+/// A task name quoted for csv if it has to be, doubling the quotes in it.
+fn csv_field(name: &str) -> String {
+    if name.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    } else {
+        name.to_owned()
+    }
+}
+
+/// What separates the fields of `view --porcelain` unless `--delimiter` says
+/// otherwise.
+pub const PORCELAIN_DELIMITER: &str = "\t";
+
+/// A line of `view --porcelain`, with the fields separated by `delimiter`.
+/// Scripts depend on this format, so it should never change.
+///
+/// The name is the last field, so that it can contain the delimiter without
+/// breaking anything.
+pub fn porcelain(age: &TaskAge, delimiter: &str) -> String {
+    format!(
+        "{}{delimiter}{}{delimiter}{}",
+        age.task.timestamp.timestamp(),
+        age.elapsed.num_days(),
+        age.name
+    )
+}
+
+/// What separates the levels of task names in `view --tree` unless
+/// `--tree-separator` says otherwise.
+pub const TREE_SEPARATOR: &str = "/";
+
+/// A task name segment in `view --tree`, with the tasks under it.
+struct Node {
+    segment:  String,
+    /// What to display for the task with exactly this name, if there is one.
+    display:  Option<String>,
+    children: Vec<Node>,
+}
+
+/// Tasks named like `home/kitchen/sink` as an indented tree of the parts
+/// of their names split on `separator`, with the `display` of every task
+/// after its last part, lined up. Tasks without the separator are at the
+/// root. Every level keeps the order that the tasks were given in.
+pub fn tree(
+    tasks: &[(String, String)],
+    separator: &str,
+    between: &str,
+) -> String {
+    let mut root: Vec<Node> = Vec::new();
+    for (name, display) in tasks {
+        let mut level = &mut root;
+        let mut segments = name.split(separator).peekable();
+        while let Some(segment) = segments.next() {
+            let index =
+                match level.iter().position(|node| node.segment == segment) {
+                    Some(index) => index,
+                    None => {
+                        level.push(Node {
+                            segment:  segment.to_owned(),
+                            display:  None,
+                            children: Vec::new(),
+                        });
+                        level.len() - 1
+                    },
+                };
+            if segments.peek().is_none() {
+                level[index].display = Some(display.clone());
+            }
+            level = &mut level[index].children;
+        }
+    }
+    let mut lines = Vec::new();
+    flatten(&root, 0, &mut lines);
+    let width = lines
+        .iter()
+        .filter(|(_, display)| display.is_some())
+        .map(|(label, _)| label.width())
+        .max()
+        .unwrap_or_default();
+    lines
+        .into_iter()
+        .map(|(label, display)| match display {
+            Some(display) => {
+                let padding = " ".repeat(width - label.width());
+                format!("{label}{padding}{between}{display}\n")
+            },
+            None => format!("{label}\n"),
+        })
+        .collect()
+}
+
+/// Every node as its indented label and display, depth first.
+fn flatten<'a>(
+    nodes: &'a [Node],
+    depth: usize,
+    lines: &mut Vec<(String, Option<&'a str>)>,
+) {
+    for node in nodes {
+        let label = format!("{}{}", "  ".repeat(depth), node.segment);
+        lines.push((label, node.display.as_deref()));
+        flatten(&node.children, depth + 1, lines);
+    }
+}
+
+/// A line per task, in a format of the user's own, like `{name}: {days}d`.
+/// The placeholders are:
+///
+/// | Placeholder | Replaced with                                     |
+/// |-------------|---------------------------------------------------|
+/// | `{name}`    | the task name                                     |
+/// | `{days}`    | how many whole days ago the task was last done    |
+/// | `{hours}`   | how many whole hours ago, in total                |
+/// | `{minutes}` | how many whole minutes ago, in total              |
+/// | `{date}`    | the day it was last done, like `2023-12-20`       |
+/// | `{iso}`     | when it was last done, like `2023-12-20T18:30:00Z` |
+/// | `{epoch}`   | when it was last done, in seconds since the epoch |
+///
+/// `{{` and `}}` are a literal `{` and `}`. Anything else in braces fails to
+/// parse, so that a typo is caught before anything is displayed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Template(Vec<Piece>);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Piece {
+    Text(String),
+    Field(Field),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Field {
+    Name,
+    Days,
+    Hours,
+    Minutes,
+    Date,
+    Iso,
+    Epoch,
+}
+
+impl FromStr for Field {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Ok(match input {
+            "name" => Self::Name,
+            "days" => Self::Days,
+            "hours" => Self::Hours,
+            "minutes" => Self::Minutes,
+            "date" => Self::Date,
+            "iso" => Self::Iso,
+            "epoch" => Self::Epoch,
+            _ => {
+                return Err(format!(
+                "unknown placeholder {{{input}}}, expected one of {{name}}, \
+                     {{days}}, {{hours}}, {{minutes}}, {{date}}, {{iso}} or \
+                     {{epoch}}"
+            ))
+            },
+        })
+    }
+}
+
+impl FromStr for Template {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut pieces = Vec::new();
+        let mut text = String::new();
+        let mut chars = input.chars();
+        while let Some(char) = chars.next() {
+            match char {
+                '{' if chars.as_str().starts_with('{') => {
+                    chars.next();
+                    text.push('{');
+                },
+                '}' if chars.as_str().starts_with('}') => {
+                    chars.next();
+                    text.push('}');
+                },
+                '{' => {
+                    let rest = chars.as_str();
+                    let end = rest
+                        .find('}')
+                        .ok_or("unclosed {, write {{ for a literal one")?;
+                    let field = rest[..end].parse()?;
+                    chars = rest[end + 1..].chars();
+                    if !text.is_empty() {
+                        pieces.push(Piece::Text(mem::take(&mut text)));
+                    }
+                    pieces.push(Piece::Field(field));
+                },
+                '}' => {
+                    return Err(String::from(
+                        "unmatched }, write }} for a literal one",
+                    ))
+                },
+                char => text.push(char),
+            }
+        }
+        if !text.is_empty() {
+            pieces.push(Piece::Text(text));
+        }
+        Ok(Self(pieces))
+    }
+}
+
+impl Template {
+    /// Fill in the placeholders for one task.
+    pub fn render(&self, age: &TaskAge) -> String {
+        let timestamp = age.task.timestamp;
+        self.0
+            .iter()
+            .map(|piece| match piece {
+                Piece::Text(text) => text.clone(),
+                Piece::Field(field) => match field {
+                    Field::Name => age.name.clone(),
+                    Field::Days => age.elapsed.num_days().to_string(),
+                    Field::Hours => age.elapsed.num_hours().to_string(),
+                    Field::Minutes => age.elapsed.num_minutes().to_string(),
+                    Field::Date => timestamp.format("%Y-%m-%d").to_string(),
+                    Field::Iso => format_timestamp_rfc3339(timestamp),
+                    Field::Epoch => timestamp.and_utc().timestamp().to_string(),
+                },
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod rendering {
+    use chrono::Duration;
+    use chrono::NaiveDateTime;
+    use loago::Task;
+    use loago::TaskAge;
+    use serde_json::Value;
+
+    use super::porcelain;
+    use super::tree;
+    use super::Format;
+    use super::Template;
+    use super::PORCELAIN_DELIMITER;
+    use crate::args::Unit;
+
+    fn ages() -> Vec<TaskAge> {
+        let day_after_epoch = NaiveDateTime::UNIX_EPOCH + Duration::days(1);
+        vec![
+            TaskAge {
+                name:    String::from("wash car"),
+                task:    Task::new(day_after_epoch),
+                elapsed: Duration::days(3) + Duration::hours(5),
+            },
+            TaskAge {
+                name:    String::from("dust"),
+                task:    Task::new(NaiveDateTime::UNIX_EPOCH),
+                elapsed: Duration::days(4) + Duration::hours(5),
+            },
+        ]
+    }
+
+    #[test]
+    fn porcelain_field_order() {
+        assert_eq!(
+            porcelain(&ages()[0], PORCELAIN_DELIMITER),
+            "86400\t3\twash car"
+        );
+        assert_eq!(porcelain(&ages()[0], " "), "86400 3 wash car");
+        let line = porcelain(&ages()[0], " ");
+        assert_eq!(line.splitn(3, ' ').nth(2), Some("wash car"));
+    }
+
+    #[test]
+    fn epoch() {
+        assert_eq!(
+            Format::Epoch.render(&ages(), true, Unit::Days),
+            "wash car 86400\ndust 0\n"
+        );
+    }
+
+    #[test]
+    fn ndjson_lines_stand_alone() {
+        let rendered = Format::Ndjson.render(&ages(), true, Unit::Days);
+        let lines: Vec<Value> = rendered
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["task"], "wash car");
+        assert_eq!(lines[0]["days"], 3);
+        assert_eq!(lines[0]["last_done"], "1970-01-02T00:00:00");
+        assert_eq!(lines[1]["task"], "dust");
+    }
+
+    #[test]
+    fn json_is_an_array() {
+        let rendered: Value = serde_json::from_str(&Format::Json.render(
+            &ages(),
+            true,
+            Unit::Days,
+        ))
+        .unwrap();
+        assert_eq!(rendered[1]["days"], 4);
+    }
+
+    #[test]
+    fn json_in_minutes() {
+        let rendered: Value = serde_json::from_str(&Format::Json.render(
+            &ages(),
+            true,
+            Unit::Minutes,
+        ))
+        .unwrap();
+        assert_eq!(rendered[0]["days"], 3);
+        assert_eq!(rendered[0]["hours"], 5);
+        assert_eq!(rendered[0]["minutes"], 0);
+        assert!(rendered[0].get("seconds").is_none());
+        let rendered = Format::Ndjson.render(&ages(), true, Unit::Seconds);
+        assert!(rendered.contains(r#""hours":5,"minutes":0,"seconds":0}"#));
+        let rendered = Format::Json.render(&ages(), true, Unit::Weeks);
+        assert!(!rendered.contains("hours"));
+    }
+
+    #[test]
+    fn template() {
+        let template: Template =
+            "{name}: {days}d {{{hours}h}} {minutes}m ({date}, {iso}, {epoch})"
+                .parse()
+                .unwrap();
+        assert_eq!(
+            template.render(&ages()[0]),
+            "wash car: 3d {77h} 4620m (1970-01-02, 1970-01-02T00:00:00Z, \
+             86400)"
+        );
+        assert_eq!("".parse::<Template>().unwrap().render(&ages()[0]), "");
+        for invalid in ["{nam}", "{name", "name}", "{}", "{{name}"] {
+            assert!(invalid.parse::<Template>().is_err(), "{invalid:?}");
+        }
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn yaml_quotes_names() {
+        let mut ages = ages();
+        ages[1].name = String::from("#1: dust, 'top' shelf");
+        assert_eq!(
+            Format::Yaml.render(&ages, true, Unit::Days),
+            "- task: wash car\n  days: 3\n  last_done: 1970-01-02T00:00:00\n\
+             - task: '#1: dust, ''top'' shelf'\n  days: 4\n  last_done: \
+             1970-01-01T00:00:00\n"
+        );
+        assert_eq!(Format::Yaml.render(&[], true, Unit::Days), "[]\n");
+    }
+
+    #[test]
+    fn csv_header() {
+        let mut ages = ages();
+        ages[1].name = String::from("dust \"top\", shelf");
+        let rows = "wash car,3,1970-01-02T00:00:00\n\"dust \"\"top\"\", \
+                    shelf\",4,1970-01-01T00:00:00\n";
+        assert_eq!(
+            Format::Csv.render(&ages, true, Unit::Days),
+            format!("task,days,last_done\n{rows}")
+        );
+        assert_eq!(Format::Csv.render(&ages, false, Unit::Days), rows);
+        assert_eq!(Format::Csv.render(&[], false, Unit::Days), "");
+    }
+
+    #[test]
+    fn markdown_header() {
+        let mut ages = ages();
+        ages[1].name = String::from("a|b");
+        let rows = "| wash car | 3 | 1970-01-02T00:00:00 |\n| a\\|b | 4 | \
+                    1970-01-01T00:00:00 |\n";
+        assert_eq!(
+            Format::Markdown.render(&ages, true, Unit::Days),
+            format!("| task | days | last done |\n|---|--:|---|\n{rows}")
+        );
+        assert_eq!(Format::Markdown.render(&ages, false, Unit::Days), rows);
+    }
+
+    #[test]
+    fn two_level_tree() {
+        let tasks = [
+            ("home/kitchen", "1"),
+            ("home/bathroom", "2"),
+            ("dust", "3"),
+            ("car/wash", "4"),
+            ("home", "5"),
+        ]
+        .map(|(name, display)| (name.to_owned(), display.to_owned()));
+        assert_eq!(
+            tree(&tasks, "/", " — "),
+            "home       — 5\n  kitchen  — 1\n  bathroom — 2\ndust       — 3\ncar\n  \
+             wash     — 4\n"
+        );
+        assert_eq!(tree(&tasks[..1], ".", ": "), "home/kitchen: 1\n");
+        assert_eq!(tree(&[], "/", " — "), "");
+    }
+}
@@ -0,0 +1,213 @@
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::mem;
+
+/// Named groups of tasks that always get done together.
+///
+/// Doing a group means doing every one of its member tasks, see
+/// [`Groups::expand`]. A group is not a task by itself, so it's never stored in
+/// [`crate::Tasks`].
+///
+/// Members are task names, never other groups: a member that happens to share
+/// a name with a group is not expanded further.
+#[derive(Clone, Default)]
+pub struct Groups(HashMap<String, BTreeSet<String>>);
+
+/// For deserializing from a data file, same as with [`crate::Tasks`].
+impl From<HashMap<String, Vec<String>>> for Groups {
+    fn from(value: HashMap<String, Vec<String>>) -> Self {
+        Self(
+            value
+                .into_iter()
+                .map(|(group, members)| (group, members.into_iter().collect()))
+                .collect(),
+        )
+    }
+}
+
+/// For serializing back into a data file. The members of each group are
+/// sorted.
+impl From<Groups> for HashMap<String, Vec<String>> {
+    fn from(value: Groups) -> Self {
+        value
+            .0
+            .into_iter()
+            .map(|(group, members)| (group, members.into_iter().collect()))
+            .collect()
+    }
+}
+
+impl Groups {
+    /// Add tasks to a group, creating the group if it didn't exist prior.
+    pub fn add(
+        &mut self,
+        group: impl Into<String>,
+        tasks: impl IntoIterator<Item = impl Into<String>>,
+    ) {
+        self.0
+            .entry(group.into())
+            .or_default()
+            .extend(tasks.into_iter().map(Into::into));
+    }
+
+    /// Remove tasks from a group. Removing every member, or not specifying any
+    /// tasks at all, removes the whole group.
+    pub fn remove(&mut self, group: &str, tasks: &[impl AsRef<str>]) {
+        let Some(members) = self.0.get_mut(group) else {
+            return;
+        };
+        for task in tasks {
+            members.remove(task.as_ref());
+        }
+        if tasks.is_empty() || members.is_empty() {
+            self.0.remove(group);
+        }
+    }
+
+    /// Add every group from `other` into these groups. Groups that exist in
+    /// both end up with the members of both.
+    pub fn merge(&mut self, other: Groups) {
+        for (group, members) in other.0 {
+            self.add(group, members);
+        }
+    }
+
+    /// Rename a member task in every group it's in, like
+    /// [`crate::Tasks::rename`] does.
+    pub fn rename_member(&mut self, old: &str, new: &str) {
+        for members in self.0.values_mut() {
+            if members.remove(old) {
+                members.insert(new.to_owned());
+            }
+        }
+    }
+
+    /// Exchange two member tasks in every group that has either, like
+    /// [`crate::Tasks::swap`] does.
+    pub fn swap_members(&mut self, a: &str, b: &str) {
+        for members in self.0.values_mut() {
+            let had_a = members.remove(a);
+            let had_b = members.remove(b);
+            if had_a {
+                members.insert(b.to_owned());
+            }
+            if had_b {
+                members.insert(a.to_owned());
+            }
+        }
+    }
+
+    /// Rename every member task starting with `old_prefix` to start with
+    /// `new_prefix` instead, like [`crate::Tasks::rename_prefix`] does.
+    pub fn rename_prefix(&mut self, old_prefix: &str, new_prefix: &str) {
+        for members in self.0.values_mut() {
+            *members = mem::take(members)
+                .into_iter()
+                .map(|task| match task.strip_prefix(old_prefix) {
+                    Some(rest) => format!("{new_prefix}{rest}"),
+                    None => task,
+                })
+                .collect();
+        }
+    }
+
+    /// Replace every name that is a group with the group's member tasks.
+    ///
+    /// Names that aren't groups are kept as is. Every task is only returned
+    /// once, even if it's mentioned several times.
+    pub fn expand(
+        &self,
+        names: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Vec<String> {
+        let mut seen = BTreeSet::new();
+        let mut tasks = Vec::new();
+        for name in names {
+            let name = name.into();
+            let expanded = match self.0.get(&name) {
+                Some(members) => members.iter().cloned().collect(),
+                None => vec![name],
+            };
+            for task in expanded {
+                if seen.insert(task.clone()) {
+                    tasks.push(task);
+                }
+            }
+        }
+        tasks
+    }
+
+    /// Iterate over every group and its members, sorted by group name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &BTreeSet<String>)> {
+        let mut groups: Vec<_> = self.0.iter().collect();
+        groups.sort_by_key(|(group, _)| *group);
+        groups
+            .into_iter()
+            .map(|(group, members)| (group.as_str(), members))
+    }
+}
+
+#[cfg(test)]
+mod membership {
+    use super::Groups;
+
+    fn kitchen() -> Groups {
+        let mut groups = Groups::default();
+        groups.add("clean-kitchen", ["wipe-counters", "sweep"]);
+        groups
+    }
+
+    #[test]
+    fn expand() {
+        let groups = kitchen();
+        assert_eq!(groups.expand(["clean-kitchen", "vacuum"]), [
+            "sweep",
+            "wipe-counters",
+            "vacuum"
+        ]);
+    }
+
+    #[test]
+    fn expand_dedups() {
+        let groups = kitchen();
+        assert_eq!(groups.expand(["sweep", "clean-kitchen"]), [
+            "sweep",
+            "wipe-counters"
+        ]);
+    }
+
+    #[test]
+    fn remove_members() {
+        let mut groups = kitchen();
+        groups.remove("clean-kitchen", &["sweep"]);
+        assert_eq!(groups.expand(["clean-kitchen"]), ["wipe-counters"]);
+        groups.remove("clean-kitchen", &["wipe-counters"]);
+        assert_eq!(groups.expand(["clean-kitchen"]), ["clean-kitchen"]);
+    }
+
+    #[test]
+    fn rename_members() {
+        let mut groups = kitchen();
+        groups.add("clean-kitchen", ["swipe"]);
+        groups.rename_member("wipe-counters", "wipe");
+        groups.rename_prefix("sw", "m");
+        assert_eq!(groups.expand(["clean-kitchen"]), ["meep", "mipe", "wipe"]);
+    }
+
+    #[test]
+    fn swap_members() {
+        let mut groups = kitchen();
+        groups.add("floors", ["mop"]);
+        groups.swap_members("sweep", "mop");
+        assert_eq!(groups.expand(["clean-kitchen"]), ["mop", "wipe-counters"]);
+        assert_eq!(groups.expand(["floors"]), ["sweep"]);
+        groups.swap_members("sweep", "sweep");
+        assert_eq!(groups.expand(["floors"]), ["sweep"]);
+    }
+
+    #[test]
+    fn remove_whole_group() {
+        let mut groups = kitchen();
+        groups.remove("clean-kitchen", &[] as &[&str]);
+        assert_eq!(groups.iter().count(), 0);
+    }
+}
@@ -0,0 +1,172 @@
+//! The log of the commands that changed the data file, kept next to it in a
+//! `{data file}.history` file with a json object per line, oldest first.
+
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::ErrorKind;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use loago::errors::Error;
+use loago::format_timestamp;
+use loago::now;
+use serde::Deserialize;
+use serde::Serialize;
+
+const HISTORY_EXTENSION: &str = "history";
+/// Recording past this many entries drops the oldest ones, so that the history
+/// doesn't grow forever.
+pub const MAX_ENTRIES: usize = 1000;
+
+/// A command that changed the data file.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    /// When the command ran, in the same format as the data file's timestamps.
+    pub at:      String,
+    /// The subcommand, with the flags that change what it does, like
+    /// `remove --except`.
+    pub command: String,
+    /// What the command was given, mostly tasks.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args:    Vec<String>,
+    /// Why the command ran, like `do --note "guests coming"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note:    Option<String>,
+}
+
+impl Entry {
+    /// A `command` that ran just now.
+    pub fn new(command: &str, args: Vec<String>) -> Self {
+        Self {
+            at: format_timestamp(now()),
+            command: command.to_owned(),
+            args,
+            note: None,
+        }
+    }
+}
+
+pub struct History {
+    path: PathBuf,
+}
+
+impl History {
+    /// The history of the data file at `data_file`.
+    pub fn of(data_file: &Path) -> Self {
+        let mut path = data_file.as_os_str().to_owned();
+        path.push(".");
+        path.push(HISTORY_EXTENSION);
+        Self {
+            path: PathBuf::from(path),
+        }
+    }
+
+    /// Append an entry, pruning the history down to [`MAX_ENTRIES`] if that
+    /// makes it too long.
+    pub fn record(&self, entry: &Entry) -> Result<(), Error> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(line.as_bytes())?;
+        self.prune(MAX_ENTRIES)?;
+        Ok(())
+    }
+
+    /// Every entry, oldest first.
+    ///
+    /// # Errors
+    /// [`Error::Json`] if any line isn't a valid entry.
+    pub fn entries(&self) -> Result<Vec<Entry>, Error> {
+        self.read()?
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| Ok(serde_json::from_str(line)?))
+            .collect()
+    }
+
+    /// Drop all but the `keep` most recent entries, returning how many were
+    /// dropped.
+    pub fn prune(&self, keep: usize) -> Result<usize, Error> {
+        let contents = self.read()?;
+        let lines: Vec<&str> =
+            contents.lines().filter(|line| !line.is_empty()).collect();
+        let dropped = lines.len().saturating_sub(keep);
+        if dropped == 0 {
+            return Ok(0);
+        }
+        let kept: String = lines[dropped..]
+            .iter()
+            .map(|line| format!("{line}\n"))
+            .collect();
+        fs::write(&self.path, kept)?;
+        Ok(dropped)
+    }
+
+    /// A history that doesn't exist yet is empty.
+    fn read(&self) -> Result<String, Error> {
+        match fs::read_to_string(&self.path) {
+            Err(error) if error.kind() == ErrorKind::NotFound => {
+                Ok(String::new())
+            },
+            contents => Ok(contents?),
+        }
+    }
+}
+
+#[cfg(test)]
+mod pruning {
+    use std::fs;
+
+    use super::Entry;
+    use super::History;
+    use super::MAX_ENTRIES;
+
+    fn entry(task: &str) -> Entry {
+        Entry {
+            at:      "2023-12-20T00:00:00".to_owned(),
+            command: "do".to_owned(),
+            args:    vec![task.to_owned()],
+            note:    None,
+        }
+    }
+
+    #[test]
+    fn keeps_the_latest() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = History::of(&dir.path().join("loago.json"));
+        assert!(history.entries().unwrap().is_empty());
+        assert_eq!(history.prune(1).unwrap(), 0);
+        for task in ["dust", "mop", "vacuum"] {
+            history.record(&entry(task)).unwrap();
+        }
+        assert_eq!(history.prune(5).unwrap(), 0);
+        let commands: Vec<Entry> = ["dust", "mop", "vacuum"].map(entry).into();
+        assert_eq!(history.entries().unwrap(), commands);
+        assert_eq!(history.prune(1).unwrap(), 2);
+        let contents = fs::read_to_string(&history.path).unwrap();
+        assert_eq!(
+            contents,
+            r#"{"at":"2023-12-20T00:00:00","command":"do","args":["vacuum"]}"#
+                .to_owned()
+                + "\n"
+        );
+        assert_eq!(history.prune(0).unwrap(), 1);
+        assert_eq!(fs::read_to_string(&history.path).unwrap(), "");
+    }
+
+    #[test]
+    fn capped() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = History::of(&dir.path().join("loago.json"));
+        let old = serde_json::to_string(&entry("old")).unwrap() + "\n";
+        fs::write(&history.path, old.repeat(MAX_ENTRIES)).unwrap();
+        history.record(&entry("new")).unwrap();
+        let contents = fs::read_to_string(&history.path).unwrap();
+        assert_eq!(contents.lines().count(), MAX_ENTRIES);
+        assert!(contents.ends_with("[\"new\"]}\n"));
+    }
+}
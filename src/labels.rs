@@ -0,0 +1,238 @@
+//! The words that durations are formatted with, all in one place, so that
+//! they can be in another language than English.
+
+use std::borrow::Cow;
+
+use chrono::Duration;
+
+use crate::duration_parts;
+use crate::DurationParts;
+
+/// How a unit of time is spelled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnitLabels {
+    /// Right after the amount in compact formats, like the `d` in `3d`.
+    pub short:    Cow<'static, str>,
+    /// After an amount of exactly one, like `day`.
+    pub singular: Cow<'static, str>,
+    /// After any other amount, like `days`.
+    pub plural:   Cow<'static, str>,
+}
+
+impl UnitLabels {
+    const fn english(
+        short: &'static str,
+        singular: &'static str,
+        plural: &'static str,
+    ) -> Self {
+        Self {
+            short:    Cow::Borrowed(short),
+            singular: Cow::Borrowed(singular),
+            plural:   Cow::Borrowed(plural),
+        }
+    }
+}
+
+/// Every word that the duration formatting functions use, like
+/// [`crate::humanize`] and [`crate::due`], which use [`Labels::ENGLISH`]. To
+/// format durations in another language, make a [`Labels`] with its words and
+/// call the methods of the same name on it instead.
+///
+/// ```
+/// use std::borrow::Cow;
+///
+/// use chrono::Duration;
+/// use loago::Labels;
+/// use loago::UnitLabels;
+/// let labels = Labels {
+///     day: UnitLabels {
+///         short:    Cow::Borrowed("T"),
+///         singular: Cow::Borrowed("Tag"),
+///         plural:   Cow::Borrowed("Tage"),
+///     },
+///     due_in: Cow::Borrowed("fällig in"),
+///     ..Labels::ENGLISH
+/// };
+/// let remaining = Duration::days(2);
+/// assert_eq!(
+///     labels.due(remaining, |days| labels.days_with_unit(days)),
+///     "fällig in 2 Tage"
+/// );
+/// assert_eq!(labels.humanize(remaining), "2T");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Labels {
+    pub second:     UnitLabels,
+    pub minute:     UnitLabels,
+    pub hour:       UnitLabels,
+    pub day:        UnitLabels,
+    pub week:       UnitLabels,
+    /// What [`Labels::humanize`] says for less than a minute, `now`.
+    pub now:        Cow<'static, str>,
+    /// What [`Labels::humanize_with_unit`] says for less than a minute,
+    /// `just now`.
+    pub just_now:   Cow<'static, str>,
+    /// Goes before the time left until a task is due, `due in`.
+    pub due_in:     Cow<'static, str>,
+    /// Goes before how long a task has been overdue, `overdue by`.
+    pub overdue_by: Cow<'static, str>,
+}
+
+impl Labels {
+    pub const ENGLISH: Self = Self {
+        second:     UnitLabels::english("s", "second", "seconds"),
+        minute:     UnitLabels::english("m", "minute", "minutes"),
+        hour:       UnitLabels::english("h", "hour", "hours"),
+        day:        UnitLabels::english("d", "day", "days"),
+        week:       UnitLabels::english("w", "week", "weeks"),
+        now:        Cow::Borrowed("now"),
+        just_now:   Cow::Borrowed("just now"),
+        due_in:     Cow::Borrowed("due in"),
+        overdue_by: Cow::Borrowed("overdue by"),
+    };
+
+    /// See [`crate::humanize`].
+    pub fn humanize(&self, duration: Duration) -> String {
+        let (amount, unit) = if duration.num_days() > 0 {
+            (duration.num_days(), &self.day)
+        } else if duration.num_hours() > 0 {
+            (duration.num_hours(), &self.hour)
+        } else if duration.num_minutes() > 0 {
+            (duration.num_minutes(), &self.minute)
+        } else {
+            return self.now.clone().into_owned();
+        };
+        format!("{amount}{}", unit.short)
+    }
+
+    /// See [`crate::humanize_with_unit`].
+    pub fn humanize_with_unit(&self, duration: Duration) -> String {
+        let (amount, unit) = if duration.num_weeks() > 0 {
+            (duration.num_weeks(), &self.week)
+        } else if duration.num_days() > 0 {
+            (duration.num_days(), &self.day)
+        } else if duration.num_hours() > 0 {
+            (duration.num_hours(), &self.hour)
+        } else if duration.num_minutes() > 0 {
+            (duration.num_minutes(), &self.minute)
+        } else {
+            return self.just_now.clone().into_owned();
+        };
+        with_unit(amount, unit)
+    }
+
+    /// See [`crate::days_with_unit`].
+    pub fn days_with_unit(&self, duration: Duration) -> String {
+        with_unit(duration.num_days(), &self.day)
+    }
+
+    /// See [`crate::due`].
+    pub fn due<F>(&self, remaining: Duration, to_string: F) -> String
+    where
+        F: Fn(Duration) -> String,
+    {
+        if remaining < Duration::zero() {
+            format!("{} {}", self.overdue_by, to_string(-remaining))
+        } else {
+            format!("{} {}", self.due_in, to_string(remaining))
+        }
+    }
+
+    /// See [`crate::days_hours_minutes`].
+    pub fn days_hours_minutes(&self, duration: Duration) -> String {
+        let DurationParts {
+            days,
+            hours,
+            minutes,
+            ..
+        } = duration_parts(duration);
+        format!(
+            "{days}{} {hours}{} {minutes}{}",
+            self.day.short, self.hour.short, self.minute.short
+        )
+    }
+
+    /// See [`crate::days_hours_minutes_seconds`].
+    pub fn days_hours_minutes_seconds(&self, duration: Duration) -> String {
+        let seconds = duration_parts(duration).seconds;
+        format!(
+            "{} {seconds}{}",
+            self.days_hours_minutes(duration),
+            self.second.short
+        )
+    }
+}
+
+impl Default for Labels {
+    fn default() -> Self {
+        Self::ENGLISH
+    }
+}
+
+/// `1 {singular}`, or `{amount} {plural}` for any other amount.
+fn with_unit(amount: i64, unit: &UnitLabels) -> String {
+    match amount {
+        1 => format!("1 {}", unit.singular),
+        amount => format!("{amount} {}", unit.plural),
+    }
+}
+
+#[cfg(test)]
+mod translated {
+    use std::borrow::Cow;
+
+    use chrono::Duration;
+
+    use super::Labels;
+    use super::UnitLabels;
+
+    fn unit(
+        short: &'static str,
+        singular: &'static str,
+        plural: &'static str,
+    ) -> UnitLabels {
+        UnitLabels {
+            short:    Cow::Borrowed(short),
+            singular: Cow::Borrowed(singular),
+            plural:   Cow::Borrowed(plural),
+        }
+    }
+
+    fn german() -> Labels {
+        Labels {
+            second:     unit("s", "Sekunde", "Sekunden"),
+            minute:     unit("m", "Minute", "Minuten"),
+            hour:       unit("h", "Stunde", "Stunden"),
+            day:        unit("T", "Tag", "Tage"),
+            week:       unit("W", "Woche", "Wochen"),
+            now:        Cow::Borrowed("jetzt"),
+            just_now:   Cow::Borrowed("gerade eben"),
+            due_in:     Cow::Borrowed("fällig in"),
+            overdue_by: Cow::Owned(String::from("überfällig seit")),
+        }
+    }
+
+    #[test]
+    fn every_label() {
+        let labels = german();
+        assert_eq!(labels.humanize(Duration::days(3)), "3T");
+        assert_eq!(labels.humanize(Duration::seconds(3)), "jetzt");
+        assert_eq!(labels.humanize_with_unit(Duration::weeks(1)), "1 Woche");
+        assert_eq!(labels.humanize_with_unit(Duration::hours(5)), "5 Stunden");
+        assert_eq!(labels.humanize_with_unit(Duration::zero()), "gerade eben");
+        let duration = Duration::days(2)
+            + Duration::hours(3)
+            + Duration::minutes(4)
+            + Duration::seconds(5);
+        assert_eq!(labels.days_hours_minutes_seconds(duration), "2T 3h 4m 5s");
+        let overdue =
+            labels.due(-Duration::days(1), |days| labels.days_with_unit(days));
+        assert_eq!(overdue, "überfällig seit 1 Tag");
+    }
+
+    #[test]
+    fn english_by_default() {
+        assert_eq!(Labels::default(), Labels::ENGLISH);
+        assert_eq!(Labels::ENGLISH.humanize(Duration::hours(47)), "1d");
+    }
+}
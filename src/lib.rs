@@ -1,13 +1,62 @@
 #![doc = include_str!("lib-documentation.md")]
 
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::fmt;
+use std::mem;
 
 use chrono::Duration;
 use chrono::NaiveDateTime;
 use chrono::Utc;
+pub use clock::Clock;
+pub use clock::FixedClock;
+pub use clock::SystemClock;
+pub use date::format_timestamp;
+pub use date::format_timestamp_rfc3339;
+pub use date::parse_date;
+pub use date::parse_date_end;
+pub use date::parse_relative_day;
+pub use date::parse_timestamp;
+pub use date::TIMESTAMP_FORMAT;
+pub use duration::format_duration;
+pub use duration::parse_duration;
+pub use duration::parse_signed_duration;
+use errors::TimestampError;
+use errors::TimestampField;
+pub use groups::Groups;
+pub use labels::Labels;
+pub use labels::UnitLabels;
+use serde::Serialize;
+use serde::Serializer;
+use task::saturating_sub;
+pub use task::Task;
+use unicode_width::UnicodeWidthChar;
+use unicode_width::UnicodeWidthStr;
 
-/// A wrapper over a `HashMap<String, NaiveDateTime>` ([`NaiveDateTime`]).
+mod clock;
+mod date;
+mod duration;
+pub mod errors;
+mod groups;
+mod labels;
+mod task;
+
+/// The timestamp of a task that was never done, see [`Tasks::never_done`].
+///
+/// It's the unix epoch, so it stays a regular timestamp that sorts as the
+/// oldest one, and that any other program reading the data file understands.
+pub const NEVER: NaiveDateTime = NaiveDateTime::UNIX_EPOCH;
+
+const HOURS_IN_DAY: i64 = 24;
+const MINUTES_IN_HOUR: i64 = 60;
+const SECONDS_IN_MINUTE: i64 = 60;
+
+/// A wrapper over a `HashMap<String, Task>`, where each [`Task`] mainly holds
+/// the [`NaiveDateTime`] it was last done at.
 ///
 /// Meant to be used for updating, removing and filtering tasks.
 ///
@@ -17,7 +66,36 @@ use chrono::Utc;
 ///
 /// For any data manipulation not implemented for [`Tasks`], feel free to
 /// manipulate the `HashMap` directly beforehand.
-pub struct Tasks(HashMap<String, NaiveDateTime>);
+///
+/// Two [`Tasks`] are equal when they have the same tasks with the same
+/// records, regardless of the order they were added in.
+#[derive(Clone, Default, PartialEq)]
+pub struct Tasks(HashMap<String, Task>);
+
+/// The order tasks are output in: the most recently done first, and those done
+/// at the same time by name.
+fn by_age(a: (&str, Duration), b: (&str, Duration)) -> Ordering {
+    a.1.cmp(&b.1).then_with(|| a.0.cmp(b.0))
+}
+
+/// Whether `task` was last done more than `cutoff` before `now`, the one
+/// boundary that every "older than" filter goes by.
+fn is_older(task: &Task, cutoff: Duration, now: NaiveDateTime) -> bool {
+    now - task.timestamp > cutoff
+}
+
+/// Tasks are listed sorted by name, so that the same tasks always debug the
+/// same, like in snapshot tests.
+impl fmt::Debug for Tasks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sorted: BTreeMap<&str, &Task> = self
+            .0
+            .iter()
+            .map(|(key, task)| (key.as_str(), task))
+            .collect();
+        f.debug_tuple("Tasks").field(&sorted).finish()
+    }
+}
 
 /// This is only useful if you can conveniently create a [`HashMap<String,
 /// NaiveDateTime>`]. The library is made with the intention to be used with
@@ -28,9 +106,62 @@ pub struct Tasks(HashMap<String, NaiveDateTime>);
 /// 2. You don't don't get the data from a file and create it programmatically,
 ///    making you not have to deserialize string data and therefore allowing you
 ///    to create [`NaiveDateTime`]s straight up.
+///
+/// It's also the fastest way to make a lot of [`Tasks`] at once, since
+/// there's nothing left to parse or check, unlike with the
+/// `TryFrom<HashMap<String, String>>`. The `construct` benchmark shows by how
+/// much.
 impl From<HashMap<String, NaiveDateTime>> for Tasks {
     fn from(value: HashMap<String, NaiveDateTime>) -> Self {
-        Self(value)
+        Self(
+            value
+                .into_iter()
+                .map(|(key, timestamp)| (key, Task::new(timestamp)))
+                .collect(),
+        )
+    }
+}
+
+/// Same as the `From<HashMap<String, NaiveDateTime>>`, for when the tasks are
+/// easier to write out in order. If a task is given more than once, the last
+/// timestamp wins.
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use loago::Tasks;
+/// let morning = NaiveDate::from_ymd_opt(2023, 12, 20)
+///     .unwrap()
+///     .and_hms_opt(9, 0, 0)
+///     .unwrap();
+/// let tasks = Tasks::from(vec![
+///     (
+///         String::from("dust"),
+///         NaiveDate::MIN.and_hms_opt(0, 0, 0).unwrap(),
+///     ),
+///     (String::from("dust"), morning),
+/// ]);
+/// assert_eq!(tasks.timestamps().collect::<Vec<_>>(), [("dust", morning)]);
+/// ```
+impl From<Vec<(String, NaiveDateTime)>> for Tasks {
+    fn from(value: Vec<(String, NaiveDateTime)>) -> Self {
+        Self(
+            value
+                .into_iter()
+                .map(|(key, timestamp)| (key, Task::new(timestamp)))
+                .collect(),
+        )
+    }
+}
+
+/// Same as the `From<Vec<(String, NaiveDateTime)>>`, cloning the names.
+impl From<&[(String, NaiveDateTime)]> for Tasks {
+    fn from(value: &[(String, NaiveDateTime)]) -> Self {
+        Self(
+            value
+                .iter()
+                .map(|(key, timestamp)| (key.clone(), Task::new(*timestamp)))
+                .collect(),
+        )
     }
 }
 
@@ -44,33 +175,130 @@ impl From<HashMap<String, NaiveDateTime>> for Tasks {
 /// possibilities.
 ///
 /// # Errors
-/// Expects this format: `%Y-%m-%dT%H:%M:%S%.f`, as defined by
-/// [`NaiveDateTime`]'s documentation on the `parse` method in the
-/// [`std::str::FromStr`].
+/// Expects the [`TIMESTAMP_FORMAT`], `%Y-%m-%dT%H:%M:%S%.f`, or RFC 3339.
 ///
-/// So, the only error is that parse failing.
+/// So, the only error is [`parse_timestamp`] failing, as a [`TimestampError`]
+/// that says which task's timestamp it was.
 ///
 /// # Examples
-/// More helpfully, that format is automatically used when you format a
-/// [`NaiveDateTime`] by using its [`fmt::Debug`] implementation.
+/// More helpfully, [`format_timestamp`] formats a [`NaiveDateTime`] that
+/// way.
 /// ```
 /// use std::collections::HashMap;
 ///
+/// use loago::format_timestamp;
 /// use loago::Tasks;
 /// let now = chrono::Utc::now().naive_utc();
-/// let timestamp = format!("{:?}", now);
+/// let timestamp = format_timestamp(now);
 /// let mut map = HashMap::new();
 /// map.insert(String::from("task-name"), timestamp);
 /// let tasks: Tasks = Tasks::try_from(map).unwrap();
 /// ```
 impl TryFrom<HashMap<String, String>> for Tasks {
-    type Error = chrono::format::ParseError;
+    type Error = TimestampError;
 
     fn try_from(value: HashMap<String, String>) -> Result<Self, Self::Error> {
-        let mut map = HashMap::new();
+        let mut map = HashMap::with_capacity(value.len());
+        for (key, timestamp) in value {
+            let timestamp = TimestampError::parse(
+                &key,
+                TimestampField::Timestamp,
+                timestamp,
+            )?;
+            map.insert(key, Task::new(timestamp));
+        }
+        Ok(Tasks(map))
+    }
+}
+
+/// Task names paired with the reason their timestamp failed to parse, as
+/// returned by [`Tasks::try_from_lenient`].
+pub type InvalidTimestamps = Vec<(String, chrono::format::ParseError)>;
+
+/// Which version of a task wins when [`Tasks::merge`] finds it on both sides.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// The one that was done more recently.
+    #[default]
+    Newer,
+    /// The one that's already there, no matter when either was done.
+    PreferSelf,
+    /// The one being merged in, no matter when either was done.
+    PreferOther,
+}
+
+impl Tasks {
+    /// Like `TryFrom<HashMap<String, String>>`, except that entries with a
+    /// timestamp that fails to parse are skipped instead of failing the whole
+    /// conversion.
+    ///
+    /// The skipped entries are returned alongside, as pairs of task name and
+    /// the reason their timestamp failed to parse, so that you can warn about
+    /// them.
+    ///
+    /// Keep in mind that writing the resulting [`Tasks`] back into the data
+    /// file loses the skipped entries.
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// use loago::Tasks;
+    /// let mut map = HashMap::new();
+    /// map.insert(String::from("dust"), String::from("2023-12-20T00:00:00"));
+    /// map.insert(String::from("vacuum"), String::from("yesterday"));
+    /// let (tasks, invalid) = Tasks::try_from_lenient(map);
+    /// assert_eq!(invalid.len(), 1);
+    /// assert_eq!(invalid[0].0, "vacuum");
+    /// ```
+    pub fn try_from_lenient(
+        value: HashMap<String, String>,
+    ) -> (Self, InvalidTimestamps) {
+        let mut map = HashMap::with_capacity(value.len());
+        let mut invalid = Vec::new();
         for (key, timestamp) in value {
-            let timestamp = timestamp.parse()?;
-            map.insert(key, timestamp);
+            match parse_timestamp(&timestamp) {
+                Ok(timestamp) => {
+                    map.insert(key, Task::new(timestamp));
+                },
+                Err(error) => invalid.push((key, error)),
+            }
+        }
+        (Tasks(map), invalid)
+    }
+
+    /// Like `TryFrom<HashMap<String, String>>`, except that only the
+    /// specified tasks are kept, and only their timestamps are parsed. For
+    /// when only a few tasks out of many are needed.
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// use loago::Tasks;
+    /// let mut map = HashMap::new();
+    /// map.insert(String::from("dust"), String::from("2023-12-20T00:00:00"));
+    /// map.insert(String::from("vacuum"), String::from("yesterday"));
+    /// let tasks = Tasks::try_from_filtered(map, &["dust", "mop"]).unwrap();
+    /// assert!(tasks.contains("dust"));
+    /// assert!(!tasks.contains("vacuum"));
+    /// ```
+    ///
+    /// # Errors
+    /// Same as `TryFrom<HashMap<String, String>>`, but only for the specified
+    /// tasks.
+    pub fn try_from_filtered(
+        mut value: HashMap<String, String>,
+        tasks: &[impl AsRef<str>],
+    ) -> Result<Self, TimestampError> {
+        let mut map = HashMap::with_capacity(tasks.len());
+        for task in tasks {
+            if let Some((key, timestamp)) = value.remove_entry(task.as_ref()) {
+                let timestamp = TimestampError::parse(
+                    &key,
+                    TimestampField::Timestamp,
+                    timestamp,
+                )?;
+                map.insert(key, Task::new(timestamp));
+            }
         }
         Ok(Tasks(map))
     }
@@ -79,18 +307,22 @@ impl TryFrom<HashMap<String, String>> for Tasks {
 /// This `From` is useful to convert the data back into a serializable data
 /// structure, for you to then write back to the data file.
 ///
-/// The value `String` in the `HashMap` uses the `%Y-%m-%dT%H:%M:%S%.f` format.
+/// The value `String` in the `HashMap` uses the [`TIMESTAMP_FORMAT`], see
+/// [`format_timestamp`].
 ///
 /// That format is expected by `TryFrom<HashMap<String, String>> for Tasks`, so
 /// this `From` comes hand-in-hand with it in terms of making the full binary
 /// application: getting data from a file, mutating it, and then writing the new
 /// data to the file.
+///
+/// Only the timestamps are kept: anything else stored in each [`Task`], like
+/// its tags, is lost. Use [`Tasks::iter`] to serialize the whole records.
 impl From<Tasks> for HashMap<String, String> {
     fn from(value: Tasks) -> Self {
         value
             .0
             .into_iter()
-            .map(|(key, timestamp)| (key, format!("{:?}", timestamp)))
+            .map(|(key, task)| (key, format_timestamp(task.timestamp)))
             .collect()
     }
 }
@@ -98,60 +330,524 @@ impl From<Tasks> for HashMap<String, String> {
 impl Tasks {
     /// Update a task's [`NaiveDateTime`] timestamp to that of right [`now`].
     /// If the given task didn't exist prior, it will be created.
-    pub fn update(&mut self, task: impl Into<String>) {
-        self.0.insert(task.into(), now());
+    ///
+    /// Anything else stored about an existing task, like its tags, is kept.
+    ///
+    /// Returns the task's previous timestamp, or `None` if it got created,
+    /// like [`HashMap::insert`].
+    pub fn update(&mut self, task: impl Into<String>) -> Option<NaiveDateTime> {
+        self.update_with(task, &SystemClock)
+    }
+
+    /// [`Tasks::update`], with the time coming from `clock`.
+    pub fn update_with(
+        &mut self,
+        task: impl Into<String>,
+        clock: &impl Clock,
+    ) -> Option<NaiveDateTime> {
+        self.update_at(task, clock.now())
     }
 
     /// Update multiple tasks' [`NaiveDateTime`] timestamps to that of right
     /// [`now`]. If any of the given tasks didn't exist prior, they will be
     /// created.
+    ///
+    /// Returns the names of the created tasks, in the order they were given.
     pub fn update_multiple(
         &mut self,
         tasks: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Vec<String> {
+        self.update_multiple_with(tasks, &SystemClock)
+    }
+
+    /// [`Tasks::update_multiple`], with the time coming from `clock`. Every
+    /// task gets the same timestamp.
+    pub fn update_multiple_with(
+        &mut self,
+        tasks: impl IntoIterator<Item = impl Into<String>>,
+        clock: &impl Clock,
+    ) -> Vec<String> {
+        self.update_multiple_at(tasks, clock.now())
+    }
+
+    /// Update a task's [`NaiveDateTime`] timestamp to `when`, creating it if
+    /// it didn't exist prior. Like with [`Tasks::update`], anything else
+    /// stored about an existing task is kept.
+    ///
+    /// Returns the task's previous timestamp, or `None` if it got created.
+    pub fn update_at(
+        &mut self,
+        task: impl Into<String>,
+        when: NaiveDateTime,
+    ) -> Option<NaiveDateTime> {
+        match self.0.entry(task.into()) {
+            Entry::Occupied(mut entry) => {
+                Some(mem::replace(&mut entry.get_mut().timestamp, when))
+            },
+            Entry::Vacant(entry) => {
+                entry.insert(Task::new(when));
+                None
+            },
+        }
+    }
+
+    /// Update multiple tasks' [`NaiveDateTime`] timestamps to `when`, creating
+    /// the ones that didn't exist prior.
+    ///
+    /// Returns the names of the created tasks, in the order they were given.
+    pub fn update_multiple_at(
+        &mut self,
+        tasks: impl IntoIterator<Item = impl Into<String>>,
+        when: NaiveDateTime,
+    ) -> Vec<String> {
+        let mut created = Vec::new();
+        for task in tasks {
+            let task = task.into();
+            if self.update_at(task.clone(), when).is_none() {
+                created.push(task);
+            }
+        }
+        created
+    }
+
+    /// Set every task's [`NaiveDateTime`] timestamp to the one it's paired
+    /// with, creating the ones that didn't exist prior, like restoring them
+    /// from a backup. Existing timestamps are overwritten even if they're
+    /// newer, while anything else stored about those tasks is kept.
+    pub fn extend(
+        &mut self,
+        items: impl IntoIterator<Item = (String, NaiveDateTime)>,
+    ) {
+        for (task, timestamp) in items {
+            self.update_at(task, timestamp);
+        }
+    }
+
+    /// Update every existing task's [`NaiveDateTime`] timestamp to that of
+    /// right [`now`]. Unlike [`Tasks::update_multiple`], no tasks are created.
+    pub fn update_all(&mut self) {
+        self.update_all_with(&SystemClock);
+    }
+
+    /// [`Tasks::update_all`], with the time coming from `clock`.
+    pub fn update_all_with(&mut self, clock: &impl Clock) {
+        let now = clock.now();
+        for task in self.0.values_mut() {
+            task.timestamp = now;
+        }
+    }
+
+    /// Mark tasks as never done, by setting their timestamp to [`NEVER`]. If
+    /// any of the given tasks didn't exist prior, they will be created.
+    ///
+    /// The `output` methods don't treat these tasks specially: they show up as
+    /// done a very long time ago. Compare [`Task::timestamp`] to [`NEVER`] in
+    /// [`Tasks::output_records`] to display them differently.
+    ///
+    /// The tasks that get created are [`Task::created`] right [`now`].
+    pub fn never_done(
+        &mut self,
+        tasks: impl IntoIterator<Item = impl Into<String>>,
     ) {
         let now = now();
         for task in tasks {
-            self.0.insert(task.into(), now);
+            let task = task.into();
+            if self.update_at(task.clone(), NEVER).is_none() {
+                self.set_created(&task, now);
+            }
         }
     }
 
     /// Remove a task from the list.
-    pub fn remove(&mut self, task: &str) {
-        self.0.remove(task);
+    ///
+    /// Returns whether the task existed.
+    pub fn remove(&mut self, task: &str) -> bool {
+        self.0.remove(task).is_some()
     }
 
     /// Remove multiple tasks from the list.
-    pub fn remove_multiple(&mut self, tasks: &[impl AsRef<str>]) {
+    ///
+    /// Returns the names of the tasks that existed and got removed, in the
+    /// order they were given.
+    pub fn remove_multiple(
+        &mut self,
+        tasks: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> Vec<String> {
+        let mut removed = Vec::new();
         for task in tasks {
-            self.0.remove(task.as_ref());
+            if let Some((task, _)) = self.0.remove_entry(task.as_ref()) {
+                removed.push(task);
+            }
         }
+        removed
     }
 
     /// Only keep the specified task in the list, removing all the other ones.
     pub fn keep(&mut self, task: impl Into<String>) {
-        let task = task.into();
-        let mut map = HashMap::new();
-        if self.0.contains_key(&task) {
-            let timestamp = self.0[&task];
-            map.insert(task, timestamp);
-        };
-        self.0 = map;
+        let kept = self.0.remove_entry(&task.into());
+        self.0.clear();
+        self.0.extend(kept);
     }
 
     /// Only keep the specified tasks in the list, removing all the other ones.
+    ///
+    /// Returns the names of the removed tasks, in no particular order.
     pub fn keep_multiple(
         &mut self,
         tasks: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Vec<String> {
+        let kept: HashSet<String> = tasks.into_iter().map(Into::into).collect();
+        let mut removed = Vec::new();
+        self.0.retain(|task, _| {
+            let keep = kept.contains(task);
+            if !keep {
+                removed.push(task.clone());
+            }
+            keep
+        });
+        removed
+    }
+
+    /// Add tags to a task. Tags it already has are kept.
+    ///
+    /// Returns `false` if the task doesn't exist.
+    pub fn tag(
+        &mut self,
+        task: &str,
+        tags: impl IntoIterator<Item = impl Into<String>>,
+    ) -> bool {
+        let Some(task) = self.0.get_mut(task) else {
+            return false;
+        };
+        task.tags.extend(tags.into_iter().map(Into::into));
+        true
+    }
+
+    /// Remove tags from a task, or all of its tags if none are specified.
+    ///
+    /// Returns `false` if the task doesn't exist.
+    pub fn untag(&mut self, task: &str, tags: &[impl AsRef<str>]) -> bool {
+        let Some(task) = self.0.get_mut(task) else {
+            return false;
+        };
+        if tags.is_empty() {
+            task.tags.clear();
+        }
+        for tag in tags {
+            task.tags.remove(tag.as_ref());
+        }
+        true
+    }
+
+    /// Set how often a task is meant to be done, or clear it with `None`.
+    ///
+    /// Returns `false` if the task doesn't exist.
+    pub fn set_interval(
+        &mut self,
+        task: &str,
+        interval: Option<Duration>,
+    ) -> bool {
+        let Some(task) = self.0.get_mut(task) else {
+            return false;
+        };
+        task.interval = interval;
+        true
+    }
+
+    /// Set when a task was first done, see [`Task::created`].
+    ///
+    /// Returns `false` if the task doesn't exist.
+    pub fn set_created(&mut self, task: &str, created: NaiveDateTime) -> bool {
+        let Some(task) = self.0.get_mut(task) else {
+            return false;
+        };
+        task.created = created;
+        true
+    }
+
+    /// Only keep the tasks with names that contain `query`, ignoring case.
+    pub fn keep_matching(&mut self, query: &str) {
+        let query = query.to_lowercase();
+        self.retain(|task, _| task.to_lowercase().contains(&query));
+    }
+
+    /// Only keep the tasks that the predicate returns `true` for, getting each
+    /// task's name and record.
+    pub fn retain<F>(&mut self, mut predicate: F)
+    where
+        F: FnMut(&str, &Task) -> bool,
+    {
+        self.0.retain(|key, task| predicate(key, task));
+    }
+
+    /// Only keep the tasks that were last done more than `cutoff` before `now`.
+    /// A task done exactly `cutoff` ago is not kept, see [`Tasks::newer_than`].
+    pub fn older_than(&mut self, cutoff: Duration, now: NaiveDateTime) {
+        self.retain(|_, task| is_older(task, cutoff, now));
+    }
+
+    /// Only keep the tasks that were last done at most `cutoff` before `now`,
+    /// including a task done exactly `cutoff` ago. The exact opposite of
+    /// [`Tasks::older_than`], so every task is kept by one of the two.
+    pub fn newer_than(&mut self, cutoff: Duration, now: NaiveDateTime) {
+        self.retain(|_, task| !is_older(task, cutoff, now));
+    }
+
+    /// Only keep the tasks that are overdue at `now`, see [`Task::is_overdue`].
+    /// Tasks without an interval are never overdue, so they're all removed.
+    pub fn keep_overdue(&mut self, now: NaiveDateTime) {
+        self.retain(|_, task| task.is_overdue(now - task.timestamp));
+    }
+
+    /// Only keep the tasks that were last done in the window from `since` to
+    /// `until`, both included. A bound that's `None` leaves that side of the
+    /// window open.
+    pub fn done_between(
+        &mut self,
+        since: Option<NaiveDateTime>,
+        until: Option<NaiveDateTime>,
     ) {
-        let mut map = HashMap::new();
-        for task in tasks {
-            let task = task.into();
-            if self.0.contains_key(&task) {
-                let timestamp = self.0[&task];
-                map.insert(task, timestamp);
+        self.retain(|_, task| {
+            since.is_none_or(|since| task.timestamp >= since)
+                && until.is_none_or(|until| task.timestamp <= until)
+        });
+    }
+
+    /// Remove the tasks that [`Tasks::older_than`] would keep, returning the
+    /// names of the removed ones in no particular order.
+    pub fn remove_older_than(
+        &mut self,
+        cutoff: Duration,
+        now: NaiveDateTime,
+    ) -> Vec<String> {
+        let older: Vec<String> = self
+            .iter()
+            .filter(|(_, task)| is_older(task, cutoff, now))
+            .map(|(key, _)| key.to_owned())
+            .collect();
+        self.remove_multiple(&older)
+    }
+
+    /// Only keep the tasks that have at least one of the specified tags,
+    /// removing all the other ones. Tasks without tags are always removed.
+    pub fn keep_tagged(&mut self, tags: &[impl AsRef<str>]) {
+        self.0.retain(|_, task| {
+            tags.iter().any(|tag| task.tags.contains(tag.as_ref()))
+        });
+    }
+
+    /// Give every task that doesn't have an interval this one, leaving the
+    /// tasks with their own alone.
+    pub fn fill_intervals(&mut self, interval: Duration) {
+        for task in self.0.values_mut() {
+            task.interval.get_or_insert(interval);
+        }
+    }
+
+    /// Add every task from `other` into this [`Tasks`].
+    ///
+    /// When a task exists in both, the `strategy` picks which one wins, with
+    /// everything else stored about it. Ties go to the existing one.
+    pub fn merge(&mut self, other: Tasks, strategy: MergeStrategy) {
+        for (key, task) in other.0 {
+            let keep_existing = match (self.0.get(&key), strategy) {
+                (None, _) => false,
+                (Some(existing), MergeStrategy::Newer) => {
+                    existing.timestamp >= task.timestamp
+                },
+                (Some(_), MergeStrategy::PreferSelf) => true,
+                (Some(_), MergeStrategy::PreferOther) => false,
+            };
+            if !keep_existing {
+                self.0.insert(key, task);
+            }
+        }
+    }
+
+    /// Rename a task, keeping everything stored about it. If there already is
+    /// a task called `new`, whichever of the two was done more recently is
+    /// kept, like with [`MergeStrategy::Newer`].
+    ///
+    /// Returns whether the task exists.
+    pub fn rename(&mut self, old: &str, new: impl Into<String>) -> bool {
+        let Some(task) = self.0.remove(old) else {
+            return false;
+        };
+        self.merge(
+            Self(HashMap::from([(new.into(), task)])),
+            MergeStrategy::Newer,
+        );
+        true
+    }
+
+    /// [`Tasks::rename`] every `(old, new)` pair, one after the other, so that
+    /// a later pair can rename what an earlier one renamed to.
+    ///
+    /// Returns how many tasks were renamed, not counting the ones that didn't
+    /// exist.
+    pub fn rename_multiple(
+        &mut self,
+        pairs: impl IntoIterator<Item = (impl AsRef<str>, impl Into<String>)>,
+    ) -> usize {
+        let mut renamed = 0;
+        for (old, new) in pairs {
+            if self.rename(old.as_ref(), new) {
+                renamed += 1;
+            }
+        }
+        renamed
+    }
+
+    /// Exchange the names of two tasks, keeping everything stored about them,
+    /// so that `a` gets `b`'s timestamp and the rest, and the other way
+    /// around. Unlike two [`Tasks::rename`]s, neither collapses into the other.
+    ///
+    /// Returns whether both tasks exist. If either doesn't, nothing changes.
+    pub fn swap(&mut self, a: &str, b: &str) -> bool {
+        if !self.contains(a) || !self.contains(b) {
+            return false;
+        }
+        if a == b {
+            return true;
+        }
+        let first = self.0.remove(a).expect("checked that it exists");
+        let second = mem::replace(
+            self.0.get_mut(b).expect("checked that it exists"),
+            first,
+        );
+        self.0.insert(a.to_owned(), second);
+        true
+    }
+
+    /// Rename every task starting with `old_prefix` to start with
+    /// `new_prefix` instead, keeping everything stored about them. A task
+    /// that ends up with the name of another one, whether that one was renamed
+    /// too or not, collapses into it: whichever of the two was done more
+    /// recently is kept, like with [`MergeStrategy::Newer`].
+    ///
+    /// Returns how many tasks were renamed.
+    pub fn rename_prefix(
+        &mut self,
+        old_prefix: &str,
+        new_prefix: &str,
+    ) -> usize {
+        let matching: Vec<String> = self
+            .0
+            .keys()
+            .filter(|key| key.starts_with(old_prefix))
+            .cloned()
+            .collect();
+        // Taken out all at once, so that the new names can't be renamed again.
+        let renamed: HashMap<String, Task> = matching
+            .iter()
+            .filter_map(|key| self.0.remove_entry(key))
+            .fold(HashMap::new(), |mut renamed, (key, task)| {
+                let key = format!("{new_prefix}{}", &key[old_prefix.len()..]);
+                match renamed.get(&key) {
+                    Some(existing) if existing.timestamp >= task.timestamp => {
+                    },
+                    _ => {
+                        renamed.insert(key, task);
+                    },
+                }
+                renamed
+            });
+        self.merge(Self(renamed), MergeStrategy::Newer);
+        matching.len()
+    }
+
+    /// Move when a task was last done by `delta`, which is negative to move it
+    /// earlier. The timestamp stops at the earliest or latest one that a
+    /// [`NaiveDateTime`] can be, instead of overflowing.
+    ///
+    /// Returns whether the task exists.
+    pub fn adjust(&mut self, task: &str, delta: Duration) -> bool {
+        let Some(record) = self.0.get_mut(task) else {
+            return false;
+        };
+        record.timestamp = record
+            .timestamp
+            .checked_add_signed(delta)
+            .unwrap_or(if delta < Duration::zero() {
+                NaiveDateTime::MIN
+            } else {
+                NaiveDateTime::MAX
+            });
+        true
+    }
+
+    /// Put off a task with an interval, so that it's only due `duration` after
+    /// `now`, if it wasn't going to be due later than that anyway. A task
+    /// without an interval is never due, so it's moved `duration` later
+    /// instead, like with [`Tasks::adjust`].
+    ///
+    /// Returns whether the task exists.
+    pub fn snooze(
+        &mut self,
+        task: &str,
+        duration: Duration,
+        now: NaiveDateTime,
+    ) -> bool {
+        let Some(record) = self.0.get(task) else {
+            return false;
+        };
+        let Some(interval) = record.interval else {
+            return self.adjust(task, duration);
+        };
+        // As if it was done right when it needs to have been, to be due then.
+        if let Some(snoozed) = now.checked_add_signed(duration - interval) {
+            let delta = snoozed - record.timestamp;
+            if delta > Duration::zero() {
+                self.adjust(task, delta);
             }
         }
-        self.0 = map;
+        true
+    }
+
+    /// Whether there are no tasks at all.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Whether a task with this name exists.
+    pub fn contains(&self, task: &str) -> bool {
+        self.0.contains_key(task)
+    }
+
+    /// The names that no task has, in the order they were given, like to
+    /// warn about before doing the tasks with them.
+    ///
+    /// ```
+    /// use loago::Tasks;
+    /// let mut tasks = Tasks::default();
+    /// tasks.update("dust");
+    /// assert_eq!(tasks.missing(["dust", "mop"]), ["mop"]);
+    /// ```
+    pub fn missing<'a>(
+        &self,
+        names: impl IntoIterator<Item = &'a str>,
+    ) -> Vec<&'a str> {
+        names
+            .into_iter()
+            .filter(|name| !self.contains(name))
+            .collect()
+    }
+
+    /// Iterate over every task's name and its record, in no particular order.
+    ///
+    /// Useful for serializing everything that's known about the tasks, unlike
+    /// `From<Tasks> for HashMap<String, String>`.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Task)> {
+        self.0.iter().map(|(key, task)| (key.as_str(), task))
+    }
+
+    /// Iterate over every task's name and when it was last done, in no
+    /// particular order, without consuming or stringifying anything like
+    /// `From<Tasks> for HashMap<String, String>` does.
+    ///
+    /// This is read-only: change the tasks with the other methods.
+    pub fn timestamps(&self) -> impl Iterator<Item = (&str, NaiveDateTime)> {
+        self.iter().map(|(key, task)| (key, task.timestamp))
     }
 
     /// Convert this [`Tasks`] into a [`OutputTasks`], meant to be used for
@@ -162,7 +858,55 @@ impl Tasks {
     ///
     /// Displays the time difference of each task in days.
     pub fn output_days(self) -> OutputTasks {
-        self.output(|duration| duration.num_days().to_string())
+        self.output(days)
+    }
+
+    /// Convert this [`Tasks`] into a [`OutputTasks`], meant to be used for
+    /// displaying the final data to the user.
+    ///
+    /// Assumes you're checking how long ago the tasks were done compared to
+    /// [`now`].
+    ///
+    /// Displays the time difference of each task in whole weeks. The tasks are
+    /// still sorted by the exact time difference.
+    pub fn output_weeks(self) -> OutputTasks {
+        self.output(weeks)
+    }
+
+    /// Convert this [`Tasks`] into a [`OutputTasks`], meant to be used for
+    /// displaying the final data to the user.
+    ///
+    /// Assumes you're checking how long ago the tasks were done compared to
+    /// [`now`].
+    ///
+    /// Displays the time difference of each task in this format: `{days}d
+    /// {hours}h {minutes}m`. Leftover seconds are truncated.
+    pub fn output_minutes(self) -> OutputTasks {
+        self.output(days_hours_minutes)
+    }
+
+    /// Convert this [`Tasks`] into a [`OutputTasks`], meant to be used for
+    /// displaying the final data to the user.
+    ///
+    /// Assumes you're checking how long ago the tasks were done compared to
+    /// [`now`].
+    ///
+    /// Displays the time difference of each task in this format: `{days}d
+    /// {hours}h {minutes}m {seconds}s`.
+    pub fn output_seconds(self) -> OutputTasks {
+        self.output(days_hours_minutes_seconds)
+    }
+
+    /// Convert this [`Tasks`] into a [`OutputTasks`], meant to be used for
+    /// displaying the final data to the user.
+    ///
+    /// Assumes you're checking how long ago the tasks were done compared to
+    /// [`now`].
+    ///
+    /// Displays the time difference of each task in the single biggest unit
+    /// that fits, as described in [`humanize`].
+    pub fn output_auto(self) -> OutputTasks {
+        self.output(humanize)
     }
 
     /// Convert this [`Tasks`] into a [`OutputTasks`], meant to be used for
@@ -195,18 +939,296 @@ impl Tasks {
     where
         F: Fn(Duration) -> String,
     {
-        type KeyToDuration = (String, Duration);
-        let mut output: Vec<KeyToDuration> = self
-            .0
-            .into_iter()
-            .map(|(key, timestamp)| (key, now - timestamp))
-            .collect();
-        output.sort_by_key(|(_, diff_days)| *diff_days);
-        let output: Vec<KeyToDisplay> = output
+        self.output_records_when(now, |_, duration| to_string(duration))
+    }
+
+    /// Convert this [`Tasks`] into a [`OutputTasks`], meant to be used for
+    /// displaying the final data to the user.
+    ///
+    /// Assumes you're checking how long ago the tasks were done compared to
+    /// [`now`].
+    ///
+    /// See [`Tasks::output_records_when`].
+    pub fn output_records<F>(self, to_string: F) -> OutputTasks
+    where
+        F: Fn(&Task, Duration) -> String,
+    {
+        self.output_records_when(now(), to_string)
+    }
+
+    /// Like [`Tasks::output_when`], except that the closure also gets each
+    /// task's record, so that it can display more than how long ago the task
+    /// was done. For example, when it's [`due`].
+    ///
+    /// The tasks are sorted the same way [`Tasks::output_when`] sorts them.
+    pub fn output_records_when<F>(
+        self,
+        now: NaiveDateTime,
+        to_string: F,
+    ) -> OutputTasks
+    where
+        F: Fn(&Task, Duration) -> String,
+    {
+        let output: Vec<KeyToDisplay> = self
+            .ages_when(now)
             .into_iter()
-            .map(|(key, duration)| (key, to_string(duration)))
+            .map(|age| {
+                let display = to_string(&age.task, age.elapsed);
+                (age.name, display)
+            })
             .collect();
-        OutputTasks(output)
+        OutputTasks::from(output)
+    }
+
+    /// Like [`Tasks::output_due_when`], compared to [`now`].
+    pub fn output_due<F>(self, to_string: F) -> OutputTasks
+    where
+        F: Fn(Duration) -> String,
+    {
+        self.output_due_when(now(), to_string)
+    }
+
+    /// Like [`Tasks::output_when`], except that tasks with an interval show
+    /// whether they're overdue at `now` instead, along with how long until or
+    /// since they were due, like `due in 2 days` or `overdue by 3 days`, see
+    /// [`due`]. Tasks without an interval show how long ago they were done,
+    /// the same as with [`Tasks::output_when`].
+    pub fn output_due_when<F>(
+        self,
+        now: NaiveDateTime,
+        to_string: F,
+    ) -> OutputTasks
+    where
+        F: Fn(Duration) -> String,
+    {
+        self.output_records_when(now, |task, elapsed| {
+            task.due_or_ago(elapsed, &to_string, &to_string)
+        })
+    }
+
+    /// Convert this [`Tasks`] into a [`TaskAge`] for every task, to render
+    /// them in your own way, with all of the details still available.
+    ///
+    /// Assumes you're checking how long ago the tasks were done compared to
+    /// [`now`].
+    pub fn ages(self) -> Vec<TaskAge> {
+        self.ages_when(now())
+    }
+
+    /// Like [`Tasks::ages`], but with the [`NaiveDateTime`] considered "now"
+    /// passed in, like in [`Tasks::output_when`].
+    ///
+    /// The tasks are sorted the same way [`Tasks::output_when`] sorts them,
+    /// with the ones done at the same time sorted by name.
+    pub fn ages_when(self, now: NaiveDateTime) -> Vec<TaskAge> {
+        let mut ages: Vec<TaskAge> = self
+            .0
+            .into_iter()
+            .map(|(name, task)| TaskAge {
+                elapsed: now - task.timestamp,
+                name,
+                task,
+            })
+            .collect();
+        ages.sort_by(|a, b| by_age((&a.name, a.elapsed), (&b.name, b.elapsed)));
+        ages
+    }
+
+    /// Every task's name and how long ago it was done compared to `now`,
+    /// sorted the same way [`Tasks::output_when`] sorts them, for rendering
+    /// them without converting them to [`OutputTasks`] first.
+    pub fn iter_sorted(
+        &self,
+        now: NaiveDateTime,
+    ) -> impl Iterator<Item = (&str, Duration)> {
+        let mut sorted: Vec<(&str, Duration)> = self
+            .0
+            .iter()
+            .map(|(name, task)| (name.as_str(), now - task.timestamp))
+            .collect();
+        sorted.sort_by(|&a, &b| by_age(a, b));
+        sorted.into_iter()
+    }
+
+    /// Every task's name and how long ago it was done, sorted the same way
+    /// [`Tasks::output_when`] sorts them, for formatting and comparing them
+    /// in your own way.
+    ///
+    /// Assumes you're checking how long ago the tasks were done compared to
+    /// [`now`].
+    pub fn durations(&self) -> Vec<(String, Duration)> {
+        self.durations_when(now())
+    }
+
+    /// Like [`Tasks::durations`], but with the [`NaiveDateTime`] considered
+    /// "now" passed in, like in [`Tasks::output_when`]. An owned
+    /// [`Tasks::iter_sorted`].
+    pub fn durations_when(
+        &self,
+        now: NaiveDateTime,
+    ) -> Vec<(String, Duration)> {
+        self.iter_sorted(now)
+            .map(|(name, elapsed)| (name.to_owned(), elapsed))
+            .collect()
+    }
+
+    /// How long ago a task was done, compared to [`now`], if it exists.
+    pub fn get(&self, task: &str) -> Option<Duration> {
+        self.get_when(task, now())
+    }
+
+    /// Like [`Tasks::get`], but with the [`NaiveDateTime`] considered "now"
+    /// passed in.
+    pub fn get_when(&self, task: &str, now: NaiveDateTime) -> Option<Duration> {
+        self.0.get(task).map(|record| now - record.timestamp)
+    }
+
+    /// How well every task with an interval is keeping up with it, compared to
+    /// [`now`]. Tasks without an interval are left out.
+    ///
+    /// See [`Tasks::standings_when`].
+    pub fn standings(self) -> Vec<Standing> {
+        self.standings_when(now())
+    }
+
+    /// Like [`Tasks::standings`], but with the [`NaiveDateTime`] considered
+    /// "now" passed in, like in [`Tasks::output_when`].
+    ///
+    /// The furthest behind tasks come first.
+    pub fn standings_when(self, now: NaiveDateTime) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = self
+            .ages_when(now)
+            .into_iter()
+            .filter_map(|age| {
+                Some(Standing {
+                    interval: age.task.interval?,
+                    elapsed:  age.elapsed,
+                    name:     age.name,
+                })
+            })
+            .collect();
+        standings.sort_by(|a, b| b.ratio().total_cmp(&a.ratio()));
+        standings
+    }
+
+    /// Convert this [`Tasks`] into a [`GroupedOutput`], with a section for
+    /// every tag.
+    ///
+    /// Assumes you're checking how long ago the tasks were done compared to
+    /// [`now`].
+    ///
+    /// See [`Tasks::output_by_tag_when`].
+    pub fn output_by_tag<F>(self, to_string: F) -> GroupedOutput
+    where
+        F: Fn(&Task, Duration) -> String,
+    {
+        self.output_by_tag_when(now(), to_string)
+    }
+
+    /// Convert this [`Tasks`] into a [`GroupedOutput`], with a section for
+    /// every tag, sorted by tag name.
+    ///
+    /// A task with several tags shows up in the section of each one of them.
+    /// Tasks without tags go into a last section called [`UNTAGGED`]. Within a
+    /// section, tasks are sorted the same way [`Tasks::output_when`] sorts
+    /// them.
+    ///
+    /// The closure gets each task's record, like in
+    /// [`Tasks::output_records_when`].
+    pub fn output_by_tag_when<F>(
+        self,
+        now: NaiveDateTime,
+        to_string: F,
+    ) -> GroupedOutput
+    where
+        F: Fn(&Task, Duration) -> String,
+    {
+        let mut sections: BTreeMap<String, HashMap<String, Task>> =
+            BTreeMap::new();
+        let mut untagged = HashMap::new();
+        for (key, task) in self.0 {
+            if task.tags.is_empty() {
+                untagged.insert(key, task);
+                continue;
+            }
+            for tag in &task.tags {
+                sections
+                    .entry(tag.clone())
+                    .or_default()
+                    .insert(key.clone(), task.clone());
+            }
+        }
+        let mut output: Vec<(String, OutputTasks)> = sections
+            .into_iter()
+            .map(|(tag, tasks)| {
+                (tag, Tasks(tasks).output_records_when(now, &to_string))
+            })
+            .collect();
+        if !untagged.is_empty() {
+            output.push((
+                String::from(UNTAGGED),
+                Tasks(untagged).output_records_when(now, &to_string),
+            ));
+        }
+        GroupedOutput(output)
+    }
+
+    /// Same as [`Tasks::output_by_age_when`], counting from [`now`].
+    pub fn output_by_age<F>(
+        self,
+        buckets: &[(String, Duration)],
+        to_string: F,
+    ) -> GroupedOutput
+    where
+        F: Fn(&Task, Duration) -> String,
+    {
+        self.output_by_age_when(now(), buckets, to_string)
+    }
+
+    /// Convert this [`Tasks`] into a [`GroupedOutput`], with a section for
+    /// every one of the `buckets` that has any tasks, in the order given.
+    ///
+    /// A bucket is a header, and how long ago a task has to have been done in
+    /// at most to go into it, like `("this week", Duration::weeks(1))`. Every
+    /// task goes into the first bucket that it fits, so they should be
+    /// ordered from the shortest duration to the longest. Tasks that fit none
+    /// go into a last section called [`OLDER`]. Within a section, tasks are
+    /// sorted the same way [`Tasks::output_when`] sorts them.
+    ///
+    /// The closure gets each task's record, like in
+    /// [`Tasks::output_records_when`].
+    pub fn output_by_age_when<F>(
+        self,
+        now: NaiveDateTime,
+        buckets: &[(String, Duration)],
+        to_string: F,
+    ) -> GroupedOutput
+    where
+        F: Fn(&Task, Duration) -> String,
+    {
+        let mut sections: Vec<HashMap<String, Task>> =
+            vec![HashMap::new(); buckets.len() + 1];
+        for (key, task) in self.0 {
+            let elapsed = now - task.timestamp;
+            let bucket = buckets
+                .iter()
+                .position(|(_, up_to)| elapsed <= *up_to)
+                .unwrap_or(buckets.len());
+            sections[bucket].insert(key, task);
+        }
+        let headers = buckets
+            .iter()
+            .map(|(header, _)| header.as_str())
+            .chain([OLDER]);
+        let output = headers
+            .zip(sections)
+            .filter(|(_, tasks)| !tasks.is_empty())
+            .map(|(header, tasks)| {
+                let tasks = Tasks(tasks).output_records_when(now, &to_string);
+                (header.to_owned(), tasks)
+            })
+            .collect();
+        GroupedOutput(output)
     }
 }
 
@@ -217,34 +1239,549 @@ pub fn now() -> NaiveDateTime {
     Utc::now().naive_utc()
 }
 
+/// Turn a [`Duration`] into a compact string using the biggest unit that
+/// fits into it at least once:
+///
+/// | Duration          | Output  |
+/// |-------------------|---------|
+/// | 1 day or more     | `{n}d`  |
+/// | 1 hour or more    | `{n}h`  |
+/// | 1 minute or more  | `{n}m`  |
+/// | less than that    | `now`   |
+///
+/// The amount is truncated, so 47 hours is `1d`. Negative durations (tasks
+/// done "in the future") are considered `now`. The words are English, see
+/// [`Labels`] for other languages.
+///
+/// ```
+/// use chrono::Duration;
+/// use loago::humanize;
+/// assert_eq!(humanize(Duration::hours(47)), "1d");
+/// assert_eq!(humanize(Duration::minutes(90)), "1h");
+/// assert_eq!(humanize(Duration::seconds(90)), "1m");
+/// assert_eq!(humanize(Duration::seconds(59)), "now");
+/// ```
+pub fn humanize(duration: Duration) -> String {
+    Labels::ENGLISH.humanize(duration)
+}
+
+/// Like [`humanize`], except that the unit is spelled out, in the singular
+/// for exactly one, and weeks are used too:
+///
+/// | Duration          | Output                       |
+/// |-------------------|------------------------------|
+/// | 1 week or more    | `1 week`, `{n} weeks`        |
+/// | 1 day or more     | `1 day`, `{n} days`          |
+/// | 1 hour or more    | `1 hour`, `{n} hours`        |
+/// | 1 minute or more  | `1 minute`, `{n} minutes`    |
+/// | less than that    | `just now`                   |
+///
+/// ```
+/// use chrono::Duration;
+/// use loago::humanize_with_unit;
+/// assert_eq!(humanize_with_unit(Duration::days(15)), "2 weeks");
+/// assert_eq!(humanize_with_unit(Duration::hours(47)), "1 day");
+/// assert_eq!(humanize_with_unit(Duration::seconds(59)), "just now");
+/// ```
+pub fn humanize_with_unit(duration: Duration) -> String {
+    Labels::ENGLISH.humanize_with_unit(duration)
+}
+
+/// Format a [`Duration`] as the amount of whole days in it.
+pub fn days(duration: Duration) -> String {
+    duration.num_days().to_string()
+}
+
+/// Like [`days`], but rounded to the nearest whole day rather than down, so
+/// that 1.6 days is `2`, and 1.5 days too.
+pub fn days_rounded(duration: Duration) -> String {
+    (fractional_days(duration).round() as i64).to_string()
+}
+
+/// Format a [`Duration`] as the amount of days in it, rounded to one decimal
+/// place, like `1.6`.
+pub fn days_tenths(duration: Duration) -> String {
+    format!("{:.1}", (fractional_days(duration) * 10.0).round() / 10.0)
+}
+
+fn fractional_days(duration: Duration) -> f64 {
+    let seconds_in_day = HOURS_IN_DAY * MINUTES_IN_HOUR * SECONDS_IN_MINUTE;
+    duration.num_seconds() as f64 / seconds_in_day as f64
+}
+
+/// Format a [`Duration`] as the amount of whole weeks in it.
+pub fn weeks(duration: Duration) -> String {
+    duration.num_weeks().to_string()
+}
+
+/// Like [`days`], followed by the unit: `1 day`, `2 days`.
+pub fn days_with_unit(duration: Duration) -> String {
+    Labels::ENGLISH.days_with_unit(duration)
+}
+
+/// Describe how a task with an interval stands, given the `interval -
+/// elapsed` time it has `remaining`: `due in {remaining}`, or `overdue by
+/// {remaining}` once that's negative. The amount is formatted by the closure.
+///
+/// ```
+/// use chrono::Duration;
+/// use loago::days_with_unit;
+/// use loago::due;
+/// let interval = Duration::days(7);
+/// let remaining = interval - Duration::days(5);
+/// assert_eq!(due(remaining, days_with_unit), "due in 2 days");
+/// let remaining = interval - Duration::days(10);
+/// assert_eq!(due(remaining, days_with_unit), "overdue by 3 days");
+/// ```
+pub fn due<F>(remaining: Duration, to_string: F) -> String
+where
+    F: Fn(Duration) -> String,
+{
+    Labels::ENGLISH.due(remaining, to_string)
+}
+
+/// Format a [`Duration`] as `{days}d {hours}h {minutes}m`, truncating the
+/// leftover seconds.
+///
+/// Every part is derived from the same total amount of minutes, so the parts
+/// always add back up to it, no matter how large the duration is.
+pub fn days_hours_minutes(duration: Duration) -> String {
+    Labels::ENGLISH.days_hours_minutes(duration)
+}
+
+/// Extends [`days_hours_minutes`] with the seconds remainder:
+/// `{days}d {hours}h {minutes}m {seconds}s`.
+pub fn days_hours_minutes_seconds(duration: Duration) -> String {
+    Labels::ENGLISH.days_hours_minutes_seconds(duration)
+}
+
+/// A [`Duration`] split into the parts that [`days_hours_minutes_seconds`]
+/// shows, as returned by [`duration_parts`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DurationParts {
+    pub days:    i64,
+    /// Past the whole days.
+    pub hours:   i64,
+    /// Past the whole hours.
+    pub minutes: i64,
+    /// Past the whole minutes.
+    pub seconds: i64,
+}
+
+/// Split `duration` into whole days and what's left of them, truncating the
+/// leftover fractions of a second, for showing it without re-parsing what
+/// [`days_hours_minutes_seconds`] returns.
+///
+/// ```
+/// use chrono::Duration;
+/// use loago::duration_parts;
+/// let parts = duration_parts(Duration::hours(25) + Duration::seconds(90));
+/// assert_eq!((parts.days, parts.hours, parts.minutes), (1, 1, 1));
+/// assert_eq!(parts.seconds, 30);
+/// ```
+pub fn duration_parts(duration: Duration) -> DurationParts {
+    let total_minutes = duration.num_minutes();
+    DurationParts {
+        days:    total_minutes / (HOURS_IN_DAY * MINUTES_IN_HOUR),
+        hours:   total_minutes / MINUTES_IN_HOUR % HOURS_IN_DAY,
+        minutes: total_minutes % MINUTES_IN_HOUR,
+        seconds: duration.num_seconds() % SECONDS_IN_MINUTE,
+    }
+}
+
+/// Separate the thousands in every number of `display` with `,`, to make
+/// large day counts easier to read. Meant to go over what the other formatting
+/// functions return.
+///
+/// ```
+/// use loago::group_digits;
+/// assert_eq!(group_digits("1234"), "1,234");
+/// assert_eq!(
+///     group_digits("overdue by 1234567 days"),
+///     "overdue by 1,234,567 days"
+/// );
+/// assert_eq!(group_digits("1000d 23h 59m"), "1,000d 23h 59m");
+/// assert_eq!(group_digits("999"), "999");
+/// ```
+pub fn group_digits(display: &str) -> String {
+    let mut grouped = String::with_capacity(display.len());
+    let mut rest = display;
+    while let Some(start) = rest.find(|c: char| c.is_ascii_digit()) {
+        grouped.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        for (index, digit) in rest[..end].chars().enumerate() {
+            if index > 0 && (end - index) % 3 == 0 {
+                grouped.push(',');
+            }
+            grouped.push(digit);
+        }
+        rest = &rest[end..];
+    }
+    grouped.push_str(rest);
+    grouped
+}
+
+/// A task along with how long ago it was done, as returned by
+/// [`Tasks::ages_when`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct TaskAge {
+    pub name:    String,
+    pub task:    Task,
+    /// How long before "now" the task was done.
+    pub elapsed: Duration,
+}
+
+impl TaskAge {
+    /// How long ago the task became due, which is negative while it's still
+    /// on track. Tasks without an interval are never due.
+    pub fn past_due(&self) -> Option<Duration> {
+        self.task
+            .interval
+            .map(|interval| saturating_sub(self.elapsed, interval))
+    }
+
+    /// The most urgent tasks first: those with an interval by how far past
+    /// due they are, then the ones without an interval the same way
+    /// [`Tasks::output_when`] sorts them. A daily task done 3 days ago is more
+    /// urgent than a yearly one done 10 days ago.
+    pub fn cmp_urgency(&self, other: &Self) -> Ordering {
+        match (self.past_due(), other.past_due()) {
+            (Some(past_due), Some(other_past_due)) => other_past_due
+                .cmp(&past_due)
+                .then_with(|| self.name.cmp(&other.name)),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => {
+                by_age((&self.name, self.elapsed), (&other.name, other.elapsed))
+            },
+        }
+    }
+
+    /// The tasks done the longest ago first, the exact opposite of how
+    /// [`Tasks::output_when`] sorts them, except that the ones done at the
+    /// same time are still sorted by name.
+    pub fn cmp_staleness(&self, other: &Self) -> Ordering {
+        other
+            .elapsed
+            .cmp(&self.elapsed)
+            .then_with(|| self.name.cmp(&other.name))
+    }
+
+    /// Alphabetically by name, no matter when the tasks were done.
+    pub fn cmp_name(&self, other: &Self) -> Ordering {
+        self.name.cmp(&other.name)
+    }
+}
+
+/// How an interval task is keeping up with its interval, as returned by
+/// [`Tasks::standings_when`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Standing {
+    pub name:     String,
+    /// How long before "now" the task was done.
+    pub elapsed:  Duration,
+    pub interval: Duration,
+}
+
+impl Standing {
+    /// How much of the interval has passed: below `1.0` while the task is on
+    /// track, and above it once it's behind.
+    pub fn ratio(&self) -> f64 {
+        let interval = self.interval.num_seconds();
+        if interval <= 0 {
+            return if self.on_track() { 0.0 } else { f64::INFINITY };
+        }
+        self.elapsed.num_seconds() as f64 / interval as f64
+    }
+
+    /// Whether the task isn't overdue, see [`Task::is_overdue`].
+    pub fn on_track(&self) -> bool {
+        self.elapsed <= self.interval
+    }
+}
+
 type KeyToDisplay = (String, String);
 
 /// Used exclusively for its [`fmt::Display`] implementation, which is what
 /// you're supposed to use to display the final data to the user in a friendly
 /// way.
+///
+/// `{}` is a table with a task per line, and the alternate `{:#}` fits the
+/// tasks on a single line as `task=display` pairs, without a newline at the
+/// end. That's handy for status bars. For anything else, see
+/// [`OutputTasks::render`].
 pub struct OutputTasks(Vec<KeyToDisplay>);
 
-impl fmt::Display for OutputTasks {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut length = 0;
-        self.0.iter().for_each(|(task_name, _)| {
-            let task_name_len = task_name.len();
-            if task_name_len > length {
-                length = task_name_len;
+/// How [`OutputTasks::render`] lays the tasks out. The [`Default`] is what
+/// the [`fmt::Display`] implementation of [`OutputTasks`] uses.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutputOptions {
+    /// Goes between every task name and its displayed value, `" — "` by
+    /// default.
+    pub separator:    String,
+    /// Whether to pad the task names so that the separators line up, which
+    /// they do by default.
+    pub align:        bool,
+    /// Make the task name column at least this many columns wide, so that it
+    /// doesn't shift around as the tasks change. Longer names still widen it.
+    /// 0 by default.
+    pub min_width:    usize,
+    /// Shorten the task names that are wider than this many columns, ending
+    /// them with `…`. Only the rendered names get shortened, the tasks are
+    /// still sorted and filtered by their full ones. No limit by default, nor
+    /// with `Some(0)`, since not even the `…` would fit.
+    pub max_width:    Option<usize>,
+    /// Separate the thousands in the displayed values, see [`group_digits`].
+    /// Off by default.
+    pub group_digits: bool,
+    /// Put every task on a single line as `task=display` pairs instead,
+    /// without a newline at the end. This ignores `separator`, `align` and
+    /// `min_width`. Off by default.
+    pub compact:      bool,
+}
+
+impl Default for OutputOptions {
+    fn default() -> Self {
+        Self {
+            separator:    String::from(" — "),
+            align:        true,
+            min_width:    0,
+            max_width:    None,
+            group_digits: false,
+            compact:      false,
+        }
+    }
+}
+
+impl OutputTasks {
+    /// Take out the `(task name, displayed value)` pairs, to render them in
+    /// your own way.
+    ///
+    /// The order is the same one the [`fmt::Display`] implementation uses:
+    /// whatever order the `output` method that made this [`OutputTasks`]
+    /// sorted the tasks in.
+    pub fn into_inner(self) -> Vec<(String, String)> {
+        self.0
+    }
+
+    /// Iterate over the `(task name, displayed value)` pairs without consuming
+    /// the [`OutputTasks`].
+    ///
+    /// The order is the same as in [`OutputTasks::into_inner`].
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(key, display)| (key.as_str(), display.as_str()))
+    }
+
+    /// Only keep the first `len` tasks, in the order they're displayed in.
+    pub fn truncate(&mut self, len: usize) {
+        self.0.truncate(len);
+    }
+
+    /// Display the tasks in the opposite order.
+    pub fn reverse(&mut self) {
+        self.0.reverse();
+    }
+
+    /// Serialize into a JSON object of `task name -> displayed value`.
+    ///
+    /// The keys are written in the same order as in [`OutputTasks::iter`],
+    /// so the sorting is preserved for consumers that read objects in order.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self)
+            .expect("a map of strings to strings always serializes")
+    }
+
+    /// Lay the tasks out the way `options` say, in the order they're in.
+    ///
+    /// ```
+    /// use loago::OutputOptions;
+    /// use loago::OutputTasks;
+    /// let output = OutputTasks::from(vec![
+    ///     (String::from("vacuum"), String::from("1234")),
+    ///     (String::from("dust"), String::from("2")),
+    /// ]);
+    /// let options = OutputOptions {
+    ///     separator: String::from(": "),
+    ///     group_digits: true,
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(output.render(&options), "vacuum: 1,234\ndust  : 2\n");
+    /// ```
+    pub fn render(&self, options: &OutputOptions) -> String {
+        let value = |display| {
+            if options.group_digits {
+                Cow::Owned(group_digits(display))
+            } else {
+                Cow::Borrowed(display)
+            }
+        };
+        let names: Vec<(Cow<str>, usize)> = self
+            .0
+            .iter()
+            .map(|(task_name, _)| displayed_name(task_name, options.max_width))
+            .collect();
+        if options.compact {
+            let pairs: Vec<String> = names
+                .iter()
+                .zip(&self.0)
+                .map(|((key, _), (_, display))| {
+                    format!("{key}={}", value(display))
+                })
+                .collect();
+            return pairs.join(" ");
+        }
+        let mut length = options.min_width;
+        names.iter().for_each(|(_, task_name_len)| {
+            if *task_name_len > length {
+                length = *task_name_len;
             }
         });
-        let mut buffer = String::new();
-        for (key, days_diff) in self.0.iter() {
-            let whitespace = " ".repeat(length - key.len());
+        // Sliced for every row, rather than allocating spaces for each.
+        let padding = if options.align {
+            " ".repeat(length)
+        } else {
+            String::new()
+        };
+        let capacity: usize = names
+            .iter()
+            .zip(&self.0)
+            .map(|((key, _), (_, display))| {
+                key.len() + display.len() + options.separator.len() + 1
+            })
+            .sum();
+        let mut buffer =
+            String::with_capacity(capacity + padding.len() * names.len());
+        for ((key, key_len), (_, days_diff)) in names.iter().zip(&self.0) {
             buffer.push_str(key);
-            buffer.push_str(&whitespace);
-            buffer.push(' ');
-            buffer.push('—');
-            buffer.push(' ');
-            buffer.push_str(&days_diff.to_string());
+            if options.align {
+                buffer.push_str(&padding[..length.saturating_sub(*key_len)]);
+            }
+            buffer.push_str(&options.separator);
+            buffer.push_str(&value(days_diff));
             buffer.push('\n')
         }
-        write!(f, "{}", buffer)
+        buffer
+    }
+}
+
+/// The task name to display, and how many columns wide it is, shortened to
+/// `max_width` columns if it's wider.
+fn displayed_name(
+    name: &str,
+    max_width: Option<usize>,
+) -> (Cow<'_, str>, usize) {
+    let width = name.width();
+    match max_width {
+        Some(max) if max > 0 && width > max => {
+            let mut shortened = String::new();
+            let mut shortened_width = 0;
+            for character in name.chars() {
+                let character_width = character.width().unwrap_or(0);
+                if shortened_width + character_width + 1 > max {
+                    break;
+                }
+                shortened.push(character);
+                shortened_width += character_width;
+            }
+            shortened.push('…');
+            (Cow::Owned(shortened), shortened_width + 1)
+        },
+        _ => (Cow::Borrowed(name), width),
+    }
+}
+
+/// Lets you reuse the aligned [`fmt::Display`] implementation for pairs you
+/// made yourself. The order of the pairs is kept as is.
+impl From<Vec<(String, String)>> for OutputTasks {
+    fn from(value: Vec<(String, String)>) -> Self {
+        Self(value)
+    }
+}
+
+/// Serializes as a map of `task name -> displayed value`, in the sorted order.
+impl Serialize for OutputTasks {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+impl fmt::Display for OutputTasks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let options = OutputOptions {
+            compact: f.alternate(),
+            ..Default::default()
+        };
+        write!(f, "{}", self.render(&options))
+    }
+}
+
+/// The header of the section that tasks without tags end up in, in
+/// [`Tasks::output_by_tag_when`].
+pub const UNTAGGED: &str = "untagged";
+
+/// The header of the section that tasks done longer ago than any bucket end up
+/// in, in [`Tasks::output_by_age_when`].
+pub const OLDER: &str = "older";
+
+/// Several [`OutputTasks`], each under a header.
+///
+/// Like [`OutputTasks`], it's used for its [`fmt::Display`] implementation,
+/// which displays every header followed by its tasks, with an empty line
+/// between the sections.
+pub struct GroupedOutput(Vec<(String, OutputTasks)>);
+
+impl GroupedOutput {
+    /// Take out the `(header, tasks)` sections, in the order they're displayed
+    /// in.
+    pub fn into_inner(self) -> Vec<(String, OutputTasks)> {
+        self.0
+    }
+
+    /// Only keep the first `len` tasks of every section, see
+    /// [`OutputTasks::truncate`].
+    pub fn truncate_sections(&mut self, len: usize) {
+        for (_, tasks) in &mut self.0 {
+            tasks.truncate(len);
+        }
+    }
+
+    /// Reverse the order of the tasks in every section, keeping the sections
+    /// themselves in order, see [`OutputTasks::reverse`].
+    pub fn reverse_sections(&mut self) {
+        for (_, tasks) in &mut self.0 {
+            tasks.reverse();
+        }
+    }
+}
+
+impl GroupedOutput {
+    /// Lay every section out the way `options` say, see
+    /// [`OutputTasks::render`].
+    pub fn render(&self, options: &OutputOptions) -> String {
+        let mut buffer = String::new();
+        for (index, (header, tasks)) in self.0.iter().enumerate() {
+            if index > 0 {
+                buffer.push('\n');
+            }
+            buffer.push_str(header);
+            buffer.push('\n');
+            buffer.push_str(&tasks.render(options));
+        }
+        buffer
+    }
+}
+
+impl fmt::Display for GroupedOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render(&OutputOptions::default()))
     }
 }
 
@@ -252,11 +1789,34 @@ impl fmt::Display for OutputTasks {
 mod tasks {
     use std::collections::HashMap;
 
+    use chrono::Duration;
     use chrono::NaiveDate;
     use chrono::NaiveDateTime;
+    use unicode_width::UnicodeWidthStr;
 
+    use crate::days;
+    use crate::days_hours_minutes;
+    use crate::days_hours_minutes_seconds;
+    use crate::days_rounded;
+    use crate::days_tenths;
+    use crate::days_with_unit;
+    use crate::due;
+    use crate::duration_parts;
+    use crate::errors::AsErrStr;
+    use crate::group_digits;
+    use crate::humanize;
+    use crate::humanize_with_unit;
     use crate::now;
+    use crate::weeks;
+    use crate::DurationParts;
+    use crate::FixedClock;
+    use crate::MergeStrategy;
+    use crate::OutputOptions;
+    use crate::OutputTasks;
+    use crate::TaskAge;
     use crate::Tasks;
+    use crate::NEVER;
+    use crate::OLDER;
 
     impl Tasks {
         fn same_days() -> Self {
@@ -265,7 +1825,7 @@ mod tasks {
             map.insert(String::from("dust"), december);
             map.insert(String::from("vacuum"), december);
             map.insert(String::from("exercise"), december);
-            Self(map)
+            Self::from(map)
         }
 
         fn different_days() -> Self {
@@ -273,7 +1833,14 @@ mod tasks {
             map.insert(String::from("dust"), november(1));
             map.insert(String::from("vacuum"), november(2));
             map.insert(String::from("exercise"), november(3));
-            Self(map)
+            Self::from(map)
+        }
+
+        fn tagged() -> Self {
+            let mut tasks = Self::same_days();
+            tasks.tag("dust", ["room"]);
+            tasks.tag("vacuum", ["room", "floor"]);
+            tasks
         }
     }
 
@@ -294,50 +1861,275 @@ mod tasks {
     #[test]
     fn update() {
         let mut tasks = Tasks::same_days();
-        tasks.update("dust");
-        let dust_ago = now() - tasks.0["dust"];
+        assert_eq!(tasks.update("dust"), Some(december()));
+        let dust_ago = now() - tasks.0["dust"].timestamp;
         assert_eq!(dust_ago.num_minutes(), 0);
+        assert_eq!(tasks.update("mop"), None);
     }
 
     #[test]
     fn update_multiple() {
         let mut tasks = Tasks::same_days();
-        tasks.update_multiple(["vacuum", "dust"]);
-        let vacuum_ago = now() - tasks.0["vacuum"];
-        let dust_ago = now() - tasks.0["dust"];
-        let exercise_ago = now() - tasks.0["exercise"];
+        let created = tasks.update_multiple(["vacuum", "mop", "dust"]);
+        assert_eq!(created, ["mop"]);
+        let vacuum_ago = now() - tasks.0["vacuum"].timestamp;
+        let dust_ago = now() - tasks.0["dust"].timestamp;
+        let exercise_ago = now() - tasks.0["exercise"].timestamp;
         assert_eq!(vacuum_ago.num_minutes(), 0);
         assert_eq!(dust_ago.num_minutes(), 0);
         assert!(exercise_ago.num_days() > 0);
     }
 
+    #[test]
+    fn update_with_clock() {
+        let mut tasks = Tasks::same_days();
+        let clock = FixedClock(november(1));
+        assert_eq!(tasks.update_with("dust", &clock), Some(december()));
+        assert_eq!(tasks.0["dust"].timestamp, november(1));
+        let created = tasks.update_multiple_with(["vacuum", "mop"], &clock);
+        assert_eq!(created, ["mop"]);
+        assert_eq!(tasks.0["vacuum"].timestamp, november(1));
+        assert_eq!(tasks.0["mop"].timestamp, november(1));
+        assert_eq!(tasks.0["exercise"].timestamp, december());
+        tasks.update_all_with(&FixedClock(november(2)));
+        assert!(tasks.0.values().all(|task| task.timestamp == november(2)));
+    }
+
+    #[test]
+    fn update_at() {
+        let mut tasks = Tasks::same_days();
+        tasks.tag("dust", ["room"]);
+        assert_eq!(tasks.update_at("dust", november(2)), Some(december()));
+        assert_eq!(tasks.0["dust"].timestamp, november(2));
+        assert_eq!(tasks.update_at("dust", november(1)), Some(november(2)));
+        assert!(tasks.0["dust"].tags.contains("room"));
+        let created = tasks.update_multiple_at(["mop", "vacuum"], november(3));
+        assert_eq!(created, ["mop"]);
+        assert_eq!(tasks.0["mop"].timestamp, november(3));
+        assert_eq!(tasks.0["vacuum"].timestamp, november(3));
+        assert_eq!(tasks.0["exercise"].timestamp, december());
+        assert_eq!(tasks.0["dust"].created, december());
+        assert_eq!(tasks.0["mop"].created, november(3));
+        assert!(tasks.remove("mop"));
+        tasks.update_at("mop", november(4));
+        assert_eq!(tasks.0["mop"].created, november(4));
+    }
+
+    #[test]
+    fn update_all() {
+        let mut tasks = Tasks::different_days();
+        tasks.update_all();
+        assert_eq!(tasks.0.len(), 3);
+        for task in tasks.0.values() {
+            assert_eq!((now() - task.timestamp).num_minutes(), 0);
+        }
+    }
+
+    #[test]
+    fn never_done() {
+        let mut tasks = Tasks::tagged();
+        tasks.never_done(["dust", "mop"]);
+        assert_eq!(tasks.0["dust"].timestamp, NEVER);
+        assert!(tasks.0["dust"].tags.contains("room"));
+        assert_eq!(tasks.0["mop"].timestamp, NEVER);
+        assert_ne!(tasks.0["dust"].created, NEVER);
+        assert_eq!((now() - tasks.0["mop"].created).num_minutes(), 0);
+        let output = tasks.output_when(december(), days).into_inner();
+        let (last, _) = output.last().unwrap();
+        assert!(last == "dust" || last == "mop");
+    }
+
+    #[test]
+    fn merge_newer_wins() {
+        let mut tasks = Tasks::different_days();
+        let mut other = Tasks::same_days();
+        other.tag("dust", ["room"]);
+        other.0.remove("exercise");
+        other.update_at("mop", december());
+        other.update_at("vacuum", november(1));
+        tasks.merge(other, MergeStrategy::Newer);
+        assert_eq!(tasks.0.len(), 4);
+        assert_eq!(tasks.0["dust"].timestamp, december());
+        assert!(tasks.0["dust"].tags.contains("room"));
+        assert_eq!(tasks.0["vacuum"].timestamp, november(2));
+        assert_eq!(tasks.0["exercise"].timestamp, november(3));
+        assert_eq!(tasks.0["mop"].timestamp, december());
+    }
+
+    #[test]
+    fn merge_preferring_a_side() {
+        let mut other = Tasks::same_days();
+        other.update_at("vacuum", november(1));
+        other.update_at("mop", december());
+        let mut tasks = Tasks::different_days();
+        tasks.merge(other.clone(), MergeStrategy::PreferSelf);
+        assert_eq!(tasks.0.len(), 4);
+        assert_eq!(tasks.0["dust"].timestamp, november(1));
+        assert_eq!(tasks.0["vacuum"].timestamp, november(2));
+        assert_eq!(tasks.0["mop"].timestamp, december());
+        let mut tasks = Tasks::different_days();
+        tasks.merge(other.clone(), MergeStrategy::PreferOther);
+        assert_eq!(tasks.0.len(), 4);
+        assert_eq!(tasks.0["dust"].timestamp, december());
+        assert_eq!(tasks.0["vacuum"].timestamp, november(1));
+        assert_eq!(tasks.0["mop"].timestamp, december());
+        let mut tasks = Tasks::different_days();
+        tasks.merge(other, MergeStrategy::Newer);
+        assert_eq!(tasks.0["dust"].timestamp, december());
+        assert_eq!(tasks.0["vacuum"].timestamp, november(2));
+    }
+
+    #[test]
+    fn equality_ignores_order() {
+        let tasks = Tasks::different_days();
+        let mut reversed = Tasks::default();
+        for task in ["exercise", "vacuum", "dust"] {
+            reversed.update_at(task, tasks.0[task].timestamp);
+        }
+        assert_eq!(tasks, reversed);
+        let mut merged = tasks.clone();
+        merged.merge(reversed.clone(), MergeStrategy::default());
+        assert_eq!(merged, tasks);
+        reversed.tag("dust", ["room"]);
+        assert_ne!(tasks, reversed);
+    }
+
+    #[test]
+    fn debug_is_sorted() {
+        let mut tasks = Tasks::default();
+        for task in ["vacuum", "dust", "mop", "exercise"] {
+            tasks.update_at(task, december());
+        }
+        let debug = format!("{tasks:?}");
+        let positions: Vec<usize> = ["dust", "exercise", "mop", "vacuum"]
+            .map(|task| debug.find(&format!("\"{task}\"")).unwrap())
+            .into();
+        assert!(positions.is_sorted(), "{debug}");
+        assert!(debug.starts_with("Tasks({\"dust\": Task { timestamp: "));
+        assert_eq!(format!("{:?}", tasks.clone()), debug);
+    }
+
+    #[test]
+    fn adjust() {
+        let mut tasks = Tasks::same_days();
+        assert!(tasks.adjust("dust", Duration::hours(2)));
+        assert_eq!(tasks.0["dust"].timestamp, december() + Duration::hours(2));
+        assert!(tasks.adjust("vacuum", Duration::days(-3)));
+        assert_eq!(tasks.0["vacuum"].timestamp, december() - Duration::days(3));
+        assert_eq!(tasks.0["vacuum"].created, december());
+        assert!(!tasks.adjust("mop", Duration::hours(1)));
+        assert!(!tasks.contains("mop"));
+        tasks.adjust("exercise", Duration::max_value());
+        assert_eq!(tasks.0["exercise"].timestamp, NaiveDateTime::MAX);
+        tasks.adjust("exercise", Duration::min_value());
+        tasks.adjust("exercise", Duration::min_value());
+        assert_eq!(tasks.0["exercise"].timestamp, NaiveDateTime::MIN);
+    }
+
+    #[test]
+    fn timestamps() {
+        let tasks = Tasks::different_days();
+        let mut timestamps: Vec<(&str, NaiveDateTime)> =
+            tasks.timestamps().collect();
+        timestamps.sort_unstable();
+        assert_eq!(timestamps, [
+            ("dust", november(1)),
+            ("exercise", november(3)),
+            ("vacuum", november(2)),
+        ]);
+    }
+
+    #[test]
+    fn durations() {
+        let tasks = Tasks::different_days();
+        let durations = tasks.durations_when(december());
+        assert_eq!(durations, [
+            ("exercise".to_owned(), december() - november(3)),
+            ("vacuum".to_owned(), december() - november(2)),
+            ("dust".to_owned(), december() - november(1)),
+        ]);
+        assert_eq!(
+            tasks.get_when("vacuum", december()),
+            Some(december() - november(2))
+        );
+        assert_eq!(tasks.get_when("mop", december()), None);
+        assert!(tasks.get("dust").unwrap() > Duration::zero());
+        assert_eq!(tasks.durations().len(), 3);
+    }
+
+    #[test]
+    fn iter_sorted() {
+        let mut tasks = Tasks::different_days();
+        tasks.update_at("mop", november(2));
+        let sorted: Vec<(&str, Duration)> =
+            tasks.iter_sorted(december()).collect();
+        assert_eq!(sorted, [
+            ("exercise", december() - november(3)),
+            ("mop", december() - november(2)),
+            ("vacuum", december() - november(2)),
+            ("dust", december() - november(1)),
+        ]);
+        let names: Vec<String> = tasks
+            .clone()
+            .ages_when(december())
+            .into_iter()
+            .map(|age| age.name)
+            .collect();
+        assert_eq!(
+            names,
+            sorted.iter().map(|(name, _)| *name).collect::<Vec<_>>()
+        );
+    }
+
     #[test]
     fn remove() {
         let mut tasks = Tasks::same_days();
-        tasks.remove("vacuum");
-        assert!(!tasks.0.contains_key("vacuum"))
+        assert!(tasks.remove("vacuum"));
+        assert!(!tasks.0.contains_key("vacuum"));
+        assert!(!tasks.remove("vacuum"));
     }
 
     #[test]
     fn remove_multiple() {
         let mut tasks = Tasks::same_days();
-        tasks.remove_multiple(&["vacuum", "dust"]);
+        let removed = tasks.remove_multiple(["vacuum", "mop"]);
+        assert_eq!(removed, ["vacuum"]);
         assert!(!tasks.0.contains_key("vacuum"));
+        let removed = tasks.remove_multiple(
+            ["dust", "mop"].into_iter().map(|task| task.to_uppercase()),
+        );
+        assert!(removed.is_empty());
+        let removed = tasks.remove_multiple(vec![String::from("dust")]);
+        assert_eq!(removed, ["dust"]);
         assert!(!tasks.0.contains_key("dust"));
     }
 
+    #[test]
+    fn missing() {
+        let tasks = Tasks::same_days();
+        assert_eq!(tasks.missing(["mop", "dust", "sweep", "vacuum"]), [
+            "mop", "sweep"
+        ]);
+        assert!(tasks.missing(["dust", "vacuum"]).is_empty());
+        assert!(tasks.missing([]).is_empty());
+    }
+
     #[test]
     fn keep() {
         let mut tasks = Tasks::same_days();
         tasks.keep("vacuum");
         assert!(!tasks.0.contains_key("dust"));
         assert!(tasks.0.contains_key("vacuum"));
+        tasks.keep("mop");
+        assert!(tasks.is_empty());
     }
 
     #[test]
     fn keep_multiple() {
         let mut tasks = Tasks::same_days();
-        tasks.keep_multiple(["dust", "vacuum"]);
+        let removed = tasks.keep_multiple(["dust", "vacuum", "mop"]);
+        assert_eq!(removed, ["exercise"]);
+        assert_eq!(tasks.0.len(), 2);
         assert!(!tasks.0.contains_key("exercise"));
         assert!(tasks.0.contains_key("dust"));
         assert!(tasks.0.contains_key("vacuum"));
@@ -352,6 +2144,15 @@ mod tasks {
         }
     }
 
+    #[test]
+    fn output_weeks() {
+        let tasks = Tasks::same_days().output_weeks();
+        let expected = (now() - december()).num_weeks().to_string();
+        for (_, actual) in tasks.0 {
+            assert_eq!(actual, expected);
+        }
+    }
+
     #[test]
     fn output_display() {
         let tasks = Tasks::different_days()
@@ -363,4 +2164,815 @@ mod tasks {
         let actual = tasks.to_string();
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn output_min_width() {
+        let tasks = Tasks::different_days()
+            .output_when(december(), |duration| {
+                duration.num_days().to_string()
+            });
+        let options = |min_width| OutputOptions {
+            min_width,
+            ..Default::default()
+        };
+        assert_eq!(
+            tasks.render(&options(10)),
+            "exercise   — 275\nvacuum     — 303\ndust       — 334\n"
+        );
+        assert_eq!(tasks.render(&options(4)), tasks.to_string());
+    }
+
+    #[test]
+    fn output_aligns_by_display_width() {
+        let output = OutputTasks::from(vec![
+            ("dust".to_owned(), "1".to_owned()),
+            ("café".to_owned(), "2".to_owned()),
+            ("déjeuner".to_owned(), "3".to_owned()),
+            ("掃除".to_owned(), "4".to_owned()),
+        ]);
+        let rendered = output.to_string();
+        let columns: Vec<usize> = rendered
+            .lines()
+            .map(|line| line[..line.find('—').unwrap()].width())
+            .collect();
+        assert_eq!(columns, [9, 9, 9, 9]);
+        assert_eq!(
+            rendered,
+            "dust     — 1\ncafé     — 2\ndéjeuner — 3\n掃除     — 4\n"
+        );
+    }
+
+    #[test]
+    fn output_max_width() {
+        let output = OutputTasks::from(vec![
+            ("vacuum the whole house".to_owned(), "1".to_owned()),
+            ("dust".to_owned(), "2".to_owned()),
+            ("пылесосить".to_owned(), "3".to_owned()),
+            ("掃除機をかける".to_owned(), "4".to_owned()),
+        ]);
+        let mut options = OutputOptions {
+            max_width: Some(8),
+            ..Default::default()
+        };
+        assert_eq!(
+            output.render(&options),
+            "vacuum … — 1\ndust     — 2\nпылесос… — 3\n掃除機…  — 4\n"
+        );
+        assert_eq!(output.iter().next().unwrap().0, "vacuum the whole house");
+        options.compact = true;
+        assert_eq!(
+            output.render(&options),
+            "vacuum …=1 dust=2 пылесос…=3 掃除機…=4"
+        );
+        options.max_width = Some(0);
+        assert_eq!(
+            output.render(&options),
+            "vacuum the whole house=1 dust=2 пылесосить=3 掃除機をかける=4"
+        );
+    }
+
+    #[test]
+    fn output_options() {
+        let output = Tasks::different_days()
+            .output_when(december(), |duration| {
+                duration.num_hours().to_string()
+            });
+        let options = OutputOptions {
+            separator: String::from("\t"),
+            align: false,
+            group_digits: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            output.render(&options),
+            "exercise\t6,600\nvacuum\t7,272\ndust\t8,016\n"
+        );
+        let grouped = Tasks::different_days()
+            .output_by_tag_when(december(), |_, elapsed| {
+                elapsed.num_days().to_string()
+            });
+        assert_eq!(
+            grouped.render(&OutputOptions::default()),
+            grouped.to_string()
+        );
+    }
+
+    #[test]
+    fn output_display_alternate() {
+        let tasks = Tasks::different_days()
+            .output_when(december(), |duration| {
+                duration.num_days().to_string()
+            });
+        assert_eq!(format!("{tasks:#}"), "exercise=275 vacuum=303 dust=334");
+        assert_eq!(format!("{:#}", Tasks::default().output_days()), "");
+    }
+
+    #[test]
+    fn group_digits_edges() {
+        assert_eq!(group_digits(""), "");
+        assert_eq!(group_digits("never"), "never");
+        assert_eq!(group_digits("100000"), "100,000");
+        assert_eq!(group_digits("12345d 0h 1000m"), "12,345d 0h 1,000m");
+    }
+
+    #[test]
+    fn minutes_exactly_one_day() {
+        assert_eq!(days_hours_minutes(Duration::days(1)), "1d 0h 0m");
+    }
+
+    #[test]
+    fn minutes_over_a_day() {
+        assert_eq!(days_hours_minutes(Duration::hours(25)), "1d 1h 0m");
+    }
+
+    #[test]
+    fn days_precision() {
+        let hours = |hours| Duration::hours(hours);
+        // 1.4 and 1.6 days.
+        let (less, more) = (
+            hours(24 + 9) + Duration::minutes(36),
+            hours(38) + Duration::minutes(24),
+        );
+        assert_eq!(days(less), "1");
+        assert_eq!(days(more), "1");
+        assert_eq!(days_rounded(less), "1");
+        assert_eq!(days_rounded(more), "2");
+        assert_eq!(days_rounded(hours(36)), "2");
+        assert_eq!(days_rounded(-hours(36)), "-2");
+        assert_eq!(days_tenths(less), "1.4");
+        assert_eq!(days_tenths(more), "1.6");
+        assert_eq!(days_tenths(Duration::zero()), "0.0");
+        assert_eq!(days_tenths(hours(47)), "2.0");
+    }
+
+    #[test]
+    fn minutes_many_days() {
+        let duration = Duration::days(123)
+            + Duration::hours(4)
+            + Duration::minutes(5)
+            + Duration::seconds(59);
+        assert_eq!(days_hours_minutes(duration), "123d 4h 5m");
+    }
+
+    #[test]
+    fn minutes_unit_boundaries() {
+        let almost_a_day = Duration::days(1) - Duration::seconds(1);
+        assert_eq!(days_hours_minutes(almost_a_day), "0d 23h 59m");
+        let almost_an_hour = Duration::hours(1) - Duration::seconds(1);
+        assert_eq!(days_hours_minutes(almost_an_hour), "0d 0h 59m");
+    }
+
+    #[test]
+    fn seconds_under_a_minute() {
+        let duration = Duration::seconds(42);
+        assert_eq!(days_hours_minutes_seconds(duration), "0d 0h 0m 42s");
+    }
+
+    #[test]
+    fn seconds_sum_back_to_total() {
+        let duration = Duration::days(2)
+            + Duration::hours(3)
+            + Duration::minutes(4)
+            + Duration::seconds(5);
+        assert_eq!(days_hours_minutes_seconds(duration), "2d 3h 4m 5s");
+    }
+
+    #[test]
+    fn parts_match_display() {
+        let duration = Duration::days(123)
+            + Duration::hours(4)
+            + Duration::minutes(5)
+            + Duration::milliseconds(6_900);
+        assert_eq!(duration_parts(duration), DurationParts {
+            days:    123,
+            hours:   4,
+            minutes: 5,
+            seconds: 6,
+        });
+        assert_eq!(days_hours_minutes_seconds(duration), "123d 4h 5m 6s");
+        let almost_a_day = Duration::days(1) - Duration::seconds(1);
+        assert_eq!(duration_parts(almost_a_day), DurationParts {
+            days:    0,
+            hours:   23,
+            minutes: 59,
+            seconds: 59,
+        });
+        assert_eq!(duration_parts(Duration::zero()).days, 0);
+    }
+
+    #[test]
+    fn humanize_thresholds() {
+        assert_eq!(humanize(Duration::days(3)), "3d");
+        assert_eq!(humanize(Duration::days(1)), "1d");
+        assert_eq!(humanize(Duration::hours(23)), "23h");
+        assert_eq!(humanize(Duration::minutes(1)), "1m");
+        assert_eq!(humanize(Duration::zero()), "now");
+        assert_eq!(humanize(Duration::minutes(-5)), "now");
+    }
+
+    #[test]
+    fn humanize_plurals() {
+        let units = [
+            (Duration::minutes(1), "minute"),
+            (Duration::hours(1), "hour"),
+            (Duration::days(1), "day"),
+            (Duration::weeks(1), "week"),
+        ];
+        for (unit, name) in units {
+            assert_eq!(humanize_with_unit(unit), format!("1 {name}"));
+            assert_eq!(humanize_with_unit(unit * 2), format!("2 {name}s"));
+        }
+        assert_eq!(humanize_with_unit(Duration::zero()), "just now");
+        assert_eq!(humanize_with_unit(Duration::seconds(-5)), "just now");
+        assert_eq!(humanize_with_unit(Duration::minutes(59)), "59 minutes");
+        assert_eq!(humanize_with_unit(Duration::days(13)), "1 week");
+    }
+
+    #[test]
+    fn output_iter() {
+        let tasks = Tasks::different_days()
+            .output_when(december(), |duration| {
+                duration.num_days().to_string()
+            });
+        let pairs: Vec<(&str, &str)> = tasks.iter().collect();
+        assert_eq!(pairs, [
+            ("exercise", "275"),
+            ("vacuum", "303"),
+            ("dust", "334")
+        ]);
+        assert_eq!(
+            tasks.into_inner()[0],
+            (String::from("exercise"), String::from("275"))
+        );
+    }
+
+    #[test]
+    fn ages() {
+        let ages = Tasks::different_days().ages_when(december());
+        let ages: Vec<(&str, i64)> = ages
+            .iter()
+            .map(|age| (age.name.as_str(), age.elapsed.num_days()))
+            .collect();
+        assert_eq!(ages, [("exercise", 275), ("vacuum", 303), ("dust", 334)]);
+        let names: Vec<String> = Tasks::same_days()
+            .ages_when(december())
+            .into_iter()
+            .map(|age| age.name)
+            .collect();
+        assert_eq!(names, ["dust", "exercise", "vacuum"]);
+    }
+
+    #[test]
+    fn output_truncate() {
+        let mut tasks = Tasks::different_days()
+            .output_when(december(), |duration| {
+                duration.num_days().to_string()
+            });
+        tasks.truncate(2);
+        assert_eq!(tasks.to_string(), "exercise — 275\nvacuum   — 303\n");
+    }
+
+    #[test]
+    fn output_json() {
+        let tasks = Tasks::different_days()
+            .output_when(december(), |duration| {
+                duration.num_days().to_string()
+            });
+        let expected =
+            String::from(r#"{"exercise":"275","vacuum":"303","dust":"334"}"#);
+        assert_eq!(expected, tasks.to_json());
+    }
+
+    #[test]
+    fn try_from_names_the_task() {
+        let mut map = HashMap::new();
+        map.insert(String::from("dust"), format!("{:?}", december()));
+        map.insert(String::from("vacuum"), String::from("not-a-date"));
+        map.insert(String::from("exercise"), format!("{:?}", november(3)));
+        let error = Tasks::try_from(map.clone()).unwrap_err();
+        assert_eq!(error.task, "vacuum");
+        assert_eq!(error.value, "not-a-date");
+        assert!(error.as_str().starts_with(
+            "failed to parse timestamp for task \"vacuum\": \"not-a-date\""
+        ));
+        let error = Tasks::try_from_filtered(map.clone(), &["dust", "vacuum"])
+            .unwrap_err();
+        assert_eq!(error.task, "vacuum");
+        assert!(Tasks::try_from_filtered(map, &["dust", "exercise"]).is_ok());
+    }
+
+    #[test]
+    fn try_from_lenient() {
+        let mut map = HashMap::new();
+        map.insert(String::from("dust"), format!("{:?}", december()));
+        map.insert(String::from("vacuum"), String::from("not-a-date"));
+        map.insert(String::from("exercise"), String::new());
+        let (tasks, mut invalid) = Tasks::try_from_lenient(map);
+        assert_eq!(tasks.0["dust"].timestamp, december());
+        assert_eq!(tasks.0.len(), 1);
+        invalid.sort_by(|(left, _), (right, _)| left.cmp(right));
+        let invalid: Vec<&str> =
+            invalid.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(invalid, ["exercise", "vacuum"]);
+    }
+
+    #[test]
+    fn update_keeps_tags() {
+        let mut tasks = Tasks::tagged();
+        tasks.update("dust");
+        assert!(tasks.0["dust"].tags.contains("room"));
+    }
+
+    #[test]
+    fn tag_missing() {
+        let mut tasks = Tasks::same_days();
+        assert!(!tasks.tag("mop", ["floor"]));
+        assert!(!tasks.0.contains_key("mop"));
+    }
+
+    #[test]
+    fn untag() {
+        let mut tasks = Tasks::tagged();
+        assert!(tasks.untag("vacuum", &["room"]));
+        assert!(!tasks.0["vacuum"].tags.contains("room"));
+        assert!(tasks.0["vacuum"].tags.contains("floor"));
+        assert!(tasks.untag("dust", &[] as &[&str]));
+        assert!(tasks.0["dust"].tags.is_empty());
+    }
+
+    #[test]
+    fn keep_matching() {
+        let mut tasks = Tasks::same_days();
+        tasks.update("Vacuum-Car");
+        tasks.keep_matching("VAC");
+        let mut names: Vec<&str> = tasks.iter().map(|(key, _)| key).collect();
+        names.sort();
+        assert_eq!(names, ["Vacuum-Car", "vacuum"]);
+        tasks.keep_matching("mop");
+        assert_eq!(tasks.iter().count(), 0);
+    }
+
+    #[test]
+    fn older_than() {
+        let mut tasks = Tasks::different_days();
+        let now = november(3) + Duration::days(30);
+        tasks.older_than(Duration::days(30), now);
+        let names: Vec<&str> = tasks.iter().map(|(key, _)| key).collect();
+        assert_eq!(names.len(), 2);
+        assert!(!names.contains(&"exercise"));
+    }
+
+    #[test]
+    fn newer_than() {
+        let mut tasks = Tasks::different_days();
+        let now = november(3) + Duration::days(30);
+        tasks.newer_than(Duration::days(30), now);
+        let names: Vec<&str> = tasks.iter().map(|(key, _)| key).collect();
+        assert_eq!(names, ["exercise"]);
+        tasks.newer_than(Duration::days(30) - Duration::seconds(1), now);
+        assert_eq!(tasks.iter().count(), 0);
+    }
+
+    #[test]
+    fn done_between() {
+        let mut tasks = Tasks::different_days();
+        tasks.done_between(Some(november(2)), None);
+        let mut names: Vec<&str> = tasks.iter().map(|(key, _)| key).collect();
+        names.sort();
+        assert_eq!(names, ["exercise", "vacuum"]);
+        tasks.done_between(None, Some(november(2)));
+        let names: Vec<&str> = tasks.iter().map(|(key, _)| key).collect();
+        assert_eq!(names, ["vacuum"]);
+    }
+
+    #[test]
+    fn remove_older_than() {
+        let mut tasks = Tasks::different_days();
+        let now = november(3) + Duration::days(30);
+        let mut removed = tasks.remove_older_than(Duration::days(30), now);
+        removed.sort();
+        assert_eq!(removed, ["dust", "vacuum"]);
+        assert!(tasks.contains("exercise"));
+        assert!(tasks.remove_older_than(Duration::days(30), now).is_empty());
+    }
+
+    #[test]
+    fn keep_tagged() {
+        let mut tasks = Tasks::tagged();
+        tasks.keep_tagged(&["floor", "kitchen"]);
+        assert!(tasks.0.contains_key("vacuum"));
+        assert!(!tasks.0.contains_key("dust"));
+        assert!(!tasks.0.contains_key("exercise"));
+    }
+
+    #[test]
+    fn keep_tagged_any() {
+        let mut tasks = Tasks::tagged();
+        tasks.keep_tagged(&["room", "floor"]);
+        assert!(tasks.0.contains_key("vacuum"));
+        assert!(tasks.0.contains_key("dust"));
+        assert!(!tasks.0.contains_key("exercise"));
+    }
+
+    #[test]
+    fn output_by_tag() {
+        let tasks = Tasks::tagged()
+            .output_by_tag_when(december(), |_, duration| days(duration));
+        let sections: Vec<(String, Vec<String>)> = tasks
+            .into_inner()
+            .into_iter()
+            .map(|(header, tasks)| {
+                let mut names: Vec<String> = tasks
+                    .into_inner()
+                    .into_iter()
+                    .map(|(key, _)| key)
+                    .collect();
+                names.sort();
+                (header, names)
+            })
+            .collect();
+        assert_eq!(sections, [
+            (String::from("floor"), vec![String::from("vacuum")]),
+            (String::from("room"), vec![
+                String::from("dust"),
+                String::from("vacuum")
+            ]),
+            (String::from("untagged"), vec![String::from("exercise")]),
+        ]);
+    }
+
+    #[test]
+    fn output_by_age() {
+        let buckets = [
+            (String::from("today"), Duration::days(1)),
+            (String::from("recent"), Duration::days(280)),
+            (String::from("old"), Duration::days(303)),
+        ];
+        let actual = Tasks::different_days()
+            .output_by_age_when(december(), &buckets, |_, duration| {
+                days(duration)
+            })
+            .to_string();
+        assert_eq!(
+            actual,
+            "recent\nexercise — 275\n\nold\nvacuum — 303\n\nolder\ndust — \
+             334\n"
+        );
+        let everything = Tasks::different_days().output_by_age_when(
+            december(),
+            &[],
+            |_, duration| days(duration),
+        );
+        let headers: Vec<String> = everything
+            .into_inner()
+            .into_iter()
+            .map(|(header, _)| header)
+            .collect();
+        assert_eq!(headers, [OLDER]);
+    }
+
+    #[test]
+    fn grouped_display() {
+        let mut tasks = Tasks::different_days();
+        tasks.tag("dust", ["room"]);
+        let expected = String::from(
+            "room\ndust — 334\n\nuntagged\nexercise — 275\nvacuum   — 303\n",
+        );
+        let actual = tasks
+            .output_by_tag_when(december(), |_, duration| days(duration))
+            .to_string();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn set_interval() {
+        let mut tasks = Tasks::same_days();
+        assert!(tasks.set_interval("dust", Some(Duration::days(7))));
+        assert_eq!(tasks.0["dust"].interval, Some(Duration::days(7)));
+        assert!(tasks.set_interval("dust", None));
+        assert_eq!(tasks.0["dust"].interval, None);
+        assert!(!tasks.set_interval("mop", Some(Duration::days(7))));
+    }
+
+    #[test]
+    fn output_records_due() {
+        let mut tasks = Tasks::different_days();
+        tasks.set_interval("exercise", Some(Duration::days(277)));
+        tasks.set_interval("vacuum", Some(Duration::days(300)));
+        let output = tasks.output_records_when(december(), |task, elapsed| {
+            match task.interval {
+                Some(interval) => due(interval - elapsed, days_with_unit),
+                None => days(elapsed),
+            }
+        });
+        let pairs: Vec<(&str, &str)> = output.iter().collect();
+        assert_eq!(pairs, [
+            ("exercise", "due in 2 days"),
+            ("vacuum", "overdue by 3 days"),
+            ("dust", "334")
+        ]);
+    }
+
+    #[test]
+    fn due_boundaries() {
+        assert_eq!(due(Duration::zero(), days_with_unit), "due in 0 days");
+        assert_eq!(due(Duration::days(1), days_with_unit), "due in 1 day");
+        assert_eq!(
+            due(Duration::hours(-1), days_with_unit),
+            "overdue by 0 days"
+        );
+    }
+
+    #[test]
+    fn standings() {
+        let mut tasks = Tasks::different_days();
+        tasks.set_interval("dust", Some(Duration::days(10)));
+        tasks.set_interval("vacuum", Some(Duration::days(40)));
+        let now = november(3);
+        let standings = tasks.standings_when(now);
+        let names: Vec<&str> = standings
+            .iter()
+            .map(|standing| standing.name.as_str())
+            .collect();
+        assert_eq!(names, ["dust", "vacuum"]);
+        assert!(!standings[0].on_track());
+        assert!(standings[0].ratio() > 1.0);
+        assert!(standings[1].on_track());
+        assert!((standings[1].ratio() - 28.0 / 40.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn overdue() {
+        let mut tasks = Tasks::same_days();
+        tasks.set_interval("dust", Some(Duration::days(7)));
+        let dust = &tasks.0["dust"];
+        assert!(!dust.is_overdue(Duration::days(7)));
+        assert!(dust.is_overdue(Duration::days(8)));
+        assert!(!tasks.0["vacuum"].is_overdue(Duration::days(800)));
+        let mut overdue = tasks.clone();
+        overdue.keep_overdue(december() + Duration::days(8));
+        assert_eq!(overdue.iter().count(), 1);
+        assert!(overdue.0.contains_key("dust"));
+        tasks.keep_overdue(december() + Duration::days(7));
+        assert!(tasks.is_empty());
+    }
+
+    #[test]
+    fn snooze() {
+        let mut tasks = Tasks::same_days();
+        tasks.set_interval("dust", Some(Duration::days(7)));
+        let now = december() + Duration::days(10);
+        assert!(tasks.snooze("dust", Duration::days(2), now));
+        assert_eq!(tasks.0["dust"].timestamp, december() + Duration::days(5));
+        assert!(!tasks.0["dust"].is_overdue(Duration::days(6)));
+        // Already due later than that.
+        assert!(tasks.snooze("dust", Duration::days(1), now));
+        assert_eq!(tasks.0["dust"].timestamp, december() + Duration::days(5));
+        assert!(tasks.snooze("vacuum", Duration::days(2), now));
+        assert_eq!(tasks.0["vacuum"].timestamp, december() + Duration::days(2));
+        assert!(!tasks.snooze("mop", Duration::days(2), now));
+    }
+
+    #[test]
+    fn fill_intervals() {
+        let mut tasks = Tasks::same_days();
+        tasks.set_interval("dust", Some(Duration::days(1)));
+        tasks.fill_intervals(Duration::days(7));
+        assert_eq!(tasks.0["dust"].interval, Some(Duration::days(1)));
+        assert_eq!(tasks.0["vacuum"].interval, Some(Duration::days(7)));
+        assert_eq!(tasks.0["exercise"].interval, Some(Duration::days(7)));
+    }
+
+    #[test]
+    fn urgency() {
+        let mut tasks = Tasks::different_days();
+        tasks.update_at("mop", november(1));
+        tasks.set_interval("dust", Some(Duration::days(365)));
+        tasks.set_interval("vacuum", Some(Duration::days(1)));
+        tasks.set_interval("mop", Some(Duration::days(60)));
+        let mut ages = tasks.ages_when(november(4));
+        assert_eq!(ages[1].past_due(), Some(Duration::days(58)));
+        assert_eq!(ages[0].past_due(), None);
+        ages.sort_by(TaskAge::cmp_urgency);
+        let names: Vec<&str> =
+            ages.iter().map(|age| age.name.as_str()).collect();
+        assert_eq!(names, ["vacuum", "mop", "dust", "exercise"]);
+    }
+
+    #[test]
+    fn output_due() {
+        let mut tasks = Tasks::different_days();
+        tasks.set_interval("dust", Some(Duration::days(60)));
+        tasks.set_interval("exercise", Some(Duration::days(30)));
+        let now = november(3) + Duration::days(7);
+        let output = tasks.output_due_when(now, days_with_unit);
+        assert_eq!(
+            output.to_string(),
+            "exercise — due in 23 days\nvacuum   — 35 days\ndust     — overdue \
+             by 6 days\n"
+        );
+    }
+
+    #[test]
+    fn staleness_and_name() {
+        let mut tasks = Tasks::different_days();
+        tasks.update_at("mop", november(2));
+        let mut ages = tasks.ages_when(december());
+        let names = |ages: &[TaskAge]| -> Vec<String> {
+            ages.iter().map(|age| age.name.clone()).collect()
+        };
+        ages.sort_by(TaskAge::cmp_staleness);
+        assert_eq!(names(&ages), ["dust", "mop", "vacuum", "exercise"]);
+        ages.sort_by(TaskAge::cmp_name);
+        assert_eq!(names(&ages), ["dust", "exercise", "mop", "vacuum"]);
+    }
+
+    #[test]
+    fn extend() {
+        let mut tasks = Tasks::tagged();
+        tasks.extend([
+            (String::from("dust"), november(1)),
+            (String::from("mop"), november(2)),
+        ]);
+        assert_eq!(tasks.0["dust"].timestamp, november(1));
+        assert!(tasks.0["dust"].tags.contains("room"));
+        assert_eq!(tasks.0["mop"].timestamp, november(2));
+        assert_eq!(tasks.0["vacuum"].timestamp, december());
+        assert_eq!(tasks.iter().count(), 4);
+    }
+
+    #[test]
+    fn rename_prefix() {
+        let mut tasks = Tasks::different_days();
+        for (task, month) in [
+            ("home-dust", 4),
+            ("home-mop", 5),
+            ("house-mop", 6),
+            ("home-vacuum", 1),
+            ("house-vacuum", 2),
+        ] {
+            tasks.update_at(task, november(month));
+        }
+        tasks.tag("home-dust", ["room"]);
+        assert_eq!(tasks.rename_prefix("home-", "house-"), 3);
+        let mut names: Vec<&str> = tasks.iter().map(|(name, _)| name).collect();
+        names.sort_unstable();
+        assert_eq!(names, [
+            "dust",
+            "exercise",
+            "house-dust",
+            "house-mop",
+            "house-vacuum",
+            "vacuum"
+        ]);
+        assert_eq!(tasks.0["house-dust"].timestamp, november(4));
+        assert!(tasks.0["house-dust"].tags.contains("room"));
+        assert_eq!(tasks.0["house-mop"].timestamp, november(6));
+        assert_eq!(tasks.0["house-vacuum"].timestamp, november(2));
+        assert_eq!(tasks.rename_prefix("home-", "house-"), 0);
+        // Prefixing every task.
+        assert_eq!(tasks.rename_prefix("", "old-"), 6);
+        assert!(tasks.contains("old-house-mop"));
+    }
+
+    #[test]
+    fn rename() {
+        let mut tasks = Tasks::different_days();
+        assert!(tasks.rename("dust", "vacuum"));
+        assert_eq!(tasks.0["vacuum"].timestamp, november(2));
+        assert!(tasks.rename("vacuum", "mop"));
+        assert!(!tasks.contains("vacuum"));
+        assert!(!tasks.rename("vacuum", "mop"));
+    }
+
+    #[test]
+    fn rename_multiple() {
+        let mut tasks = Tasks::different_days();
+        let renamed = tasks.rename_multiple([
+            ("dust", "sweep"),
+            ("sweep", "mop"),
+            ("vacuum", "mop"),
+            ("cook", "bake"),
+        ]);
+        assert_eq!(renamed, 3);
+        assert_eq!(tasks.0["mop"].timestamp, november(2));
+        assert!(!tasks.contains("dust"));
+        assert!(!tasks.contains("sweep"));
+        assert_eq!(tasks.iter().count(), 2);
+    }
+
+    #[test]
+    fn swap() {
+        let mut tasks = Tasks::different_days();
+        tasks.tag("dust", ["room"]);
+        assert!(tasks.swap("dust", "vacuum"));
+        assert_eq!(tasks.0["dust"].timestamp, november(2));
+        assert_eq!(tasks.0["vacuum"].timestamp, november(1));
+        assert!(tasks.0["vacuum"].tags.contains("room"));
+        assert!(tasks.0["dust"].tags.is_empty());
+        assert!(tasks.swap("exercise", "exercise"));
+        assert_eq!(tasks.0["exercise"].timestamp, november(3));
+        assert!(!tasks.swap("dust", "mop"));
+        assert!(!tasks.swap("mop", "dust"));
+        assert_eq!(tasks.0["dust"].timestamp, november(2));
+        assert_eq!(tasks.timestamps().count(), 3);
+    }
+
+    #[test]
+    fn extreme_timestamps() {
+        let mut tasks = Tasks::default();
+        tasks.update_at("ancient", NaiveDateTime::MIN);
+        tasks.update_at("future", NaiveDateTime::MAX);
+        tasks.update_at("ancient-weekly", NaiveDateTime::MIN);
+        tasks.update_at("future-weekly", NaiveDateTime::MAX);
+        tasks.update_at("future-forever", NaiveDateTime::MAX);
+        tasks.set_interval("ancient-weekly", Some(Duration::weeks(1)));
+        tasks.set_interval("future-weekly", Some(Duration::weeks(1)));
+        tasks.set_interval("future-forever", Some(Duration::max_value()));
+        for now in [NaiveDateTime::MIN, december(), NaiveDateTime::MAX] {
+            for to_string in [
+                days,
+                weeks,
+                days_hours_minutes,
+                days_hours_minutes_seconds,
+                humanize,
+                humanize_with_unit,
+            ] {
+                tasks.clone().output_when(now, to_string);
+            }
+            tasks
+                .clone()
+                .output_records_when(now, |task, elapsed| {
+                    match task.remaining(elapsed) {
+                        Some(remaining) => due(remaining, humanize),
+                        None => humanize(elapsed),
+                    }
+                });
+            let mut ages = tasks.clone().ages_when(now);
+            ages.sort_by(TaskAge::cmp_urgency);
+            tasks.clone().standings_when(now);
+            let mut snoozed = tasks.clone();
+            snoozed.snooze("future-forever", Duration::max_value(), now);
+            snoozed.keep_overdue(now);
+        }
+        let ages = tasks.ages_when(december());
+        let future = ages.iter().find(|age| age.name == "future-forever");
+        assert_eq!(future.unwrap().past_due(), Some(Duration::min_value()));
+        let remaining = future.unwrap().task.remaining(future.unwrap().elapsed);
+        assert_eq!(remaining, Some(Duration::max_value()));
+    }
+
+    #[test]
+    fn from_pairs() {
+        let pairs = vec![
+            (String::from("dust"), november(1)),
+            (String::from("vacuum"), november(2)),
+            (String::from("exercise"), november(3)),
+        ];
+        assert_eq!(Tasks::from(pairs.as_slice()), Tasks::different_days());
+        assert_eq!(Tasks::from(pairs), Tasks::different_days());
+        let repeated = vec![
+            (String::from("dust"), november(1)),
+            (String::from("dust"), december()),
+        ];
+        assert_eq!(Tasks::from(repeated).0["dust"].timestamp, december());
+    }
+}
+
+#[cfg(test)]
+mod conversions {
+    use std::collections::HashMap;
+
+    use chrono::DateTime;
+    use chrono::NaiveDateTime;
+    use proptest::prelude::*;
+
+    use crate::Tasks;
+
+    /// From year 1 to year 9999, the years that need no sign or extra digits.
+    fn timestamp() -> impl Strategy<Value = NaiveDateTime> {
+        (-62_135_596_800_i64..=253_402_300_799, 0_u32..1_000_000_000).prop_map(
+            |(seconds, nanoseconds)| {
+                DateTime::from_timestamp(seconds, nanoseconds)
+                    .unwrap()
+                    .naive_utc()
+            },
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn round_trip(
+            timestamps in prop::collection::hash_map(".*", timestamp(), 0..20)
+        ) {
+            let tasks = Tasks::from(timestamps.clone());
+            let strings: HashMap<String, String> = tasks.into();
+            let tasks = Tasks::try_from(strings).unwrap();
+            let parsed: HashMap<String, NaiveDateTime> = tasks
+                .iter()
+                .map(|(key, task)| (key.to_owned(), task.timestamp))
+                .collect();
+            prop_assert_eq!(parsed, timestamps);
+        }
+    }
 }
@@ -0,0 +1,168 @@
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::fs::TryLockError;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+
+use loago::errors::Error;
+
+const LOCK_EXTENSION: &str = "lock";
+const BUSY_TIMEOUT: Duration = Duration::from_secs(2);
+const RETRY_INTERVAL: Duration = Duration::from_millis(20);
+
+/// An advisory lock over the data file, held for as long as this value lives.
+///
+/// The lock is taken on a separate `{data file}.lock` file rather than the data
+/// file itself, so that replacing the data file doesn't invalidate the lock.
+///
+/// Other `loago` processes respect it, other programs editing the data file
+/// don't.
+pub struct DataLock(File);
+
+impl DataLock {
+    /// Lock for reading: any amount of readers can hold the lock at once, but
+    /// not while someone holds it [`DataLock::exclusive`]ly.
+    pub fn shared(data_file: &Path) -> Result<Self, Error> {
+        Self::acquire(data_file, false, BUSY_TIMEOUT)
+    }
+
+    /// Lock for a read-modify-write cycle: nobody else can hold the lock at
+    /// the same time.
+    pub fn exclusive(data_file: &Path) -> Result<Self, Error> {
+        Self::acquire(data_file, true, BUSY_TIMEOUT)
+    }
+
+    /// Like [`DataLock::shared`], except that it doesn't create the lock file
+    /// when there isn't one yet, for only looking. Nobody else can be holding
+    /// the lock then, so there's nothing to wait for.
+    pub fn shared_if_exists(data_file: &Path) -> Result<Option<Self>, Error> {
+        match OpenOptions::new().read(true).open(lock_path(data_file)) {
+            Ok(file) => Self::wait(file, false, BUSY_TIMEOUT).map(Some),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    fn acquire(
+        data_file: &Path,
+        exclusive: bool,
+        timeout: Duration,
+    ) -> Result<Self, Error> {
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(lock_path(data_file))?;
+        Self::wait(file, exclusive, timeout)
+    }
+
+    /// Keeps retrying for `timeout` if someone else holds the lock, and gives
+    /// up with a "busy" error afterwards.
+    fn wait(
+        file: File,
+        exclusive: bool,
+        timeout: Duration,
+    ) -> Result<Self, Error> {
+        let started = Instant::now();
+        loop {
+            let attempt = if exclusive {
+                file.try_lock()
+            } else {
+                file.try_lock_shared()
+            };
+            match attempt {
+                Ok(()) => return Ok(Self(file)),
+                Err(TryLockError::Error(error)) => return Err(error.into()),
+                Err(TryLockError::WouldBlock) => {
+                    if started.elapsed() >= timeout {
+                        return Err(Error::Busy);
+                    }
+                    thread::sleep(RETRY_INTERVAL);
+                },
+            }
+        }
+    }
+}
+
+/// Closing the file releases the lock too, but explicitly unlocking documents
+/// the intent.
+impl Drop for DataLock {
+    fn drop(&mut self) {
+        let _ = self.0.unlock();
+    }
+}
+
+fn lock_path(data_file: &Path) -> PathBuf {
+    let mut path = data_file.as_os_str().to_owned();
+    path.push(".");
+    path.push(LOCK_EXTENSION);
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod locking {
+    use std::fs;
+    use std::thread;
+    use std::time::Duration;
+
+    use super::lock_path;
+    use super::DataLock;
+
+    #[test]
+    fn busy_while_exclusive() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_file = dir.path().join("loago.json");
+        let _held = DataLock::exclusive(&data_file).unwrap();
+        let timeout = Duration::from_millis(50);
+        assert!(DataLock::acquire(&data_file, false, timeout).is_err());
+        assert!(DataLock::acquire(&data_file, true, timeout).is_err());
+    }
+
+    #[test]
+    fn only_looking_creates_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_file = dir.path().join("loago.json");
+        assert!(DataLock::shared_if_exists(&data_file).unwrap().is_none());
+        assert!(!lock_path(&data_file).exists());
+        drop(DataLock::exclusive(&data_file).unwrap());
+        assert!(DataLock::shared_if_exists(&data_file).unwrap().is_some());
+    }
+
+    #[test]
+    fn readers_share() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_file = dir.path().join("loago.json");
+        let _first = DataLock::shared(&data_file).unwrap();
+        let _second = DataLock::shared(&data_file).unwrap();
+    }
+
+    #[test]
+    fn writers_dont_lose_data() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_file = dir.path().join("loago.json");
+        fs::write(&data_file, "0").unwrap();
+        let writers: Vec<_> = (0..2)
+            .map(|_| {
+                let data_file = data_file.clone();
+                thread::spawn(move || {
+                    for _ in 0..50 {
+                        let _lock = DataLock::exclusive(&data_file).unwrap();
+                        let count: u32 = fs::read_to_string(&data_file)
+                            .unwrap()
+                            .parse()
+                            .unwrap();
+                        fs::write(&data_file, (count + 1).to_string()).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        assert_eq!(fs::read_to_string(&data_file).unwrap(), "100");
+    }
+}
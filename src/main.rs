@@ -1,51 +1,450 @@
-use std::collections::HashMap;
-use std::error::Error;
+use std::env;
 use std::fs;
 use std::fs::OpenOptions;
 use std::io;
-use std::io::Read;
 use std::io::Write;
+use std::path;
 use std::path::Path;
 use std::path::PathBuf;
+use std::process;
+use std::thread;
+use std::time::Duration;
 
+use args::Action;
 use args::Args;
+use args::Options;
+use args::Verbosity;
+use args::ViewArgs;
 use clap::Parser;
-use loago::Tasks;
+use config::Config;
+use config::DEFAULT_MAX_TASKS;
+use config::UNITS_ENV_VAR;
+use history::Entry;
+use history::History;
+use loago::errors::AsErrStr;
+use loago::errors::DataDirNotFoundError;
+use loago::errors::Error;
+use lock::DataLock;
+use repo::Data;
+use repo::JsonRepo;
+use serde::Serialize;
 
 mod args;
+#[cfg(feature = "color")]
+mod color;
+mod config;
+mod doctor;
+mod editor;
+mod format;
+mod history;
+mod lock;
+#[cfg(feature = "notify")]
+mod notify;
+mod repo;
+#[cfg(feature = "tui")]
+mod tui;
 
 const APP_NAME: &str = "loago";
 const DATA_FILE_NAME: &str = "loago.json";
+/// Overrides [`APP_NAME`] as the name of the data and config directories.
+const APP_NAME_ENV_VAR: &str = "LOAGO_APP_NAME";
+/// Overrides the platform's data directory, that the [`APP_NAME`] directory is
+/// in.
+const DATA_DIR_ENV_VAR: &str = "LOAGO_DATA_DIR";
+/// Overrides [`DATA_FILE_NAME`].
+const DATA_FILE_NAME_ENV_VAR: &str = "LOAGO_DATA_FILE_NAME";
+/// Overrides the whole path of the data file, like `--data-file` does.
+const DATA_FILE_ENV_VAR: &str = "LOAGO_DATA";
 const EMPTY_JSON_FILE_CONTENT: &[u8; 2] = b"{}";
+/// Clears the terminal, and moves the cursor to the top left.
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let Args { action } = Args::parse();
-    let data_dir = app_data_dir()?;
-    let path = ensure_exists(data_dir, DATA_FILE_NAME)?;
-    let contents = read(&path)?;
-    let data: HashMap<String, String> = serde_json::from_str(&contents)?;
-    let tasks = Tasks::try_from(data)?;
-    action.execute(path, tasks)?;
+fn main() {
+    let args = match Args::try_parse() {
+        Ok(args) => args,
+        // Parsing failed before `--json` could be read, so it's looked for
+        // among the arguments instead. Help and the version aren't errors.
+        Err(error)
+            if error.use_stderr()
+                && env::args_os().skip(1).any(|arg| arg == "--json") =>
+        {
+            eprintln!(
+                "{}",
+                json_object(&usage_message(&error), error.exit_code())
+            );
+            process::exit(error.exit_code());
+        },
+        Err(error) => error.exit(),
+    };
+    let json = args.json;
+    if let Err(error) = run(args) {
+        // The exit code says it all, and the output already said the rest.
+        let silent = matches!(error, Error::Empty);
+        if json && !silent {
+            eprintln!("{}", json_message(&error));
+        } else if !silent {
+            eprintln!("{}", message(&error));
+        }
+        process::exit(error.exit_code());
+    }
+}
+
+fn run(args: Args) -> Result<(), Error> {
+    let Args {
+        action,
+        #[cfg(feature = "color")]
+        color,
+        quiet,
+        verbose,
+        json_indent,
+        timestamps,
+        dry_run,
+        allow_empty,
+        no_create_dirs,
+        json,
+        data_file,
+        strict_parse,
+        default_interval,
+    } = args;
+    let (config, unknown_keys) = Config::load()?;
+    let (unit, invalid_unit) =
+        config.unit(env::var(UNITS_ENV_VAR).ok().as_deref());
+    #[cfg(feature = "color")]
+    let color = color.or(config.color).unwrap_or_default().enabled();
+    #[cfg(not(feature = "color"))]
+    let color = false;
+    let options = Options {
+        color,
+        // A dry run is only useful for what it says would change.
+        verbosity: if quiet {
+            Verbosity::Quiet
+        } else if verbose || dry_run {
+            Verbosity::Verbose
+        } else {
+            Verbosity::Normal
+        },
+        unit,
+        sort: config.sort.unwrap_or_default(),
+        reverse: config.reverse.unwrap_or_default(),
+        red_after: config.red_after()?,
+        dry_run,
+        max_tasks: config.max_tasks.unwrap_or(DEFAULT_MAX_TASKS),
+        create_dirs: !no_create_dirs,
+        default_interval: match default_interval {
+            Some(interval) => Some(interval),
+            None => config.default_interval()?,
+        },
+        age_buckets: config.age_buckets()?,
+        json,
+    };
+    if let Some(value) = invalid_unit {
+        options.warn(format_args!(
+            "ignoring {UNITS_ENV_VAR}=\"{value}\", expected days, weeks, \
+             minutes or seconds"
+        ));
+    }
+    for key in unknown_keys {
+        options.warn(format_args!("ignoring unknown config key \"{key}\""));
+    }
+    #[cfg(feature = "completions")]
+    if let Action::Completions { shell } = action {
+        let mut command = <Args as clap::CommandFactory>::command();
+        clap_complete::generate(
+            shell,
+            &mut command,
+            APP_NAME,
+            &mut io::stdout(),
+        );
+        return Ok(());
+    }
+    #[cfg(feature = "schema")]
+    if let Action::Schema = action {
+        println!("{:#}", repo::schema());
+        return Ok(());
+    }
+    let data_file = data_file.or_else(|| {
+        env::var_os(DATA_FILE_ENV_VAR)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    });
+    let path = match data_file {
+        Some(path) => path::absolute(path)?,
+        None => app_data_dir()?
+            .join(from_env(DATA_FILE_NAME_ENV_VAR, DATA_FILE_NAME)),
+    };
+    if let Action::Path = action {
+        println!("{}", path.display());
+        return Ok(());
+    }
+    // Whatever's wrong with the data file is what it reports on.
+    if let Action::Doctor = action {
+        let repo = JsonRepo::new(path).with_strict(strict_parse);
+        return doctor::doctor(&repo, &options);
+    }
+    ensure_regular_file(&path)?;
+    // The lock is only held while reading, not for as long as the view is
+    // being watched.
+    if let Action::Watch { interval, view } = action {
+        let repo = JsonRepo::new(path).with_strict(strict_parse);
+        return watch(&repo, view, Duration::from_secs(interval), &options);
+    }
+    let repo = JsonRepo::new(path)
+        .with_indent(json_indent.unwrap_or_default())
+        .with_timestamps(timestamps)
+        .with_dry_run(dry_run)
+        .with_strict(strict_parse)
+        // Removing everything is the whole point of clearing.
+        .with_allow_empty(allow_empty || matches!(action, Action::Clear));
+    #[cfg(feature = "tui")]
+    if let Action::Tui = action {
+        return tui::run(&repo, &options);
+    }
+    // Only commands that write create the data file: without one, there are
+    // just no tasks to read yet, see [`JsonRepo::load_lenient`]. There's also
+    // nowhere to put the lock then.
+    let _lock = if action.mutates() && !dry_run {
+        ensure_exists(repo.path(), options.create_dirs)?;
+        Some(DataLock::exclusive(repo.path())?)
+    } else {
+        shared_lock(repo.path())?
+    };
+    if let Action::Verify = action {
+        return verify(&repo);
+    }
+    // The data file may well be invalid, which is why it's being edited, so
+    // it's only loaded after.
+    let entry = action.history_entry();
+    if dry_run && action.mutates() {
+        if let Action::Edit = action {
+            options.warn("can't edit the data file in a dry run");
+            return Ok(());
+        }
+        // Without a data file, there are no tasks to change yet.
+        let data = if repo.path().exists() {
+            repo.load()?
+        } else {
+            Data::default()
+        };
+        action.execute(&repo, data, &options)?;
+        options.info("(dry run, no changes written)");
+        return Ok(());
+    }
+    if let Action::Edit = action {
+        editor::edit(repo.path())?;
+        record(&repo, entry, &options);
+        if let Err(error) = repo.load() {
+            options.warn(error.as_str());
+        }
+        warn_duplicates(&repo, &options);
+        return Ok(());
+    }
+    // Mutating commands would drop the invalid tasks on save, so they refuse
+    // to work instead.
+    let data = if action.mutates() {
+        let data = repo.load()?;
+        warn_duplicates(&repo, &options);
+        data
+    } else {
+        load_lenient(&repo, action.only_tasks(), &options)?
+    };
+    action.execute(&repo, data, &options)?;
+    record(&repo, entry, &options);
     Ok(())
 }
 
-fn app_data_dir() -> Result<PathBuf, &'static str> {
-    Ok(dirs::data_local_dir()
-        .ok_or("local data directory wasn't found")?
-        .join(APP_NAME))
+/// Add what was done to the history. The change itself is already saved by
+/// now, so failing to record it is only worth a warning.
+fn record(repo: &JsonRepo, entry: Option<Entry>, options: &Options) {
+    let Some(entry) = entry else {
+        return;
+    };
+    if let Err(error) = History::of(repo.path()).record(&entry) {
+        options.warn(format_args!(
+            "couldn't record the change in the history: {}",
+            error.as_str()
+        ));
+    }
+}
+
+/// A lock for reading, if there's a data file to read.
+fn shared_lock(path: &Path) -> Result<Option<DataLock>, Error> {
+    if path.exists() {
+        DataLock::shared(path).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Load the data, warning about the tasks that were skipped.
+fn load_lenient(
+    repo: &JsonRepo,
+    only: Option<&[String]>,
+    options: &Options,
+) -> Result<Data, Error> {
+    let (data, invalid) = repo.load_lenient(only)?;
+    warn_duplicates(repo, options);
+    for (task, error) in invalid {
+        options.warn(format_args!(
+            "skipping task \"{task}\" with invalid timestamp: {error}"
+        ));
+    }
+    Ok(data)
+}
+
+/// Warn about every key that the data file had more than once, since all but
+/// the last one of each were lost while reading it.
+fn warn_duplicates(repo: &JsonRepo, options: &Options) {
+    for key in repo.duplicates() {
+        options.warn(format_args!(
+            "the data file has \"{key}\" more than once, only the last one \
+             is kept"
+        ));
+    }
+}
+
+/// Print how many tasks there are, and every one with a timestamp that fails
+/// to parse, with the reason why.
+fn verify(repo: &JsonRepo) -> Result<(), Error> {
+    let (data, mut invalid) = repo.load_lenient(None)?;
+    let count = data.tasks.iter().count() + invalid.len();
+    let noun = if count == 1 { "task" } else { "tasks" };
+    println!("{count} {noun}");
+    if invalid.is_empty() {
+        return Ok(());
+    }
+    invalid.sort_unstable_by(|(left, _), (right, _)| left.cmp(right));
+    for (task, error) in &invalid {
+        println!("\"{task}\": invalid timestamp: {error}");
+    }
+    Err(Error::InvalidTasks(invalid.len()))
+}
+
+/// Clear the screen and view the tasks every `interval`, reading the data file
+/// anew whenever it changed. Only stops when interrupted.
+fn watch(
+    repo: &JsonRepo,
+    view: ViewArgs,
+    interval: Duration,
+    options: &Options,
+) -> Result<(), Error> {
+    let mut data = None;
+    loop {
+        print!("{CLEAR_SCREEN}");
+        if data.is_none() || repo.changed()? {
+            let _lock = shared_lock(repo.path())?;
+            data = Some(load_lenient(repo, view.only_tasks(), options)?);
+        }
+        if let Some(data) = &data {
+            // Watching goes on for as long as it's asked to, whether or not
+            // any tasks match right now.
+            match view.clone().execute(data.clone(), options) {
+                Err(Error::Empty) => {},
+                result => result?,
+            }
+        }
+        io::stdout().flush()?;
+        thread::sleep(interval);
+    }
+}
+
+/// Every message `loago` prints to stderr has this format, to make it clear
+/// where it came from.
+fn message(error: &impl AsErrStr) -> String {
+    format!("{APP_NAME}: {}", error.as_str())
 }
 
-fn ensure_exists(
-    data_dir: PathBuf,
-    data_file: impl AsRef<Path>,
-) -> Result<PathBuf, io::Error> {
-    fs::create_dir_all(&data_dir)?;
-    let full_path = data_dir.join(data_file);
-    match OpenOptions::new()
-        .write(true)
-        .create_new(true)
-        .open(&full_path)
-    {
+/// [`message`] for scripts: `{"error": "message", "code": exit code}`.
+fn json_message(error: &Error) -> String {
+    json_object(&error.as_str(), error.exit_code())
+}
+
+/// What [`json_message`] prints, with the fields in the order it says.
+#[derive(Serialize)]
+struct JsonError<'a> {
+    error: &'a str,
+    code:  i32,
+}
+
+fn json_object(error: &str, code: i32) -> String {
+    serde_json::to_string(&JsonError { error, code })
+        .expect("strings and numbers always serialize")
+}
+
+/// Just what's wrong with the command line, without the `error:` prefix and
+/// the usage that clap prints after it.
+fn usage_message(error: &clap::Error) -> String {
+    let rendered = error.render().to_string();
+    let first_line = rendered.lines().next().unwrap_or_default();
+    first_line
+        .strip_prefix("error: ")
+        .unwrap_or(first_line)
+        .to_owned()
+}
+
+/// Where the data file is kept, unless `--data-file` says otherwise: the
+/// [`APP_NAME`] directory in `$LOAGO_DATA_DIR`, or else in the platform's
+/// local data directory. That's not the config directory, nor the roaming one
+/// on Windows, since it's data that's written all the time rather than
+/// configuration to carry around.
+fn app_data_dir() -> Result<PathBuf, DataDirNotFoundError> {
+    let data_dir = env::var_os(DATA_DIR_ENV_VAR)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .or_else(dirs::data_local_dir)
+        .ok_or(DataDirNotFoundError)?;
+    Ok(data_dir.join(app_name()))
+}
+
+/// The name of the directories `loago` keeps its files in, inside the
+/// platform's data and config directories.
+pub fn app_name() -> String {
+    from_env(APP_NAME_ENV_VAR, APP_NAME)
+}
+
+/// The name an environment variable overrides `default` with, if it's set
+/// and isn't empty.
+fn from_env(var: &str, default: &str) -> String {
+    env::var(var)
+        .ok()
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| default.to_owned())
+}
+
+/// Fail clearly when there's something other than a file where the data file
+/// goes, like a directory, or a symlink that leads nowhere, instead of with
+/// whatever reading or writing it would fail with later. Nothing at all is
+/// fine, the data file just doesn't exist yet.
+fn ensure_regular_file(path: &Path) -> Result<(), Error> {
+    match fs::metadata(path) {
+        Ok(metadata) if metadata.is_file() => Ok(()),
+        Ok(_) => Err(Error::NotAFile(path.to_owned())),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            match fs::symlink_metadata(path) {
+                Ok(_) => Err(Error::NotAFile(path.to_owned())),
+                Err(_) => Ok(()),
+            }
+        },
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Create the data file with no tasks in it, and the directories it's in if
+/// `create_dirs`, if it doesn't exist yet.
+fn ensure_exists(path: &Path, create_dirs: bool) -> Result<(), io::Error> {
+    match path.parent() {
+        Some(parent) if create_dirs => fs::create_dir_all(parent)?,
+        Some(parent) if !parent.as_os_str().is_empty() && !parent.is_dir() => {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "{} doesn't exist, and --no-create-dirs is set",
+                    parent.display()
+                ),
+            ));
+        },
+        _ => {},
+    }
+    match OpenOptions::new().write(true).create_new(true).open(path) {
         Ok(mut file) => {
             file.write_all(EMPTY_JSON_FILE_CONTENT)?;
             file.flush()?;
@@ -58,12 +457,34 @@ fn ensure_exists(
             }
         },
     };
-    Ok(full_path)
+    Ok(())
 }
 
-fn read(path: &Path) -> Result<String, io::Error> {
-    let mut file = OpenOptions::new().read(true).open(path)?;
-    let mut contents = String::new();
-    file.read_to_string(&mut contents)?;
-    Ok(contents)
+#[cfg(test)]
+mod messages {
+    use loago::errors::DataDirNotFoundError;
+    use loago::errors::Error;
+
+    use super::json_message;
+    use super::message;
+
+    #[test]
+    fn prefixed() {
+        assert_eq!(
+            message(&DataDirNotFoundError),
+            "loago: local data directory wasn't found"
+        );
+        assert_eq!(
+            message(&Error::Busy),
+            "loago: data file is busy: another loago is using it"
+        );
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(
+            json_message(&Error::TaskNotFound(vec!["dust".to_owned()])),
+            r#"{"error":"task \"dust\" doesn't exist","code":9}"#
+        );
+    }
 }
@@ -0,0 +1,23 @@
+//! Desktop notifications, for when `loago` runs on a timer rather than in a
+//! terminal.
+
+use loago::errors::Error;
+use notify_rust::Notification;
+
+use crate::APP_NAME;
+
+/// Show a single notification about all of the `overdue` tasks, each of which
+/// is a line of the notification's body.
+pub fn overdue(overdue: &[String]) -> Result<(), Error> {
+    let summary = match overdue.len() {
+        1 => String::from("1 task is overdue"),
+        count => format!("{count} tasks are overdue"),
+    };
+    Notification::new()
+        .appname(APP_NAME)
+        .summary(&summary)
+        .body(&overdue.join("\n"))
+        .show()
+        .map_err(|error| Error::Notification(error.to_string()))?;
+    Ok(())
+}
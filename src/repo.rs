@@ -0,0 +1,1258 @@
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::ErrorKind;
+use std::io::Write;
+use std::mem;
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::SystemTime;
+
+use chrono::NaiveDateTime;
+use clap::ValueEnum;
+use loago::errors::Error;
+use loago::errors::TimestampError;
+use loago::errors::TimestampField;
+use loago::format_duration;
+use loago::format_timestamp;
+use loago::format_timestamp_rfc3339;
+use loago::parse_duration;
+use loago::parse_timestamp;
+use loago::Groups;
+use loago::InvalidTimestamps;
+use loago::Task;
+use loago::Tasks;
+use serde::de::DeserializeOwned;
+use serde::de::DeserializeSeed;
+use serde::de::MapAccess;
+use serde::de::SeqAccess;
+use serde::de::Visitor;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde_json::json;
+use serde_json::ser::PrettyFormatter;
+use serde_json::Serializer;
+use serde_json::Value;
+use unicode_normalization::UnicodeNormalization;
+
+/// The version of the data file format that this build writes.
+///
+/// | Version | Format                                              |
+/// |---------|-----------------------------------------------------|
+/// | 0       | `{"task": "timestamp"}` (no `version` field at all) |
+/// | 1       | `{"version": 1, "tasks": {"task": "timestamp"}}`    |
+/// | 2       | version 1, plus `"groups": {"group": ["task"]}`     |
+/// | 3       | version 2, with each task's value being a record    |
+/// | 4       | version 3, plus a `"created"` timestamp in records  |
+///
+/// Records are objects with a `"timestamp"` string, in either of the
+/// [`TimestampFormat`]s no matter which one writes them, an optional `"tags"`
+/// array an optional `"interval"` duration string, like `"7d"`, and a
+/// `"created"` timestamp string, which defaults to the `"timestamp"` when it's
+/// missing. Optional fields are left out when they're empty.
+pub const CURRENT_VERSION: u64 = 4;
+
+const VERSION_KEY: &str = "version";
+const TASKS_KEY: &str = "tasks";
+const GROUPS_KEY: &str = "groups";
+/// Data files with this extension can have comments.
+const JSONC_EXTENSION: &str = "jsonc";
+/// Saving writes `{data file}.tmp` first, and then renames it over the data
+/// file.
+const TEMPORARY_EXTENSION: &str = "tmp";
+
+/// Everything that's stored in the data file.
+#[derive(Clone, Default)]
+pub struct Data {
+    pub tasks:  Tasks,
+    pub groups: Groups,
+}
+
+/// What [`JsonRepo::save`] wrote, which displays as `wrote 1.2 KB to {path}`.
+#[derive(Debug)]
+pub struct SaveReport {
+    pub path:    PathBuf,
+    pub bytes:   usize,
+    /// False in a dry run.
+    pub written: bool,
+    /// Whether what was, or would've been, written is any different from
+    /// what the data file had before.
+    pub changed: bool,
+}
+
+impl fmt::Display for SaveReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let verb = if self.written { "wrote" } else { "would write" };
+        write!(
+            f,
+            "{verb} {} to {}",
+            format_size(self.bytes),
+            self.path.display()
+        )
+    }
+}
+
+/// Sizes under a kilobyte are in bytes, and the rest in kilobytes or
+/// megabytes with one decimal, like `1.2 KB`.
+fn format_size(bytes: usize) -> String {
+    const KILOBYTE: f64 = 1000.0;
+    if bytes < 1000 {
+        return format!("{bytes} B");
+    }
+    let kilobytes = bytes as f64 / KILOBYTE;
+    if kilobytes < KILOBYTE {
+        format!("{kilobytes:.1} KB")
+    } else {
+        format!("{:.1} MB", kilobytes / KILOBYTE)
+    }
+}
+
+/// [`Data`] before the timestamps get parsed.
+#[derive(Default)]
+struct RawData {
+    tasks:  HashMap<String, StoredTask>,
+    groups: HashMap<String, Vec<String>>,
+}
+
+/// The fields of a [`StoredTask`], for `--strict-parse` to tell typos apart.
+const RECORD_FIELDS: [&str; 4] = ["timestamp", "tags", "interval", "created"];
+
+/// A [`Task`] the way it's stored in the data file.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "schema",
+    schemars(rename = "Task", description = "A task, under its name.")
+)]
+struct StoredTask {
+    /// When the task was last done, in UTC.
+    timestamp: String,
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    tags:      BTreeSet<String>,
+    /// How often the task is meant to be done, like `7d` or `1d12h`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    interval:  Option<String>,
+    /// When the task was first done, in UTC.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created:   Option<String>,
+}
+
+/// The data file of the [`CURRENT_VERSION`], only for its schema, since it's
+/// read and written through [`Value`]s to upgrade older versions.
+#[cfg(feature = "schema")]
+#[derive(schemars::JsonSchema)]
+#[schemars(
+    title = "loago data file",
+    description = "The tasks that loago keeps track of."
+)]
+#[allow(dead_code)]
+struct StoredData {
+    version: u64,
+    tasks:   BTreeMap<String, StoredTask>,
+    /// The tasks that get done together, by group name.
+    #[serde(default)]
+    groups:  BTreeMap<String, Vec<String>>,
+}
+
+/// The JSON Schema of the data file that this build writes.
+#[cfg(feature = "schema")]
+pub fn schema() -> Value {
+    let mut schema = schemars::schema_for!(StoredData).to_value();
+    // Older versions are still read, but they're upgraded on the next save.
+    schema["properties"][VERSION_KEY] = json!({ "const": CURRENT_VERSION });
+    schema
+}
+
+impl StoredTask {
+    fn from_task(task: &Task, format: TimestampFormat) -> Self {
+        Self {
+            timestamp: format.format(task.timestamp),
+            tags:      task.tags.clone(),
+            interval:  task.interval.map(format_duration),
+            created:   Some(format.format(task.created)),
+        }
+    }
+
+    fn new(timestamp: String) -> Self {
+        Self {
+            timestamp,
+            tags: BTreeSet::new(),
+            interval: None,
+            created: None,
+        }
+    }
+}
+
+impl RawData {
+    /// Timestamps get parsed by [`Tasks`] itself, then the rest of each record
+    /// is attached to the successfully parsed tasks.
+    fn split(
+        self,
+    ) -> (HashMap<String, String>, Vec<(String, StoredTask)>, Groups) {
+        let mut timestamps = HashMap::with_capacity(self.tasks.len());
+        let mut records = Vec::with_capacity(self.tasks.len());
+        for (key, mut task) in self.tasks {
+            timestamps.insert(key.clone(), mem::take(&mut task.timestamp));
+            records.push((key, task));
+        }
+        (timestamps, records, self.groups.into())
+    }
+}
+
+fn attach(
+    tasks: &mut Tasks,
+    records: Vec<(String, StoredTask)>,
+) -> Result<(), Error> {
+    for (key, record) in records {
+        let interval = record.interval.as_deref().map(parse_duration);
+        tasks.set_interval(&key, interval.transpose()?);
+        tasks.tag(&key, record.tags);
+        if let Some(created) = record.created {
+            let created =
+                TimestampError::parse(&key, TimestampField::Created, created)?;
+            tasks.set_created(&key, created);
+        }
+    }
+    Ok(())
+}
+
+/// How the data file is indented when saving.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Indent {
+    /// This many spaces per level, with 0 meaning no whitespace at all.
+    Spaces(usize),
+    /// A tab per level.
+    Tab,
+}
+
+impl Default for Indent {
+    fn default() -> Self {
+        Self::Spaces(2)
+    }
+}
+
+impl FromStr for Indent {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if input == "tab" {
+            return Ok(Self::Tab);
+        }
+        input
+            .parse()
+            .map(Self::Spaces)
+            .map_err(|_| "expected a number of spaces or \"tab\"".to_owned())
+    }
+}
+
+/// How timestamps are written when saving. Loading understands both.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum TimestampFormat {
+    /// `2023-12-20T18:30:00`, implicitly in UTC.
+    #[default]
+    Naive,
+    /// `2023-12-20T18:30:00Z`, saying that it's in UTC.
+    Rfc3339,
+}
+
+impl TimestampFormat {
+    fn format(self, timestamp: NaiveDateTime) -> String {
+        match self {
+            Self::Naive => format_timestamp(timestamp),
+            Self::Rfc3339 => format_timestamp_rfc3339(timestamp),
+        }
+    }
+}
+
+/// Reads and writes [`Data`] from and to a json data file.
+///
+/// Files of older format versions are upgraded when loading, so that every
+/// save writes the [`CURRENT_VERSION`].
+///
+/// Files with the `.jsonc` extension can have `//` and `/* */` comments in
+/// them. Saving writes plain json, so the comments are lost.
+pub struct JsonRepo {
+    path:        PathBuf,
+    indent:      Indent,
+    timestamps:  TimestampFormat,
+    dry_run:     bool,
+    allow_empty: bool,
+    strict:      bool,
+    /// The data file's [`Stamp`] when it was last read.
+    last_read:   Cell<Option<Stamp>>,
+    /// The keys that the data file had more than once when it was last read.
+    duplicates:  RefCell<Vec<String>>,
+}
+
+/// When a file was last modified, and how big it is, to tell that it changed
+/// even if it did so within the same second, on file systems that don't
+/// record more precise modification times.
+type Stamp = (SystemTime, u64);
+
+impl JsonRepo {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            indent: Indent::default(),
+            timestamps: TimestampFormat::default(),
+            dry_run: false,
+            allow_empty: false,
+            strict: false,
+            last_read: Cell::new(None),
+            duplicates: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn with_indent(self, indent: Indent) -> Self {
+        Self { indent, ..self }
+    }
+
+    pub fn with_timestamps(self, timestamps: TimestampFormat) -> Self {
+        Self { timestamps, ..self }
+    }
+
+    /// Make saving do nothing, for seeing what commands would change without
+    /// changing it.
+    pub fn with_dry_run(self, dry_run: bool) -> Self {
+        Self { dry_run, ..self }
+    }
+
+    /// Let saving remove every task from a data file that has some. Without
+    /// this, that fails with [`Error::WouldEmpty`], since it's much more
+    /// likely to be a filter that matched more than it was meant to.
+    pub fn with_allow_empty(self, allow_empty: bool) -> Self {
+        Self {
+            allow_empty,
+            ..self
+        }
+    }
+
+    /// Fail to load a data file with fields that aren't known, like a typo
+    /// in a record. Without this, they're ignored, so that files written by
+    /// newer versions of loago can still be read.
+    pub fn with_strict(self, strict: bool) -> Self {
+        Self { strict, ..self }
+    }
+
+    /// The keys that the data file had more than once when it was last read,
+    /// like a task name, in the order they were found. Only the last of each
+    /// is loaded, and with [`JsonRepo::with_strict`], loading fails instead.
+    pub fn duplicates(&self) -> Vec<String> {
+        self.duplicates.borrow().clone()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn load(&self) -> Result<Data, Error> {
+        let (timestamps, records, groups) = self.read()?.split();
+        let mut tasks = Tasks::try_from(timestamps)?;
+        attach(&mut tasks, records)?;
+        Ok(Data { tasks, groups })
+    }
+
+    /// Skips tasks with invalid timestamps instead of failing, see
+    /// [`Tasks::try_from_lenient`]. A data file that doesn't exist is the same
+    /// as an empty one.
+    ///
+    /// Only the tasks in `only` are loaded, if it's there, so that the rest
+    /// aren't parsed for nothing.
+    pub fn load_lenient(
+        &self,
+        only: Option<&[String]>,
+    ) -> Result<(Data, InvalidTimestamps), Error> {
+        let mut raw = match self.read() {
+            Err(Error::Io(error)) if error.kind() == ErrorKind::NotFound => {
+                RawData::default()
+            },
+            raw => raw?,
+        };
+        if let Some(only) = only {
+            raw.tasks.retain(|task, _| only.contains(task));
+        }
+        let (timestamps, records, groups) = raw.split();
+        let (mut tasks, invalid) = Tasks::try_from_lenient(timestamps);
+        attach(&mut tasks, records)?;
+        Ok((Data { tasks, groups }, invalid))
+    }
+
+    /// Tasks and groups are written sorted by name, so that saving the same
+    /// data always gives the same file. In a dry run, nothing is written, but
+    /// the report still says what would've been.
+    ///
+    /// # Errors
+    /// [`Error::WouldEmpty`] if this would remove every task from the data
+    /// file, unless [`JsonRepo::with_allow_empty`].
+    pub fn save(&self, data: Data) -> Result<SaveReport, Error> {
+        if !self.allow_empty && data.tasks.is_empty() && self.has_tasks()? {
+            return Err(Error::WouldEmpty);
+        }
+        let tasks: BTreeMap<&str, StoredTask> = data
+            .tasks
+            .iter()
+            .map(|(key, task)| {
+                (key, StoredTask::from_task(task, self.timestamps))
+            })
+            .collect();
+        let groups: HashMap<String, Vec<String>> = data.groups.into();
+        let groups: BTreeMap<String, Vec<String>> =
+            groups.into_iter().collect();
+        let data = json!({
+            VERSION_KEY: CURRENT_VERSION,
+            TASKS_KEY: tasks,
+            GROUPS_KEY: groups,
+        });
+        let json = self.serialize(&data)?;
+        let report = SaveReport {
+            path:    self.path.clone(),
+            bytes:   json.len(),
+            written: !self.dry_run,
+            changed: fs::read(&self.path).map_or(true, |old| old != json),
+        };
+        if self.dry_run {
+            return Ok(report);
+        }
+        self.write_atomically(&json)?;
+        Ok(report)
+    }
+
+    /// Write a temporary file next to the data file, and rename it over the
+    /// data file, so that a write that's cut short, by a full disk or a killed
+    /// process, leaves the data file as it was. A data file that's a symlink
+    /// stays one, with the file it points to getting replaced instead.
+    fn write_atomically(&self, json: &[u8]) -> Result<(), Error> {
+        let target =
+            fs::canonicalize(&self.path).unwrap_or_else(|_| self.path.clone());
+        let temporary = temporary_path(&target);
+        let written = write_temporary(&temporary, json, &target)
+            .and_then(|()| Ok(fs::rename(&temporary, &target)?));
+        if written.is_err() {
+            // Whatever made it into it is of no use.
+            let _ = fs::remove_file(&temporary);
+        }
+        written
+    }
+
+    /// Whether the data file currently has any tasks in it. A data file that
+    /// doesn't exist doesn't.
+    fn has_tasks(&self) -> Result<bool, Error> {
+        match self.read() {
+            Err(Error::Io(error)) if error.kind() == ErrorKind::NotFound => {
+                Ok(false)
+            },
+            raw => Ok(!raw?.tasks.is_empty()),
+        }
+    }
+
+    fn serialize(&self, data: &Value) -> Result<Vec<u8>, Error> {
+        let indent = match self.indent {
+            Indent::Spaces(0) => return Ok(serde_json::to_vec(data)?),
+            Indent::Spaces(width) => " ".repeat(width),
+            Indent::Tab => "\t".to_owned(),
+        };
+        let mut json = Vec::new();
+        let formatter = PrettyFormatter::with_indent(indent.as_bytes());
+        let mut serializer = Serializer::with_formatter(&mut json, formatter);
+        data.serialize(&mut serializer)?;
+        Ok(json)
+    }
+
+    /// Whether the data file changed since it was last read, because another
+    /// `loago` wrote to it for example. For long-running commands to only
+    /// reload it when they have to.
+    ///
+    /// Before it's first read, only a data file that exists changed.
+    pub fn changed(&self) -> Result<bool, Error> {
+        Ok(self.stamp()? != self.last_read.get())
+    }
+
+    /// The data file's [`Stamp`], if it exists.
+    fn stamp(&self) -> Result<Option<Stamp>, Error> {
+        match fs::metadata(&self.path) {
+            Ok(metadata) => Ok(Some((metadata.modified()?, metadata.len()))),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// The format version that the data file is in, 0 for the legacy one
+    /// without a `version` field. It may well be newer than the
+    /// [`CURRENT_VERSION`].
+    pub fn version(&self) -> Result<u64, Error> {
+        let data: Value = serde_json::from_str(&self.contents()?)?;
+        Ok(version_of(&data))
+    }
+
+    fn read(&self) -> Result<RawData, Error> {
+        // Taken before reading, so that a write in between counts as a change
+        // the next time.
+        self.last_read.set(self.stamp()?);
+        let contents = self.contents()?;
+        let data: Value = serde_json::from_str(&contents)?;
+        let duplicates = duplicate_keys(&contents)?;
+        if self.strict {
+            if let Some(key) = duplicates.first() {
+                let error: serde_json::Error = serde::de::Error::custom(
+                    format!("duplicate key \"{key}\""),
+                );
+                return Err(error.into());
+            }
+            ensure_known_fields(&data)?;
+        }
+        *self.duplicates.borrow_mut() = duplicates;
+        Ok(migrate(data)?.normalize())
+    }
+
+    /// The json in the data file, without the comments if it can have them.
+    fn contents(&self) -> Result<String, Error> {
+        let contents = fs::read_to_string(&self.path)?;
+        if self
+            .path
+            .extension()
+            .is_some_and(|extension| extension == JSONC_EXTENSION)
+        {
+            return Ok(strip_comments(&contents));
+        }
+        Ok(contents)
+    }
+}
+
+fn temporary_path(data_file: &Path) -> PathBuf {
+    let mut path = data_file.as_os_str().to_owned();
+    path.push(".");
+    path.push(TEMPORARY_EXTENSION);
+    PathBuf::from(path)
+}
+
+/// Write all of `json` to `path`, with the same permissions as `like` if it
+/// exists, and make sure it's on the disk before it replaces anything.
+fn write_temporary(path: &Path, json: &[u8], like: &Path) -> Result<(), Error> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    file.write_all(json)?;
+    if let Ok(metadata) = fs::metadata(like) {
+        file.set_permissions(metadata.permissions())?;
+    }
+    file.sync_all()?;
+    Ok(())
+}
+
+/// Remove `//` and `/* */` comments from json, leaving the newlines in them so
+/// that errors still point to the right line. Strings are left as is.
+fn strip_comments(jsonc: &str) -> String {
+    let mut json = String::with_capacity(jsonc.len());
+    let mut chars = jsonc.chars().peekable();
+    while let Some(character) = chars.next() {
+        match character {
+            '"' => {
+                json.push(character);
+                while let Some(character) = chars.next() {
+                    json.push(character);
+                    match character {
+                        '\\' => json.extend(chars.next()),
+                        '"' => break,
+                        _ => {},
+                    }
+                }
+            },
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.next_if(|&next| next != '\n').is_some() {}
+            },
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for character in chars.by_ref() {
+                    if previous == '*' && character == '/' {
+                        break;
+                    }
+                    if character == '\n' {
+                        json.push('\n');
+                    }
+                    previous = character;
+                }
+                json.push(' ');
+            },
+            _ => json.push(character),
+        }
+    }
+    json
+}
+
+/// The form task and group names are stored and looked up in: Unicode
+/// normalization form C, so that the same visible name is always the same
+/// task, whether its accents were typed as separate characters or not.
+pub fn normalize_name(name: &str) -> String {
+    name.nfc().collect()
+}
+
+impl RawData {
+    /// Names written by older versions, or by hand, might not be normalized
+    /// yet, see [`normalize_name`]. If that makes two tasks the same, the
+    /// more recently done one is kept.
+    fn normalize(self) -> Self {
+        let mut tasks: HashMap<String, StoredTask> =
+            HashMap::with_capacity(self.tasks.len());
+        for (key, task) in self.tasks {
+            let key = normalize_name(&key);
+            match tasks.get(&key) {
+                Some(existing)
+                    if newer(&existing.timestamp, &task.timestamp) => {},
+                _ => {
+                    tasks.insert(key, task);
+                },
+            }
+        }
+        let groups = self
+            .groups
+            .into_iter()
+            .map(|(group, members)| {
+                let members = members.iter().map(|task| normalize_name(task));
+                (normalize_name(&group), members.collect())
+            })
+            .collect();
+        Self { tasks, groups }
+    }
+}
+
+/// Whether the `existing` timestamp is at least as recent as the `other`
+/// one. Timestamps that don't parse lose, so that a valid one is kept.
+fn newer(existing: &str, other: &str) -> bool {
+    match (parse_timestamp(existing), parse_timestamp(other)) {
+        (Ok(existing), Ok(other)) => existing >= other,
+        (Ok(_), Err(_)) => true,
+        (Err(_), _) => false,
+    }
+}
+
+/// The `version` field of the data file, or 0 for a legacy file, see
+/// [`migrate`].
+fn version_of(data: &Value) -> u64 {
+    data.get(VERSION_KEY).and_then(Value::as_u64).unwrap_or(0)
+}
+
+/// Bring data of any known format version to the shape of the current one.
+///
+/// A legacy file is recognized by not having a numeric `version` field; a
+/// task that happens to be called "version" holds a timestamp string, so it
+/// doesn't get confused for one.
+fn migrate(mut data: Value) -> Result<RawData, Error> {
+    let version = version_of(&data);
+    match version {
+        0 => Ok(RawData {
+            tasks: timestamps_only(serde_json::from_value(data)?),
+            ..Default::default()
+        }),
+        1 | 2 => Ok(RawData {
+            tasks:  timestamps_only(take(&mut data, TASKS_KEY)?),
+            groups: take(&mut data, GROUPS_KEY)?,
+        }),
+        3 | CURRENT_VERSION => Ok(RawData {
+            tasks:  take(&mut data, TASKS_KEY)?,
+            groups: take(&mut data, GROUPS_KEY)?,
+        }),
+        newer => Err(Error::UnsupportedVersion {
+            found:     newer,
+            supported: CURRENT_VERSION,
+        }),
+    }
+}
+
+/// Every key that an object in `json` has more than once, which parsing it
+/// into a [`Value`] silently keeps only the last one of.
+fn duplicate_keys(json: &str) -> Result<Vec<String>, serde_json::Error> {
+    let mut duplicates = Vec::new();
+    DuplicateKeys(&mut duplicates)
+        .deserialize(&mut serde_json::Deserializer::from_str(json))?;
+    Ok(duplicates)
+}
+
+/// Walks a json value, collecting the keys that its objects repeat.
+struct DuplicateKeys<'a>(&'a mut Vec<String>);
+
+impl<'de> DeserializeSeed<'de> for DuplicateKeys<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for DuplicateKeys<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("any json value")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let mut seen = HashSet::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if !seen.insert(key.clone()) && !self.0.contains(&key) {
+                self.0.push(key);
+            }
+            map.next_value_seed(DuplicateKeys(&mut *self.0))?;
+        }
+        Ok(())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while seq
+            .next_element_seed(DuplicateKeys(&mut *self.0))?
+            .is_some()
+        {}
+        Ok(())
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_i64<E>(self, _: i64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_u64<E>(self, _: u64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_str<E>(self, _: &str) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_unit<E>(self) -> Result<(), E> {
+        Ok(())
+    }
+}
+
+/// Fail on the first field that the data file's version doesn't have, naming
+/// the task it's in, if it's in one.
+fn ensure_known_fields(data: &Value) -> Result<(), serde_json::Error> {
+    let unknown = |field: &str, place: &str, expected: &[&str]| {
+        serde::de::Error::custom(format!(
+            "unknown field \"{field}\" {place}, expected one of {}",
+            expected.join(", ")
+        ))
+    };
+    let version = version_of(data);
+    // Without a version, every field is a task.
+    let Some(object) = data.as_object().filter(|_| version > 0) else {
+        return Ok(());
+    };
+    let sections = [VERSION_KEY, TASKS_KEY, GROUPS_KEY];
+    let is_section = |key: &&String| sections.contains(&key.as_str());
+    if let Some(field) = object.keys().find(|key| !is_section(key)) {
+        return Err(unknown(field, "in the data file", &sections));
+    }
+    // Before version 3, tasks were just their timestamp.
+    if version < 3 {
+        return Ok(());
+    }
+    let records = object.get(TASKS_KEY).and_then(Value::as_object);
+    for (task, record) in records.into_iter().flatten() {
+        let Some(record) = record.as_object() else {
+            continue;
+        };
+        let is_field = |key: &&String| RECORD_FIELDS.contains(&key.as_str());
+        if let Some(field) = record.keys().find(|key| !is_field(key)) {
+            let place = format!("in task \"{task}\"");
+            return Err(unknown(field, &place, &RECORD_FIELDS));
+        }
+    }
+    Ok(())
+}
+
+/// Before version 3, every task was stored as just its timestamp.
+fn timestamps_only(
+    tasks: HashMap<String, String>,
+) -> HashMap<String, StoredTask> {
+    tasks
+        .into_iter()
+        .map(|(key, timestamp)| (key, StoredTask::new(timestamp)))
+        .collect()
+}
+
+/// Deserialize a section of the data file, an absent section is the same as
+/// an empty one.
+fn take<T: DeserializeOwned + Default>(
+    data: &mut Value,
+    key: &str,
+) -> Result<T, Error> {
+    match data.get_mut(key) {
+        Some(section) => Ok(serde_json::from_value(section.take())?),
+        None => Ok(T::default()),
+    }
+}
+
+#[cfg(test)]
+mod versions {
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::Path;
+
+    use chrono::Duration;
+    use chrono::NaiveDate;
+    use loago::errors::AsErrStr;
+    use serde_json::Value;
+
+    use super::format_size;
+    use super::normalize_name;
+    use super::strip_comments;
+    use super::temporary_path;
+    use super::Data;
+    use super::Error;
+    use super::Indent;
+    use super::JsonRepo;
+    use super::TimestampFormat;
+    use super::CURRENT_VERSION;
+
+    fn repo_with(dir: &Path, contents: &str) -> JsonRepo {
+        let path = dir.join("loago.json");
+        fs::write(&path, contents).unwrap();
+        JsonRepo::new(path)
+    }
+
+    #[test]
+    fn legacy_upgrades() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = repo_with(dir.path(), r#"{"dust": "2023-12-20T00:00:00"}"#);
+        let data = repo.load().unwrap();
+        repo.save(data).unwrap();
+        let saved: Value =
+            serde_json::from_str(&fs::read_to_string(&repo.path).unwrap())
+                .unwrap();
+        assert_eq!(saved["version"], CURRENT_VERSION);
+        assert_eq!(saved["tasks"]["dust"]["timestamp"], "2023-12-20T00:00:00");
+        let reloaded: HashMap<String, String> =
+            repo.load().unwrap().tasks.into();
+        assert_eq!(reloaded["dust"], "2023-12-20T00:00:00");
+    }
+
+    #[test]
+    fn legacy_task_named_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo =
+            repo_with(dir.path(), r#"{"version": "2023-12-20T00:00:00"}"#);
+        let tasks: HashMap<String, String> = repo.load().unwrap().tasks.into();
+        assert_eq!(tasks["version"], "2023-12-20T00:00:00");
+    }
+
+    #[test]
+    fn newer_version_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = repo_with(dir.path(), r#"{"version": 999, "tasks": {}}"#);
+        assert!(repo.load().is_err());
+    }
+
+    #[test]
+    fn groups_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = repo_with(dir.path(), r#"{"version": 1, "tasks": {}}"#);
+        let mut data = repo.load().unwrap();
+        data.groups.add("clean-kitchen", ["sweep"]);
+        repo.save(data).unwrap();
+        let groups = repo.load().unwrap().groups;
+        assert_eq!(groups.expand(["clean-kitchen"]), ["sweep"]);
+    }
+
+    #[test]
+    fn tags_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = repo_with(dir.path(), r#"{"dust": "2023-12-20T00:00:00"}"#);
+        let mut data = repo.load().unwrap();
+        data.tasks.tag("dust", ["room"]);
+        repo.save(data).unwrap();
+        let data = repo.load().unwrap();
+        let (_, dust) = data.tasks.iter().next().unwrap();
+        assert!(dust.tags.contains("room"));
+    }
+
+    #[test]
+    fn interval_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = repo_with(dir.path(), r#"{"dust": "2023-12-20T00:00:00"}"#);
+        let mut data = repo.load().unwrap();
+        data.tasks.set_interval("dust", Some(Duration::hours(36)));
+        repo.save(data).unwrap();
+        let saved: Value =
+            serde_json::from_str(&fs::read_to_string(&repo.path).unwrap())
+                .unwrap();
+        assert_eq!(saved["tasks"]["dust"]["interval"], "36h");
+        let data = repo.load().unwrap();
+        let (_, dust) = data.tasks.iter().next().unwrap();
+        assert_eq!(dust.interval, Some(Duration::hours(36)));
+    }
+
+    #[test]
+    fn indent() {
+        let dir = tempfile::tempdir().unwrap();
+        let contents = r#"{"dust": "2023-12-20T00:00:00"}"#;
+        let saved_with = |indent| {
+            let repo = repo_with(dir.path(), contents).with_indent(indent);
+            repo.save(repo.load().unwrap()).unwrap();
+            fs::read_to_string(&repo.path).unwrap()
+        };
+        assert!(saved_with(Indent::default()).contains("\n  \"tasks\""));
+        assert!(saved_with(Indent::Spaces(4)).contains("\n    \"tasks\""));
+        assert!(saved_with(Indent::Tab).contains("\n\t\"tasks\""));
+        assert!(!saved_with(Indent::Spaces(0)).contains('\n'));
+        assert_eq!("tab".parse(), Ok(Indent::Tab));
+        assert_eq!("0".parse(), Ok(Indent::Spaces(0)));
+        assert!("-1".parse::<Indent>().is_err());
+    }
+
+    #[test]
+    fn saves_are_stable() {
+        let dir = tempfile::tempdir().unwrap();
+        let tasks: String = (0..50)
+            .map(|index| format!(r#""task{index}": "2023-12-20T00:00:00""#))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let repo = repo_with(dir.path(), &format!("{{{tasks}}}"));
+        let mut data = repo.load().unwrap();
+        data.groups.add("chores", ["task3", "task1", "task2"]);
+        data.groups.add("errands", ["task4"]);
+        repo.save(data).unwrap();
+        let first = fs::read(&repo.path).unwrap();
+        repo.save(repo.load().unwrap()).unwrap();
+        assert_eq!(fs::read(&repo.path).unwrap(), first);
+        let saved = String::from_utf8(first).unwrap();
+        assert!(saved.find("\"task10\": {") < saved.find("\"task2\": {"));
+        assert!(saved.find("\"chores\"") < saved.find("\"errands\""));
+    }
+
+    #[test]
+    fn missing_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = JsonRepo::new(dir.path().join("loago.json"));
+        let (data, invalid) = repo.load_lenient(None).unwrap();
+        assert_eq!(data.tasks.iter().count(), 0);
+        assert!(invalid.is_empty());
+        assert!(!repo.path.exists());
+        assert!(repo.load().is_err());
+    }
+
+    #[test]
+    fn rfc3339_timestamps() {
+        let dir = tempfile::tempdir().unwrap();
+        let contents = r#"{"version": 3, "tasks": {
+            "dust": {"timestamp": "2023-12-20T00:00:00"},
+            "mop": {"timestamp": "2023-12-20T02:00:00+02:00"}
+        }}"#;
+        let repo = repo_with(dir.path(), contents)
+            .with_timestamps(TimestampFormat::Rfc3339);
+        let data = repo.load().unwrap();
+        repo.save(data).unwrap();
+        let saved: Value =
+            serde_json::from_str(&fs::read_to_string(&repo.path).unwrap())
+                .unwrap();
+        assert_eq!(saved["tasks"]["dust"]["timestamp"], "2023-12-20T00:00:00Z");
+        assert_eq!(saved["tasks"]["mop"]["timestamp"], "2023-12-20T00:00:00Z");
+        let repo = JsonRepo::new(repo.path);
+        repo.save(repo.load().unwrap()).unwrap();
+        let tasks: HashMap<String, String> = repo.load().unwrap().tasks.into();
+        assert_eq!(tasks["mop"], "2023-12-20T00:00:00");
+    }
+
+    #[test]
+    fn created_defaults_to_timestamp() {
+        let dir = tempfile::tempdir().unwrap();
+        let contents = r#"{"version": 3, "tasks": {
+            "dust": {"timestamp": "2023-12-20T00:00:00"}
+        }}"#;
+        let repo = repo_with(dir.path(), contents);
+        let mut data = repo.load().unwrap();
+        data.tasks.update_at(
+            "dust",
+            NaiveDate::from_ymd_opt(2024, 1, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap(),
+        );
+        repo.save(data).unwrap();
+        let saved: Value =
+            serde_json::from_str(&fs::read_to_string(&repo.path).unwrap())
+                .unwrap();
+        assert_eq!(saved["tasks"]["dust"]["timestamp"], "2024-01-01T00:00:00");
+        assert_eq!(saved["tasks"]["dust"]["created"], "2023-12-20T00:00:00");
+        let tasks: HashMap<String, String> = repo.load().unwrap().tasks.into();
+        assert_eq!(tasks["dust"], "2024-01-01T00:00:00");
+    }
+
+    #[test]
+    fn invalid_created() {
+        let dir = tempfile::tempdir().unwrap();
+        let contents = r#"{"version": 4, "tasks": {
+            "dust": {"timestamp": "2023-12-20T00:00:00", "created": "nope"}
+        }}"#;
+        let Err(error) = repo_with(dir.path(), contents).load() else {
+            panic!("the created date is invalid");
+        };
+        assert!(error
+            .as_str()
+            .starts_with("failed to parse \"created\" date for task \"dust\""));
+    }
+
+    #[test]
+    fn comments() {
+        let jsonc = "{\n  // dusting\n  \"dust\": \"a // b /* c */\", /* multi\nline */ \"x\\\"//\": 1\n}";
+        assert_eq!(
+            strip_comments(jsonc),
+            "{\n  \n  \"dust\": \"a // b /* c */\", \n  \"x\\\"//\": 1\n}"
+        );
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("loago.jsonc");
+        let contents = r#"{
+            // the old format, but with comments
+            "dust": "2023-12-20T00:00:00" /* done after the party */
+        }"#;
+        fs::write(&path, contents).unwrap();
+        let repo = JsonRepo::new(path);
+        let tasks: HashMap<String, String> = repo.load().unwrap().tasks.into();
+        assert_eq!(tasks["dust"], "2023-12-20T00:00:00");
+        let plain = repo_with(dir.path(), contents);
+        assert!(plain.load().is_err());
+    }
+
+    #[test]
+    fn names_normalized() {
+        let dir = tempfile::tempdir().unwrap();
+        let contents = r#"{"version": 4, "tasks": {
+            "cafe\u0301": {"timestamp": "2023-12-20T00:00:00"},
+            "caf\u00e9": {"timestamp": "2023-11-20T00:00:00"}
+        }, "groups": {"cafe\u0301s": ["cafe\u0301"]}}"#;
+        let data = repo_with(dir.path(), contents).load().unwrap();
+        let tasks: HashMap<String, String> = data.tasks.into();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks["caf\u{e9}"], "2023-12-20T00:00:00");
+        assert_eq!(data.groups.expand(vec!["caf\u{e9}s".to_owned()]), [
+            "caf\u{e9}"
+        ]);
+        assert_eq!(normalize_name("cafe\u{301}"), "caf\u{e9}");
+    }
+
+    #[test]
+    fn refuses_to_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let contents = r#"{"version": 4, "tasks": {
+            "dust": {"timestamp": "2023-12-20T00:00:00"}
+        }}"#;
+        let repo = repo_with(dir.path(), contents);
+        let mut data = repo.load().unwrap();
+        data.tasks.remove("dust");
+        assert!(matches!(repo.save(data), Err(Error::WouldEmpty)));
+        assert_eq!(fs::read_to_string(&repo.path).unwrap(), contents);
+        let repo = repo.with_allow_empty(true);
+        let mut data = repo.load().unwrap();
+        data.tasks.remove("dust");
+        repo.save(data).unwrap();
+        let repo = repo.with_allow_empty(false);
+        assert!(repo.load().unwrap().tasks.is_empty());
+        repo.save(Data::default()).unwrap();
+    }
+
+    #[test]
+    fn only_some_tasks() {
+        let dir = tempfile::tempdir().unwrap();
+        let contents = r#"{"version": 4, "tasks": {
+            "dust": {"timestamp": "2023-12-20T00:00:00", "tags": ["room"]},
+            "mop": {"timestamp": "yesterday"},
+            "vacuum": {"timestamp": "2023-12-20T00:00:00"}
+        }}"#;
+        let repo = repo_with(dir.path(), contents);
+        let only = ["dust".to_owned(), "sweep".to_owned()];
+        let (data, invalid) = repo.load_lenient(Some(&only)).unwrap();
+        let names: Vec<&str> =
+            data.tasks.iter().map(|(task, _)| task).collect();
+        assert_eq!(names, ["dust"]);
+        assert!(data
+            .tasks
+            .iter()
+            .all(|(_, task)| task.tags.contains("room")));
+        assert!(invalid.is_empty());
+        assert_eq!(repo.load_lenient(None).unwrap().1.len(), 1);
+    }
+
+    #[test]
+    fn changed() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = JsonRepo::new(dir.path().join("loago.json"));
+        assert!(!repo.changed().unwrap());
+        let contents = r#"{"version": 4, "tasks": {
+            "dust": {"timestamp": "2023-12-20T00:00:00"}
+        }}"#;
+        fs::write(&repo.path, contents).unwrap();
+        assert!(repo.changed().unwrap());
+        let mut data = repo.load().unwrap();
+        assert!(!repo.changed().unwrap());
+        data.tasks.update("vacuum");
+        repo.save(data).unwrap();
+        assert!(repo.changed().unwrap());
+        repo.load_lenient(None).unwrap();
+        assert!(!repo.changed().unwrap());
+    }
+
+    #[test]
+    fn failed_saves_keep_the_data_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let contents = r#"{"version": 4, "tasks": {
+            "dust": {"timestamp": "2023-12-20T00:00:00"}
+        }}"#;
+        let repo = repo_with(dir.path(), contents);
+        let temporary = temporary_path(&fs::canonicalize(&repo.path).unwrap());
+        // Nothing can be written where the temporary file goes.
+        fs::create_dir(&temporary).unwrap();
+        let mut data = repo.load().unwrap();
+        data.tasks.update("vacuum");
+        assert!(repo.save(data).is_err());
+        assert_eq!(fs::read_to_string(&repo.path).unwrap(), contents);
+        fs::remove_dir(&temporary).unwrap();
+        let mut data = repo.load().unwrap();
+        data.tasks.update("vacuum");
+        repo.save(data).unwrap();
+        assert!(repo.load().unwrap().tasks.contains("vacuum"));
+        assert!(!temporary.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn saves_through_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = repo_with(dir.path(), r#"{"version": 4, "tasks": {}}"#);
+        let link = dir.path().join("link.json");
+        std::os::unix::fs::symlink(&repo.path, &link).unwrap();
+        let linked = JsonRepo::new(link.clone());
+        let mut data = linked.load().unwrap();
+        data.tasks.update("dust");
+        linked.save(data).unwrap();
+        assert!(fs::symlink_metadata(&link).unwrap().is_symlink());
+        assert!(repo.load().unwrap().tasks.contains("dust"));
+    }
+
+    #[test]
+    fn save_reports() {
+        let dir = tempfile::tempdir().unwrap();
+        let contents = r#"{"version": 4, "tasks": {
+            "dust": {"timestamp": "2023-12-20T00:00:00"}
+        }}"#;
+        let repo = repo_with(dir.path(), contents).with_dry_run(true);
+        let report = repo.save(repo.load().unwrap()).unwrap();
+        assert!(!report.written);
+        assert!(report.changed);
+        assert_eq!(fs::read_to_string(&repo.path).unwrap(), contents);
+        let repo = repo.with_dry_run(false);
+        let written = repo.save(repo.load().unwrap()).unwrap();
+        assert_eq!(written.path, repo.path);
+        assert_eq!(
+            written.bytes,
+            fs::metadata(&repo.path).unwrap().len() as usize
+        );
+        assert_eq!(written.bytes, report.bytes);
+        assert!(written.changed);
+        assert!(!repo.save(repo.load().unwrap()).unwrap().changed);
+        assert_eq!(
+            written.to_string(),
+            format!("wrote {} B to {}", written.bytes, repo.path.display())
+        );
+        assert_eq!(format_size(999), "999 B");
+        assert_eq!(format_size(1234), "1.2 KB");
+        assert_eq!(format_size(5_678_901), "5.7 MB");
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn schema_covers_saved_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = repo_with(dir.path(), r#"{"dust": "2023-12-20T00:00:00"}"#);
+        let mut data = repo.load().unwrap();
+        data.tasks.tag("dust", ["room"]);
+        data.tasks.set_interval("dust", Some(Duration::days(7)));
+        repo.save(data).unwrap();
+        let saved: Value =
+            serde_json::from_str(&fs::read_to_string(&repo.path).unwrap())
+                .unwrap();
+        let schema = super::schema();
+        assert_eq!(schema["properties"]["version"]["const"], CURRENT_VERSION);
+        for key in saved.as_object().unwrap().keys() {
+            assert!(schema["properties"].get(key).is_some(), "{key}");
+        }
+        let task = &schema["$defs"]["Task"]["properties"];
+        for key in saved["tasks"]["dust"].as_object().unwrap().keys() {
+            assert!(task.get(key).is_some(), "{key}");
+        }
+    }
+
+    #[test]
+    fn strict_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let typo = r#"{"version": 4, "tasks": {
+            "vacuum": {"timestamp": "2023-12-20T00:00:00", "intrval": "7d"}
+        }}"#;
+        let repo = repo_with(dir.path(), typo);
+        assert!(repo.load().is_ok());
+        let Err(error) = repo.with_strict(true).load() else {
+            panic!("the typo was ignored");
+        };
+        let message = error.to_string();
+        assert!(
+            message.contains("\"intrval\" in task \"vacuum\""),
+            "{message}"
+        );
+        let section = r#"{"version": 2, "tasks": {}, "grops": {}}"#;
+        let repo = repo_with(dir.path(), section).with_strict(true);
+        assert!(repo.load().is_err());
+        for valid in [
+            r#"{"dust": "2023-12-20T00:00:00"}"#,
+            r#"{"version": 2, "tasks": {"dust": "2023-12-20T00:00:00"}}"#,
+            r#"{"version": 4, "tasks": {"dust": {
+                "timestamp": "2023-12-20T00:00:00",
+                "tags": ["room"],
+                "interval": "7d",
+                "created": "2023-12-01T00:00:00"
+            }}, "groups": {}}"#,
+        ] {
+            let repo = repo_with(dir.path(), valid).with_strict(true);
+            assert!(repo.load().is_ok(), "{valid}");
+        }
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let contents = r#"{"version": 4, "tasks": {
+            "vacuum": {"timestamp": "2023-12-20T00:00:00"},
+            "dust": {"timestamp": "2023-12-20T00:00:00", "tags": ["a", "a"]},
+            "vacuum": {"timestamp": "2023-12-21T00:00:00"},
+            "mop": {"timestamp": "2023-12-20T00:00:00", "tags": []},
+            "mop": {"timestamp": "2023-12-19T00:00:00", "timestamp": "x"}
+        }}"#;
+        let repo = repo_with(dir.path(), contents);
+        assert!(repo.duplicates().is_empty());
+        let data = repo.load_lenient(None).unwrap().0;
+        assert_eq!(repo.duplicates(), ["vacuum", "mop", "timestamp"]);
+        assert_eq!(data.tasks.iter().count(), 2);
+        let repo = repo.with_strict(true);
+        assert!(matches!(repo.load(), Err(Error::Json(_))));
+        let repo = repo_with(dir.path(), r#"{"version": 4, "tasks": {}}"#);
+        repo.load().unwrap();
+        assert!(repo.duplicates().is_empty());
+    }
+}
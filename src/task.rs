@@ -0,0 +1,75 @@
+use std::collections::BTreeSet;
+
+use chrono::Duration;
+use chrono::NaiveDateTime;
+
+/// Everything that's stored about a single task.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Task {
+    /// When the task was last done.
+    pub timestamp: NaiveDateTime,
+    /// When the task was first done, or created, which doing it again doesn't
+    /// change.
+    pub created:   NaiveDateTime,
+    /// Labels to filter tasks by, see [`crate::Tasks::keep_tagged`].
+    pub tags:      BTreeSet<String>,
+    /// How often the task is meant to be done, see [`crate::due`].
+    pub interval:  Option<Duration>,
+}
+
+impl Task {
+    /// A task first and last done at `timestamp`, with nothing else known
+    /// about it.
+    pub fn new(timestamp: NaiveDateTime) -> Self {
+        Self {
+            timestamp,
+            created: timestamp,
+            tags: BTreeSet::new(),
+            interval: None,
+        }
+    }
+
+    /// Whether the task has an interval, and it's been longer than that since
+    /// it was done `elapsed` ago.
+    pub fn is_overdue(&self, elapsed: Duration) -> bool {
+        self.interval.is_some_and(|interval| elapsed > interval)
+    }
+
+    /// How long is left until the task is due, given it was done `elapsed`
+    /// ago, which is negative once it's overdue, or `None` without an
+    /// interval. It stops at the longest [`Duration`] there is either way,
+    /// instead of overflowing for timestamps that are far in the past or the
+    /// future.
+    pub fn remaining(&self, elapsed: Duration) -> Option<Duration> {
+        self.interval
+            .map(|interval| saturating_sub(interval, elapsed))
+    }
+
+    /// How the task is shown when it was done `elapsed` ago: whether it's
+    /// overdue and by how much, see [`crate::due`], with `due_to_string` if
+    /// it has an interval, or else just `elapsed` with `ago_to_string`.
+    pub fn due_or_ago<F, G>(
+        &self,
+        elapsed: Duration,
+        ago_to_string: F,
+        due_to_string: G,
+    ) -> String
+    where
+        F: Fn(Duration) -> String,
+        G: Fn(Duration) -> String,
+    {
+        match self.remaining(elapsed) {
+            Some(remaining) => crate::due(remaining, due_to_string),
+            None => ago_to_string(elapsed),
+        }
+    }
+}
+
+/// `a - b`, stopping at the longest [`Duration`] instead of overflowing.
+pub(crate) fn saturating_sub(a: Duration, b: Duration) -> Duration {
+    a.checked_sub(&b).unwrap_or(if b < Duration::zero() {
+        Duration::max_value()
+    } else {
+        Duration::min_value()
+    })
+}
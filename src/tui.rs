@@ -0,0 +1,191 @@
+//! `loago tui`, a list of the tasks to move through, and do or remove the
+//! highlighted one with a single key.
+//!
+//! The data file is only locked while a change is written, not for as long as
+//! the list is open, so other `loago`s keep working in the meantime.
+
+use std::time::Duration;
+
+use loago::errors::AsErrStr;
+use loago::errors::Error;
+use loago::OutputOptions;
+use ratatui::crossterm::event;
+use ratatui::crossterm::event::Event;
+use ratatui::crossterm::event::KeyCode;
+use ratatui::crossterm::event::KeyEventKind;
+use ratatui::layout::Constraint;
+use ratatui::layout::Layout;
+use ratatui::style::Style;
+use ratatui::widgets::List;
+use ratatui::widgets::ListState;
+use ratatui::widgets::Paragraph;
+use ratatui::DefaultTerminal;
+use ratatui::Frame;
+
+use crate::args::Options;
+use crate::ensure_exists;
+use crate::history::Entry;
+use crate::history::History;
+use crate::lock::DataLock;
+use crate::repo::JsonRepo;
+use crate::shared_lock;
+
+const KEYS: &str = "j/k: move  d: do  x: remove  q: quit";
+/// How often to check whether the data file changed.
+const REFRESH: Duration = Duration::from_secs(1);
+
+/// Take over the terminal until quitting, giving it back the way it was even
+/// if something fails.
+pub fn run(repo: &JsonRepo, options: &Options) -> Result<(), Error> {
+    let mut terminal = ratatui::init();
+    let result = browse(&mut terminal, repo, options);
+    ratatui::restore();
+    result
+}
+
+/// The tasks on screen, most recently done first like in `loago view`.
+struct Screen {
+    names:  Vec<String>,
+    lines:  Vec<String>,
+    state:  ListState,
+    /// What went wrong with the last change, shown instead of the keys.
+    status: Option<String>,
+}
+
+impl Screen {
+    fn load(
+        &mut self,
+        repo: &JsonRepo,
+        options: &Options,
+    ) -> Result<(), Error> {
+        let mut data = {
+            let _lock = shared_lock(repo.path())?;
+            repo.load_lenient(None)?.0
+        };
+        options.apply_default_interval(&mut data.tasks);
+        let output = data.tasks.output_records(|task, elapsed| {
+            options.unit.format(task, elapsed, false, None)
+        });
+        self.names = output.iter().map(|(name, _)| name.to_owned()).collect();
+        self.lines = output
+            .render(&OutputOptions::default())
+            .lines()
+            .map(str::to_owned)
+            .collect();
+        let selected = self.state.selected().unwrap_or_default();
+        self.state.select(
+            (!self.names.is_empty())
+                .then(|| selected.min(self.names.len() - 1)),
+        );
+        Ok(())
+    }
+
+    fn selected(&self) -> Option<&str> {
+        self.state
+            .selected()
+            .and_then(|index| self.names.get(index))
+            .map(String::as_str)
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [list, footer] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)])
+                .areas(frame.area());
+        let items = List::new(self.lines.clone())
+            .highlight_style(Style::new().reversed())
+            .highlight_symbol("> ");
+        frame.render_stateful_widget(items, list, &mut self.state);
+        let status = self.status.as_deref().unwrap_or(KEYS);
+        frame.render_widget(Paragraph::new(status), footer);
+    }
+}
+
+fn browse(
+    terminal: &mut DefaultTerminal,
+    repo: &JsonRepo,
+    options: &Options,
+) -> Result<(), Error> {
+    let mut screen = Screen {
+        names:  Vec::new(),
+        lines:  Vec::new(),
+        state:  ListState::default(),
+        status: None,
+    };
+    screen.load(repo, options)?;
+    loop {
+        terminal.draw(|frame| screen.draw(frame))?;
+        // Other `loago`s may change the data file in the meantime.
+        if !event::poll(REFRESH)? {
+            if repo.changed()? {
+                screen.load(repo, options)?;
+            }
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let change = match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Char('j') | KeyCode::Down => {
+                screen.state.select_next();
+                None
+            },
+            KeyCode::Char('k') | KeyCode::Up => {
+                screen.state.select_previous();
+                None
+            },
+            KeyCode::Char('d') | KeyCode::Enter => Some(Change::Do),
+            KeyCode::Char('x') | KeyCode::Delete => Some(Change::Remove),
+            _ => None,
+        };
+        let (Some(change), Some(task)) = (change, screen.selected()) else {
+            continue;
+        };
+        if options.dry_run {
+            screen.status = Some(String::from("(dry run, no changes written)"));
+            continue;
+        }
+        let result = change.apply(repo, task.to_owned(), options);
+        screen.status = result.err().map(|error| error.as_str().into_owned());
+        screen.load(repo, options)?;
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Change {
+    Do,
+    Remove,
+}
+
+impl Change {
+    /// Change the task in the data file the same way the command would, and
+    /// add that to the history.
+    fn apply(
+        self,
+        repo: &JsonRepo,
+        task: String,
+        options: &Options,
+    ) -> Result<(), Error> {
+        ensure_exists(repo.path(), options.create_dirs)?;
+        let _lock = DataLock::exclusive(repo.path())?;
+        let mut data = repo.load()?;
+        let command = match self {
+            Self::Do => "do",
+            Self::Remove => "remove",
+        };
+        let entry = Entry::new(command, vec![task.clone()]);
+        match self {
+            Self::Do => {
+                data.tasks.update(task);
+            },
+            Self::Remove => {
+                data.tasks.remove(&task);
+            },
+        }
+        repo.save(data)?;
+        History::of(repo.path()).record(&entry)
+    }
+}
@@ -0,0 +1,1410 @@
+//! Running the `loago` binary end to end, against a data file in a temporary
+//! directory.
+
+use std::fs;
+use std::path::PathBuf;
+
+use assert_cmd::Command;
+use serde_json::Value;
+use tempfile::TempDir;
+
+struct Sandbox {
+    dir: TempDir,
+}
+
+impl Sandbox {
+    fn new() -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("config.toml"), "").unwrap();
+        Self { dir }
+    }
+
+    fn data_file(&self) -> PathBuf {
+        self.dir.path().join("data").join("loago.json")
+    }
+
+    /// `loago` with the data file in the sandbox, and without anything from
+    /// the environment changing the output.
+    fn loago(&self, args: &[&str]) -> Command {
+        let mut command = self.without_data_file();
+        command.arg("--data-file").arg(self.data_file()).args(args);
+        command
+    }
+
+    /// `loago` with the sandbox as its data directory, for when the data file
+    /// shouldn't be passed explicitly.
+    fn without_data_file(&self) -> Command {
+        let mut command = Command::new(assert_cmd::cargo::cargo_bin!("loago"));
+        command
+            .env("LOAGO_CONFIG", self.dir.path().join("config.toml"))
+            .env("LOAGO_DATA_DIR", self.dir.path())
+            .env_remove("LOAGO_APP_NAME")
+            .env_remove("LOAGO_DATA_FILE_NAME")
+            .env_remove("LOAGO_DATA")
+            .env_remove("LOAGO_UNITS")
+            .env_remove("CLICOLOR_FORCE");
+        command
+    }
+
+    fn stdout(&self, args: &[&str]) -> String {
+        let output = self.loago(args).assert().success().get_output().clone();
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    fn saved(&self) -> Value {
+        serde_json::from_str(&fs::read_to_string(self.data_file()).unwrap())
+            .unwrap()
+    }
+}
+
+#[test]
+fn do_view_remove() {
+    let sandbox = Sandbox::new();
+    sandbox.loago(&["do", "dust", "vacuum"]).assert().success();
+    let saved = sandbox.saved();
+    assert_eq!(saved["version"], 4);
+    assert!(saved["tasks"]["dust"]["timestamp"].is_string());
+    assert_eq!(sandbox.stdout(&["view", "--names-only"]), "dust\nvacuum\n");
+    assert_eq!(sandbox.stdout(&["view", "dust"]), "dust — 0\n");
+    sandbox.loago(&["remove", "dust"]).assert().success();
+    assert!(sandbox.saved()["tasks"].get("dust").is_none());
+    assert_eq!(sandbox.stdout(&["view", "--names-only"]), "vacuum\n");
+    sandbox
+        .loago(&["view", "dust"])
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("loago: no tasks match\n");
+}
+
+#[test]
+fn reading_creates_nothing() {
+    let sandbox = Sandbox::new();
+    sandbox
+        .loago(&["view"])
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("loago: no tasks yet\n");
+    assert_eq!(sandbox.stdout(&["view", "--names-only"]), "");
+    assert!(!sandbox.data_file().exists());
+    assert_eq!(
+        sandbox.stdout(&["path"]),
+        format!("{}\n", sandbox.data_file().display())
+    );
+}
+
+#[test]
+fn missing_task_fails() {
+    let sandbox = Sandbox::new();
+    sandbox
+        .loago(&["tag", "dust", "room"])
+        .assert()
+        .code(9)
+        .stderr("loago: task \"dust\" doesn't exist\n");
+}
+
+#[test]
+fn gc_prunes_history() {
+    let sandbox = Sandbox::new();
+    for task in ["dust", "vacuum", "mop"] {
+        sandbox.loago(&["do", task]).assert().success();
+    }
+    assert_eq!(
+        sandbox.stdout(&["gc", "--keep", "1"]),
+        "dropped 2 history entries\n"
+    );
+    assert_eq!(
+        sandbox.stdout(&["gc", "--keep", "1"]),
+        "dropped 0 history entries\n"
+    );
+}
+
+#[test]
+fn tidy() {
+    let sandbox = Sandbox::new();
+    fs::create_dir_all(sandbox.data_file().parent().unwrap()).unwrap();
+    fs::write(
+        sandbox.data_file(),
+        r#"{"vacuum": "2023-12-20T00:00:00",   "dust": "2023-12-19T00:00:00"}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        sandbox.stdout(&["--dry-run", "tidy"]),
+        "the data file would be tidied\n(dry run, no changes written)\n"
+    );
+    assert_eq!(sandbox.stdout(&["tidy"]), "tidied the data file\n");
+    let tidied = fs::read_to_string(sandbox.data_file()).unwrap();
+    assert!(tidied.find("dust").unwrap() < tidied.find("vacuum").unwrap());
+    assert_eq!(
+        sandbox.saved()["tasks"]["vacuum"]["timestamp"],
+        "2023-12-20T00:00:00"
+    );
+    assert_eq!(sandbox.stdout(&["tidy"]), "the data file is already tidy\n");
+    assert_eq!(fs::read_to_string(sandbox.data_file()).unwrap(), tidied);
+}
+
+#[test]
+fn doctor() {
+    let sandbox = Sandbox::new();
+    let dir = sandbox.data_file().parent().unwrap().display().to_string();
+    let file = sandbox.data_file().display().to_string();
+    let not_yet = "doesn't exist yet, it's created on the first change";
+    assert_eq!(
+        sandbox.stdout(&["doctor"]),
+        format!(
+            "data directory: {dir} ({not_yet})\ndata file: {file} \
+             ({not_yet})\nno problems found\n"
+        )
+    );
+    assert!(!sandbox.data_file().exists());
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        sandbox.data_file(),
+        r#"{"dust": "2023-12-20T00:00:00", "mop": "nope", "dust": "2023-12-20T00:00:00"}"#,
+    )
+    .unwrap();
+    let stdout = sandbox.stdout(&["doctor"]);
+    assert!(
+        stdout.contains("format version: 0, upgraded to 4"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("\ntasks: 2\n"), "{stdout}");
+    assert!(stdout.contains("has \"dust\" more than once"), "{stdout}");
+    assert!(stdout.contains("task \"mop\" has an invalid"), "{stdout}");
+    assert!(!sandbox.data_file().with_extension("json.lock").exists());
+    fs::write(sandbox.data_file(), "{").unwrap();
+    let output = sandbox
+        .loago(&["doctor"])
+        .assert()
+        .code(20)
+        .stderr("loago: found 1 problem\n")
+        .get_output()
+        .clone();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("data file: problem: data file is invalid"));
+    assert_eq!(fs::read_to_string(sandbox.data_file()).unwrap(), "{");
+    for entry in fs::read_dir(&dir).unwrap() {
+        let name = entry.unwrap().file_name().into_string().unwrap();
+        assert!(!name.contains("doctor"), "{name}");
+    }
+}
+
+#[test]
+fn history_lists_changes() {
+    let sandbox = Sandbox::new();
+    sandbox
+        .loago(&["history"])
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("loago: nothing has been changed yet\n");
+    sandbox.loago(&["do", "dust", "mop"]).assert().success();
+    sandbox.loago(&["view"]).assert().success();
+    sandbox.loago(&["tag", "dust", "room"]).assert().success();
+    sandbox
+        .loago(&["remove", "--except", "dust"])
+        .assert()
+        .success();
+    let history = sandbox.stdout(&["history"]);
+    let commands: Vec<&str> = history
+        .lines()
+        .map(|line| line.split_once("  ").unwrap().1)
+        .collect();
+    assert_eq!(commands, [
+        "do dust mop",
+        "tag dust room",
+        "remove --except dust"
+    ]);
+    assert_eq!(sandbox.stdout(&["history", "-n", "1"]).lines().count(), 1);
+    assert_eq!(sandbox.stdout(&["history", "mop"]).lines().count(), 1);
+    sandbox
+        .loago(&["history", "vacuum"])
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("loago: nothing has been recorded for \"vacuum\" yet\n");
+}
+
+#[test]
+fn view_out_writes_file() {
+    let sandbox = Sandbox::new();
+    sandbox.loago(&["do", "dust"]).assert().success();
+    let report = sandbox.dir.path().join("report.txt");
+    fs::write(&report, "something old and much longer").unwrap();
+    let out = report.to_str().unwrap();
+    assert_eq!(sandbox.stdout(&["view", "--out", out]), "");
+    assert_eq!(fs::read_to_string(&report).unwrap(), "dust — 0\n");
+    sandbox
+        .loago(&["view", "--names-only", "--out", out])
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(&report).unwrap(), "dust\n");
+}
+
+#[test]
+fn dry_run_writes_nothing() {
+    let sandbox = Sandbox::new();
+    sandbox
+        .loago(&["--dry-run", "do", "dust"])
+        .assert()
+        .success()
+        .stdout(
+            "updated: dust (dust was created)\n(dry run, no changes written)\n",
+        );
+    assert!(!sandbox.data_file().exists());
+    sandbox.loago(&["do", "dust", "vacuum"]).assert().success();
+    let before = fs::read_to_string(sandbox.data_file()).unwrap();
+    sandbox
+        .loago(&["remove", "--dry-run", "vacuum"])
+        .assert()
+        .success()
+        .stdout("removed: vacuum\n(dry run, no changes written)\n");
+    assert_eq!(fs::read_to_string(sandbox.data_file()).unwrap(), before);
+    assert_eq!(sandbox.stdout(&["history"]).lines().count(), 1);
+}
+
+#[test]
+fn json_errors() {
+    let sandbox = Sandbox::new();
+    sandbox
+        .loago(&["--json", "every", "dust", "7d"])
+        .assert()
+        .code(9)
+        .stderr("{\"error\":\"task \\\"dust\\\" doesn't exist\",\"code\":9}\n");
+    sandbox
+        .loago(&["--json", "view", "--bogus"])
+        .assert()
+        .code(2)
+        .stderr(
+            "{\"error\":\"unexpected argument '--bogus' found\",\"code\":2}\n",
+        );
+    let output = sandbox
+        .loago(&["view", "--bogus"])
+        .assert()
+        .code(2)
+        .get_output()
+        .clone();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("error: unexpected argument '--bogus' found"));
+    sandbox.loago(&["--json", "--help"]).assert().success();
+}
+
+#[test]
+fn do_no_create() {
+    let sandbox = Sandbox::new();
+    sandbox.loago(&["do", "dust"]).assert().success();
+    let before = fs::read_to_string(sandbox.data_file()).unwrap();
+    sandbox
+        .loago(&["do", "--no-create", "dust", "dsut"])
+        .assert()
+        .code(9)
+        .stderr("loago: task \"dsut\" doesn't exist\n");
+    assert_eq!(fs::read_to_string(sandbox.data_file()).unwrap(), before);
+    sandbox
+        .loago(&["do", "--no-create", "dust"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn names_are_nfc() {
+    let sandbox = Sandbox::new();
+    sandbox.loago(&["do", "cafe\u{301}"]).assert().success();
+    assert!(sandbox.saved()["tasks"].get("caf\u{e9}").is_some());
+    sandbox
+        .loago(&["tag", "caf\u{e9}", "out"])
+        .assert()
+        .success();
+    assert_eq!(sandbox.stdout(&["view", "--names-only"]), "caf\u{e9}\n");
+}
+
+#[test]
+fn porcelain_delimiter() {
+    let sandbox = Sandbox::new();
+    sandbox.loago(&["do", "wash the car"]).assert().success();
+    let line = sandbox.stdout(&["view", "--porcelain"]);
+    let fields: Vec<&str> = line.trim_end().split('\t').collect();
+    assert_eq!(fields.len(), 3);
+    assert_eq!(fields[1], "0");
+    assert_eq!(fields[2], "wash the car");
+    let line = sandbox.stdout(&["view", "--porcelain", "--delimiter", ","]);
+    assert!(line.ends_with(",0,wash the car\n"));
+    sandbox
+        .loago(&["view", "--delimiter", ","])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn emptying_needs_allow_empty() {
+    let sandbox = Sandbox::new();
+    sandbox.loago(&["do", "dust", "vacuum"]).assert().success();
+    sandbox
+        .loago(&["remove", "--except", "nothing"])
+        .assert()
+        .code(16);
+    assert_eq!(sandbox.stdout(&["view", "--names-only"]), "dust\nvacuum\n");
+    sandbox
+        .loago(&["remove", "--allow-empty", "dust", "vacuum"])
+        .assert()
+        .success();
+    assert_eq!(sandbox.stdout(&["view", "--names-only"]), "");
+    sandbox.loago(&["do", "dust"]).assert().success();
+    sandbox.loago(&["clear"]).assert().success();
+    assert_eq!(sandbox.saved()["tasks"], serde_json::json!({}));
+}
+
+#[test]
+fn search_count_only() {
+    let sandbox = Sandbox::new();
+    sandbox
+        .loago(&["do", "clean-floor", "Clean-car", "dust"])
+        .assert()
+        .success();
+    assert_eq!(sandbox.stdout(&["search", "clean", "--count-only"]), "2\n");
+    assert_eq!(sandbox.stdout(&["search", "-r", "^d", "-c"]), "1\n");
+    assert_eq!(sandbox.stdout(&["search", "mop", "-c"]), "0\n");
+}
+
+#[test]
+fn data_dir_from_env() {
+    let sandbox = Sandbox::new();
+    let data_file = sandbox.dir.path().join("loago").join("loago.json");
+    sandbox
+        .without_data_file()
+        .arg("path")
+        .assert()
+        .success()
+        .stdout(format!("{}\n", data_file.display()));
+    sandbox
+        .without_data_file()
+        .args(["do", "dust"])
+        .assert()
+        .success();
+    assert!(data_file.exists());
+    assert!(!sandbox.data_file().exists());
+}
+
+#[test]
+fn data_file_from_env() {
+    let sandbox = Sandbox::new();
+    let from_env = sandbox.dir.path().join("synced").join("tasks.json");
+    let flag = sandbox.dir.path().join("flag.json");
+    let loago = || {
+        let mut command = sandbox.without_data_file();
+        command
+            .current_dir(sandbox.dir.path())
+            .env("LOAGO_DATA", "synced/tasks.json");
+        command
+    };
+    loago()
+        .arg("path")
+        .assert()
+        .success()
+        .stdout(format!("{}\n", from_env.display()));
+    loago().args(["do", "dust"]).assert().success();
+    assert!(from_env.exists());
+    loago()
+        .args(["--file", "flag.json", "do", "mop"])
+        .assert()
+        .success();
+    let tasks = |path| {
+        let mut command = sandbox.without_data_file();
+        command.arg("--file").arg(path).args(["view", "-n"]);
+        let output = command.assert().success().get_output().clone();
+        String::from_utf8(output.stdout).unwrap()
+    };
+    assert_eq!(tasks(&from_env), "dust\n");
+    assert_eq!(tasks(&flag), "mop\n");
+}
+
+#[test]
+fn too_many_tasks() {
+    let sandbox = Sandbox::new();
+    let names: Vec<String> = (0..51).map(|n| format!("task{n}")).collect();
+    let mut args = vec!["do"];
+    args.extend(names.iter().map(String::as_str));
+    sandbox.loago(&args).assert().code(17);
+    assert_eq!(sandbox.stdout(&["count"]), "0\n");
+    args.push("--force");
+    sandbox.loago(&args).assert().success();
+    assert_eq!(sandbox.stdout(&["count"]), "51\n");
+    fs::write(sandbox.dir.path().join("config.toml"), "max_tasks = 2").unwrap();
+    sandbox
+        .loago(&["remove", "task0", "task1", "task2"])
+        .assert()
+        .code(17);
+    sandbox
+        .loago(&["remove", "task0", "task1"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn remove_older_than_lists_removed() {
+    let sandbox = Sandbox::new();
+    fs::create_dir_all(sandbox.data_file().parent().unwrap()).unwrap();
+    let contents = r#"{"version": 4, "tasks": {
+        "dust": {"timestamp": "2000-01-01T00:00:00"},
+        "mop": {"timestamp": "2000-01-02T00:00:00"}
+    }}"#;
+    fs::write(sandbox.data_file(), contents).unwrap();
+    sandbox.loago(&["do", "vacuum"]).assert().success();
+    sandbox
+        .loago(&["remove", "--older-than", "365d", "--porcelain"])
+        .assert()
+        .success()
+        .stdout("dust\nmop\n")
+        .stderr("");
+    assert_eq!(sandbox.stdout(&["view", "--names-only"]), "vacuum\n");
+    fs::write(sandbox.data_file(), contents).unwrap();
+    sandbox
+        .loago(&["remove", "--older-than", "365d", "--allow-empty"])
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("dust\nmop\n");
+}
+
+#[test]
+fn do_from_list() {
+    let sandbox = Sandbox::new();
+    fs::write(sandbox.dir.path().join("config.toml"), "max_tasks = 1\n")
+        .unwrap();
+    let list = sandbox.dir.path().join("tasks.txt");
+    fs::write(&list, "# weekly\n dust \n\nvacuum\n").unwrap();
+    let from = list.to_str().unwrap();
+    sandbox
+        .loago(&["do", "--from", from])
+        .assert()
+        .success()
+        .stdout("updated 2 tasks\n");
+    assert_eq!(sandbox.stdout(&["view", "-n"]), "dust\nvacuum\n");
+    sandbox
+        .loago(&["do", "--stdin"])
+        .write_stdin("mop\n")
+        .assert()
+        .success()
+        .stdout("updated 1 task\n");
+    let saved = sandbox.saved();
+    let mut names: Vec<&String> =
+        saved["tasks"].as_object().unwrap().keys().collect();
+    names.sort_unstable();
+    assert_eq!(names, ["dust", "mop", "vacuum"]);
+    sandbox
+        .loago(&["do", "--from", from, "mop"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn do_selectors() {
+    let sandbox = Sandbox::new();
+    fs::create_dir_all(sandbox.data_file().parent().unwrap()).unwrap();
+    let contents = r#"{"version": 4, "tasks": {
+        "clean-oven": {"timestamp": "2000-01-01T00:00:00", "tags": ["kitchen"]},
+        "clean.sink": {"timestamp": "2000-01-01T00:00:00", "tags": ["kitchen"]},
+        "cleanXsink": {"timestamp": "2000-01-01T00:00:00"},
+        "dust": {"timestamp": "2000-01-01T00:00:00"}
+    }}"#;
+    fs::write(sandbox.data_file(), contents).unwrap();
+    let recent = |sandbox: &Sandbox| {
+        let stdout = sandbox.stdout(&["view", "--newer-than", "1d", "-n"]);
+        stdout.lines().map(str::to_owned).collect::<Vec<_>>()
+    };
+    sandbox
+        .loago(&["do", "--tag", "kitchen"])
+        .assert()
+        .success()
+        .stdout("2 tasks matched\n");
+    assert_eq!(recent(&sandbox), ["clean-oven", "clean.sink"]);
+    fs::write(sandbox.data_file(), contents).unwrap();
+    sandbox
+        .loago(&["do", "--glob", "clean.s*"])
+        .assert()
+        .success()
+        .stdout("1 task matched\n");
+    assert_eq!(recent(&sandbox), ["clean.sink"]);
+    sandbox
+        .loago(&["do", "--glob", "mop?"])
+        .assert()
+        .success()
+        .stdout("0 tasks matched\n");
+    assert_eq!(sandbox.stdout(&["count"]), "4\n");
+    sandbox
+        .loago(&["do", "--tag", "kitchen", "dust"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn adjust() {
+    let sandbox = Sandbox::new();
+    sandbox.loago(&["do", "dust"]).assert().success();
+    sandbox.loago(&["adjust", "dust", "-2d"]).assert().success();
+    assert_eq!(sandbox.stdout(&["view"]), "dust — 2\n");
+    sandbox.loago(&["adjust", "dust", "+1d"]).assert().success();
+    assert_eq!(sandbox.stdout(&["view"]), "dust — 1\n");
+    sandbox.loago(&["adjust", "mop", "1d"]).assert().code(9);
+    sandbox.loago(&["adjust", "dust", "-2x"]).assert().code(2);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn default_data_dir() {
+    let sandbox = Sandbox::new();
+    let path = |command: &mut Command| {
+        let output = command.arg("path").env_remove("LOAGO_DATA_DIR").output();
+        String::from_utf8(output.unwrap().stdout).unwrap()
+    };
+    let data_home = sandbox.dir.path().join("share");
+    let expected = data_home.join("loago").join("loago.json");
+    assert_eq!(
+        path(sandbox.without_data_file().env("XDG_DATA_HOME", &data_home)),
+        format!("{}\n", expected.display())
+    );
+    let renamed = data_home.join("chores").join("tasks.json");
+    assert_eq!(
+        path(
+            sandbox
+                .without_data_file()
+                .env("XDG_DATA_HOME", &data_home)
+                .env("LOAGO_APP_NAME", "chores")
+                .env("LOAGO_DATA_FILE_NAME", "tasks.json")
+        ),
+        format!("{}\n", renamed.display())
+    );
+}
+
+#[test]
+fn no_create_dirs() {
+    let sandbox = Sandbox::new();
+    let data_dir = sandbox.data_file().parent().unwrap().to_owned();
+    sandbox
+        .loago(&["do", "dust", "--no-create-dirs"])
+        .assert()
+        .code(3)
+        .stderr(format!(
+            "loago: {} doesn't exist, and --no-create-dirs is set\n",
+            data_dir.display()
+        ));
+    assert!(!data_dir.exists());
+    fs::create_dir(&data_dir).unwrap();
+    sandbox
+        .loago(&["do", "dust", "--no-create-dirs"])
+        .assert()
+        .success();
+    assert!(sandbox.data_file().exists());
+}
+
+#[test]
+fn verbose_says_what_was_written() {
+    let sandbox = Sandbox::new();
+    let output = sandbox.stdout(&["--verbose", "do", "dust"]);
+    let size = fs::metadata(sandbox.data_file()).unwrap().len();
+    assert_eq!(
+        output,
+        format!(
+            "wrote {size} B to {}\nupdated: dust (dust was created)\n",
+            sandbox.data_file().display()
+        )
+    );
+    assert_eq!(sandbox.stdout(&["do", "dust"]), "");
+}
+
+#[test]
+fn verify() {
+    let sandbox = Sandbox::new();
+    assert_eq!(sandbox.stdout(&["verify"]), "0 tasks\n");
+    sandbox.loago(&["do", "dust", "mop"]).assert().success();
+    assert_eq!(sandbox.stdout(&["verify"]), "2 tasks\n");
+    let contents = r#"{"version": 4, "tasks": {
+        "dust": {"timestamp": "2023-12-20T00:00:00"},
+        "vacuum": {"timestamp": "yesterday"},
+        "mop": {"timestamp": "2023-12-20"}
+    }}"#;
+    fs::write(sandbox.data_file(), contents).unwrap();
+    sandbox
+        .loago(&["verify"])
+        .assert()
+        .code(18)
+        .stdout(
+            "3 tasks\n\"mop\": invalid timestamp: premature end of input\n\
+             \"vacuum\": invalid timestamp: input contains invalid \
+             characters\n",
+        )
+        .stderr("loago: 2 tasks have invalid timestamps\n");
+    assert_eq!(fs::read_to_string(sandbox.data_file()).unwrap(), contents);
+}
+
+#[test]
+fn view_between_durations() {
+    let sandbox = Sandbox::new();
+    sandbox
+        .loago(&["do", "dust", "mop", "vacuum"])
+        .assert()
+        .success();
+    sandbox.loago(&["adjust", "mop", "-10d"]).assert().success();
+    sandbox
+        .loago(&["adjust", "vacuum", "-40d"])
+        .assert()
+        .success();
+    let view = |args: &[&str]| {
+        sandbox.stdout(&[&["view", "--names-only"], args].concat())
+    };
+    assert_eq!(view(&["--after", "30d", "--before", "7d"]), "mop\n");
+    assert_eq!(view(&["--after", "30d"]), "dust\nmop\n");
+    assert_eq!(view(&["--before", "7d"]), "mop\nvacuum\n");
+    assert_eq!(
+        view(&["--newer-than", "30d", "--older-than", "7d"]),
+        view(&["--after", "30d", "--before", "7d"])
+    );
+    sandbox
+        .loago(&["view", "--after", "7d", "--before", "30d"])
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("loago: no tasks match\n");
+}
+
+#[test]
+fn only_overdue() {
+    let sandbox = Sandbox::new();
+    sandbox
+        .loago(&["do", "dust", "mop", "sweep", "vacuum"])
+        .assert()
+        .success();
+    for (task, interval, delta) in [
+        ("dust", "1d", "-3d"),
+        ("mop", "5d", "-10d"),
+        ("sweep", "30d", "-20d"),
+    ] {
+        sandbox.loago(&["every", task, interval]).assert().success();
+        sandbox.loago(&["adjust", task, delta]).assert().success();
+    }
+    sandbox
+        .loago(&["adjust", "vacuum", "-40d"])
+        .assert()
+        .success();
+    let view = |args: &[&str]| {
+        sandbox.stdout(
+            &[&["view", "--only-overdue", "--names-only"], args].concat(),
+        )
+    };
+    assert_eq!(view(&[]), "dust\nmop\n");
+    assert_eq!(view(&["--reverse", "--limit", "1"]), "mop\n");
+    assert_eq!(
+        sandbox.stdout(&["view", "--reverse", "--names-only"]),
+        "vacuum\nsweep\nmop\ndust\n"
+    );
+}
+
+#[test]
+fn sort_overdue() {
+    let sandbox = Sandbox::new();
+    sandbox
+        .loago(&["do", "dust", "mop", "sweep", "vacuum"])
+        .assert()
+        .success();
+    for (task, interval, delta) in [
+        ("dust", "1d", "-3d"),
+        ("mop", "30d", "-10d"),
+        ("sweep", "365d", "-2d"),
+    ] {
+        sandbox.loago(&["every", task, interval]).assert().success();
+        sandbox.loago(&["adjust", task, delta]).assert().success();
+    }
+    sandbox
+        .loago(&["adjust", "vacuum", "-1d"])
+        .assert()
+        .success();
+    assert_eq!(
+        sandbox.stdout(&["view", "--names-only"]),
+        "vacuum\nsweep\ndust\nmop\n"
+    );
+    assert_eq!(
+        sandbox.stdout(&["view", "--names-only", "--sort", "overdue"]),
+        "dust\nmop\nsweep\nvacuum\n"
+    );
+    sandbox
+        .loago(&["view", "--sort", "overdue", "--group-by", "tag"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn group_by_age() {
+    let sandbox = Sandbox::new();
+    sandbox
+        .loago(&["do", "dust", "mop", "sweep", "vacuum"])
+        .assert()
+        .success();
+    for (task, delta) in [("mop", "-3d"), ("sweep", "-3d"), ("vacuum", "-60d")]
+    {
+        sandbox.loago(&["adjust", task, delta]).assert().success();
+    }
+    assert_eq!(
+        sandbox.stdout(&["view", "--group-by", "age"]),
+        "today\ndust — 0\n\nthis week\nmop   — 3\nsweep — 3\n\nolder\nvacuum \
+         — 60\n"
+    );
+    fs::write(
+        sandbox.dir.path().join("config.toml"),
+        "[age_buckets]\nrecent = \"90d\"\n",
+    )
+    .unwrap();
+    sandbox
+        .loago(&["view", "--group-by", "age", "-r"])
+        .assert()
+        .success()
+        .stdout("recent\nvacuum — 60\nsweep  — 3\nmop    — 3\ndust   — 0\n");
+}
+
+#[test]
+fn csv_no_header() {
+    let sandbox = Sandbox::new();
+    fs::create_dir_all(sandbox.data_file().parent().unwrap()).unwrap();
+    fs::write(
+        sandbox.data_file(),
+        r#"{"wash car, outside": "2000-01-01T00:00:00"}"#,
+    )
+    .unwrap();
+    let output = sandbox.stdout(&["view", "--format", "csv"]);
+    let mut lines = output.lines();
+    assert_eq!(lines.next(), Some("task,days,last_done"));
+    let row = lines.next().unwrap();
+    assert!(row.starts_with("\"wash car, outside\","), "{row}");
+    assert!(row.ends_with(",2000-01-01T00:00:00"), "{row}");
+    assert_eq!(lines.next(), None);
+    assert_eq!(
+        sandbox.stdout(&["view", "--format", "csv", "--no-header"]),
+        format!("{row}\n")
+    );
+    sandbox.loago(&["view", "--no-header"]).assert().code(2);
+}
+
+#[test]
+fn view_tree() {
+    let sandbox = Sandbox::new();
+    sandbox
+        .loago(&["do", "home/kitchen", "home/bathroom", "dust"])
+        .assert()
+        .success();
+    sandbox
+        .loago(&["adjust", "home/bathroom", "-1d"])
+        .assert()
+        .success();
+    sandbox.loago(&["adjust", "dust", "-2d"]).assert().success();
+    assert_eq!(
+        sandbox.stdout(&["view", "--tree"]),
+        "home\n  kitchen  — 0\n  bathroom — 1\ndust       — 2\n"
+    );
+    assert_eq!(
+        sandbox.stdout(&["view", "--tree", "--tree-separator", "/k"]),
+        "home\n  itchen      — 0\nhome/bathroom — 1\ndust          — 2\n"
+    );
+    sandbox
+        .loago(&["view", "--tree", "--tree-separator", ""])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn view_precision() {
+    let sandbox = Sandbox::new();
+    sandbox.loago(&["do", "dust", "mop"]).assert().success();
+    // 1.4 and 1.6 days ago.
+    sandbox
+        .loago(&["adjust", "dust", "-1d9h36m"])
+        .assert()
+        .success();
+    sandbox
+        .loago(&["adjust", "mop", "-1d14h24m"])
+        .assert()
+        .success();
+    assert_eq!(sandbox.stdout(&["view"]), "dust — 1\nmop  — 1\n");
+    assert_eq!(
+        sandbox.stdout(&["view", "--precision", "truncate"]),
+        "dust — 1\nmop  — 1\n"
+    );
+    assert_eq!(
+        sandbox.stdout(&["view", "--precision", "round"]),
+        "dust — 1\nmop  — 2\n"
+    );
+    assert_eq!(
+        sandbox.stdout(&["view", "--precision", "tenths"]),
+        "dust — 1.4\nmop  — 1.6\n"
+    );
+    assert_eq!(
+        sandbox.stdout(&["view", "--precision", "round", "--weeks"]),
+        "dust — 0\nmop  — 0\n"
+    );
+}
+
+#[test]
+fn view_template() {
+    let sandbox = Sandbox::new();
+    sandbox.loago(&["do", "dust", "mop"]).assert().success();
+    sandbox.loago(&["adjust", "mop", "-2d"]).assert().success();
+    assert_eq!(
+        sandbox.stdout(&["view", "--template", "{name}: {days}d {{{hours}h}}"]),
+        "dust: 0d {0h}\nmop: 2d {48h}\n"
+    );
+    let output = sandbox
+        .loago(&["view", "--template", "{name} {day}"])
+        .assert()
+        .code(2)
+        .get_output()
+        .clone();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("unknown placeholder {day}"), "{stderr}");
+}
+
+#[test]
+fn data_file_not_a_file() {
+    let sandbox = Sandbox::new();
+    fs::create_dir_all(sandbox.data_file()).unwrap();
+    let message = format!(
+        "loago: data file path is not a regular file: {}\n",
+        sandbox.data_file().display()
+    );
+    for args in [&["view"][..], &["do", "dust"]] {
+        sandbox
+            .loago(args)
+            .assert()
+            .code(19)
+            .stderr(message.clone());
+    }
+    fs::remove_dir(sandbox.data_file()).unwrap();
+    #[cfg(unix)]
+    {
+        let nowhere = sandbox.dir.path().join("nowhere.json");
+        std::os::unix::fs::symlink(nowhere, sandbox.data_file()).unwrap();
+        sandbox
+            .loago(&["do", "dust"])
+            .assert()
+            .code(19)
+            .stderr(message);
+    }
+}
+
+#[test]
+fn view_also() {
+    let sandbox = Sandbox::new();
+    sandbox.loago(&["do", "dust", "mop"]).assert().success();
+    sandbox.loago(&["adjust", "mop", "-2d"]).assert().success();
+    let other = sandbox.dir.path().join("other.json");
+    let other_file = other.to_str().unwrap();
+    let loago_other = |args: &[&str]| {
+        sandbox
+            .without_data_file()
+            .args([&["--data-file", other_file], args].concat())
+            .assert()
+            .success();
+    };
+    loago_other(&["do", "mop", "vacuum"]);
+    loago_other(&["adjust", "vacuum", "-5d"]);
+    let before = fs::read_to_string(&other).unwrap();
+    let mine = fs::read_to_string(sandbox.data_file()).unwrap();
+    assert_eq!(
+        sandbox.stdout(&["view", "--also", other_file]),
+        "mop    — 0\ndust   — 0\nvacuum — 5\n"
+    );
+    assert_eq!(fs::read_to_string(&other).unwrap(), before);
+    assert_eq!(fs::read_to_string(sandbox.data_file()).unwrap(), mine);
+    let missing = sandbox.dir.path().join("missing.json");
+    sandbox
+        .loago(&["view", "--also", missing.to_str().unwrap()])
+        .assert()
+        .code(3)
+        .stderr(format!("loago: {} doesn't exist\n", missing.display()));
+}
+
+#[test]
+fn fail_if_empty() {
+    let sandbox = Sandbox::new();
+    sandbox
+        .loago(&["count", "--fail-if-empty"])
+        .assert()
+        .code(1)
+        .stdout("0\n")
+        .stderr("");
+    sandbox.loago(&["do", "dust"]).assert().success();
+    sandbox
+        .loago(&["count", "--fail-if-empty"])
+        .assert()
+        .success();
+    sandbox
+        .loago(&["view", "--only-overdue", "--fail-if-empty", "--names-only"])
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr("");
+    sandbox
+        .loago(&["--json", "view", "--only-overdue", "--fail-if-empty"])
+        .assert()
+        .code(1)
+        .stdout("[]\n")
+        .stderr("");
+    sandbox
+        .loago(&["view", "--fail-if-empty", "--names-only"])
+        .assert()
+        .success()
+        .stdout("dust\n");
+    sandbox
+        .loago(&["search", "mop", "--fail-if-empty"])
+        .assert()
+        .code(1)
+        .stdout("");
+    sandbox
+        .loago(&["search", "us", "--count-only", "--fail-if-empty"])
+        .assert()
+        .success()
+        .stdout("1\n");
+}
+
+#[test]
+fn view_today() {
+    let sandbox = Sandbox::new();
+    sandbox.loago(&["do", "dust", "mop"]).assert().success();
+    sandbox.loago(&["adjust", "mop", "-1d"]).assert().success();
+    for timezone in ["UTC", "Pacific/Kiritimati", "America/Adak"] {
+        let output = sandbox
+            .loago(&["view", "--today", "--names-only"])
+            .env("TZ", timezone)
+            .assert()
+            .success()
+            .get_output()
+            .clone();
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), "dust\n");
+    }
+}
+
+#[test]
+fn default_interval() {
+    let sandbox = Sandbox::new();
+    sandbox
+        .loago(&["do", "dust", "mop", "sweep"])
+        .assert()
+        .success();
+    sandbox.loago(&["every", "dust", "30d"]).assert().success();
+    for task in ["dust", "mop"] {
+        sandbox.loago(&["adjust", task, "-10d"]).assert().success();
+    }
+    let overdue = |args: &[&str]| {
+        sandbox.stdout(
+            &[&["view", "--only-overdue", "--names-only"], args].concat(),
+        )
+    };
+    assert_eq!(overdue(&[]), "");
+    assert_eq!(overdue(&["--default-interval", "7d"]), "mop\n");
+    assert_eq!(overdue(&["--default-interval", "1d"]), "mop\n");
+    let stats = sandbox.stdout(&["stats", "--default-interval", "7d"]);
+    assert_eq!(stats, "tasks: 3\nwith an interval: 3\noverdue: 1\n");
+    // It's never written to the data file.
+    assert_eq!(overdue(&[]), "");
+}
+
+#[test]
+fn snooze() {
+    let sandbox = Sandbox::new();
+    sandbox.loago(&["do", "vacuum"]).assert().success();
+    sandbox.loago(&["every", "vacuum", "7d"]).assert().success();
+    sandbox
+        .loago(&["adjust", "vacuum", "-10d"])
+        .assert()
+        .success();
+    let overdue =
+        || sandbox.stdout(&["view", "--only-overdue", "--names-only"]);
+    assert_eq!(overdue(), "vacuum\n");
+    sandbox
+        .loago(&["snooze", "vacuum", "2d"])
+        .assert()
+        .success();
+    assert_eq!(overdue(), "");
+    assert_eq!(sandbox.stdout(&["view"]), "vacuum — due in 1 day\n");
+    sandbox
+        .loago(&["adjust", "vacuum", "-3d"])
+        .assert()
+        .success();
+    assert_eq!(overdue(), "vacuum\n");
+    sandbox.loago(&["snooze", "mop", "2d"]).assert().code(9);
+}
+
+#[test]
+fn view_relative_days() {
+    let sandbox = Sandbox::new();
+    sandbox
+        .loago(&["do", "dust", "mop", "sweep"])
+        .assert()
+        .success();
+    sandbox.loago(&["adjust", "mop", "-3d"]).assert().success();
+    sandbox
+        .loago(&["adjust", "sweep", "-30d"])
+        .assert()
+        .success();
+    let view = |args: &[&str]| {
+        sandbox.stdout(&[&["view", "--names-only"], args].concat())
+    };
+    assert_eq!(view(&["--since", "today"]), "dust\n");
+    assert_eq!(view(&["--since", "2 weeks ago"]), "dust\nmop\n");
+    assert_eq!(view(&["--until", "yesterday"]), "mop\nsweep\n");
+    let output = sandbox
+        .loago(&["view", "--since", "last month"])
+        .assert()
+        .code(2)
+        .get_output()
+        .clone();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("3 days ago"), "{stderr}");
+}
+
+#[test]
+fn rename() {
+    let sandbox = Sandbox::new();
+    sandbox
+        .loago(&["do", "home-dust", "home-mop", "house-mop", "vacuum"])
+        .assert()
+        .success();
+    sandbox
+        .loago(&["adjust", "home-mop", "-2d"])
+        .assert()
+        .success();
+    sandbox
+        .loago(&["group", "add", "chores", "home-dust", "vacuum"])
+        .assert()
+        .success();
+    sandbox
+        .loago(&["rename", "--prefix", "home-", "house-"])
+        .assert()
+        .success();
+    let names = sandbox.stdout(&["view", "--names-only"]);
+    assert_eq!(names, "house-dust\nhouse-mop\nvacuum\n");
+    // The more recently done mop is kept.
+    assert_eq!(sandbox.stdout(&["view", "house-mop"]), "house-mop — 0\n");
+    sandbox
+        .loago(&["rename", "vacuum", "hoover"])
+        .assert()
+        .success();
+    assert_eq!(
+        sandbox.stdout(&["group", "list"]),
+        "chores — hoover, house-dust\n"
+    );
+    sandbox.loago(&["rename", "vacuum", "mop"]).assert().code(9);
+}
+
+#[test]
+fn swap() {
+    let sandbox = Sandbox::new();
+    sandbox.loago(&["do", "dust", "mop"]).assert().success();
+    sandbox.loago(&["adjust", "dust", "-2d"]).assert().success();
+    sandbox
+        .loago(&["group", "add", "floors", "mop"])
+        .assert()
+        .success();
+    sandbox.loago(&["swap", "dust", "mop"]).assert().success();
+    assert_eq!(sandbox.stdout(&["view"]), "dust — 0\nmop  — 2\n");
+    assert_eq!(sandbox.stdout(&["group", "list"]), "floors — dust\n");
+    sandbox
+        .loago(&["swap", "mop", "sweep"])
+        .assert()
+        .code(9)
+        .stderr("loago: task \"sweep\" doesn't exist\n");
+    assert_eq!(sandbox.stdout(&["view"]), "dust — 0\nmop  — 2\n");
+}
+
+#[test]
+fn extreme_timestamps() {
+    let sandbox = Sandbox::new();
+    let contents = r#"{"version": 4, "tasks": {
+        "ancient": {"timestamp": "-262143-01-01T00:00:00", "interval": "7d"},
+        "future": {
+            "timestamp": "+262142-12-31T23:59:59",
+            "interval": "106751991167d"
+        }
+    }}"#;
+    fs::create_dir_all(sandbox.data_file().parent().unwrap()).unwrap();
+    fs::write(sandbox.data_file(), contents).unwrap();
+    for args in [
+        &["view"][..],
+        &["view", "--seconds"],
+        &["view", "--sort", "overdue"],
+        &["view", "--porcelain"],
+        &["stats", "--streaks"],
+        &["next"],
+    ] {
+        sandbox.loago(args).assert().success();
+    }
+    assert_eq!(
+        sandbox.stdout(&["view", "future"]),
+        "future — due in 106751991167 days\n"
+    );
+}
+
+#[test]
+fn strict_parse() {
+    let sandbox = Sandbox::new();
+    let contents = r#"{"version": 4, "tasks": {
+        "vacuum": {"timestamp": "2023-12-20T00:00:00", "intrval": "7d"}
+    }}"#;
+    fs::create_dir_all(sandbox.data_file().parent().unwrap()).unwrap();
+    fs::write(sandbox.data_file(), contents).unwrap();
+    sandbox.loago(&["view"]).assert().success();
+    let output = sandbox
+        .loago(&["view", "--strict-parse"])
+        .assert()
+        .code(5)
+        .get_output()
+        .clone();
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "loago: data file is invalid: unknown field \"intrval\" in task \
+         \"vacuum\", expected one of timestamp, tags, interval, created\n"
+    );
+}
+
+#[test]
+fn duplicate_tasks() {
+    let sandbox = Sandbox::new();
+    let contents = r#"{"version": 4, "tasks": {
+        "vacuum": {"timestamp": "2023-12-20T00:00:00"},
+        "vacuum": {"timestamp": "2023-12-21T00:00:00"}
+    }}"#;
+    fs::create_dir_all(sandbox.data_file().parent().unwrap()).unwrap();
+    fs::write(sandbox.data_file(), contents).unwrap();
+    let stderr = |args: &[&str], code: i32| {
+        let output =
+            sandbox.loago(args).assert().code(code).get_output().clone();
+        String::from_utf8(output.stderr).unwrap()
+    };
+    assert_eq!(
+        stderr(&["view", "--names-only"], 0),
+        "loago: the data file has \"vacuum\" more than once, only the last \
+         one is kept\n"
+    );
+    assert_eq!(
+        stderr(&["view", "--strict-parse"], 5),
+        "loago: data file is invalid: duplicate key \"vacuum\"\n"
+    );
+    assert_eq!(stderr(&["view", "--quiet"], 0), "");
+}
+
+#[test]
+fn view_relative_to() {
+    let sandbox = Sandbox::new();
+    let contents = r#"{"version": 4, "tasks": {
+        "vacuum": {"timestamp": "2023-12-20T12:00:00"},
+        "mop": {"timestamp": "2023-12-23T12:00:00"},
+        "dust": {"timestamp": "2023-12-18T12:00:00"}
+    }}"#;
+    fs::create_dir_all(sandbox.data_file().parent().unwrap()).unwrap();
+    fs::write(sandbox.data_file(), contents).unwrap();
+    assert_eq!(
+        sandbox.stdout(&["view", "--relative-to", "vacuum"]),
+        "mop    — -3\nvacuum — 0\ndust   — 2\n"
+    );
+    assert_eq!(
+        sandbox.stdout(&["view", "dust", "--relative-to", "mop"]),
+        "dust — 5\n"
+    );
+    assert_eq!(
+        sandbox.stdout(&[
+            "view",
+            "--relative-to",
+            "vacuum",
+            "--newer-than",
+            "1d",
+            "--names-only"
+        ]),
+        "mop\nvacuum\n"
+    );
+    sandbox
+        .loago(&["view", "--relative-to", "sweep"])
+        .assert()
+        .code(9);
+}
+
+#[test]
+fn history_notes() {
+    let sandbox = Sandbox::new();
+    sandbox
+        .loago(&["do", "vacuum", "--note", "guests coming"])
+        .assert()
+        .success();
+    sandbox.loago(&["do", "dust"]).assert().success();
+    sandbox.loago(&["do", "vacuum", "mop"]).assert().success();
+    let commands = |args: &[&str]| -> Vec<String> {
+        sandbox
+            .stdout(&[&["history"], args].concat())
+            .lines()
+            .map(|line| line.split_once("  ").unwrap().1.to_owned())
+            .collect()
+    };
+    assert_eq!(commands(&["vacuum"]), [
+        "do vacuum — guests coming",
+        "do vacuum mop"
+    ]);
+    assert_eq!(commands(&["-n", "1", "dust"]), ["do dust"]);
+    assert!(commands(&["sweep"]).is_empty());
+    let history =
+        fs::read_to_string(sandbox.data_file().with_extension("json.history"))
+            .unwrap();
+    assert_eq!(history.matches("\"note\"").count(), 1);
+}
+
+#[test]
+fn view_older_than() {
+    let sandbox = Sandbox::new();
+    sandbox
+        .loago(&["do", "dust", "mop", "vacuum"])
+        .assert()
+        .success();
+    for (task, delta) in [("dust", "-10d"), ("vacuum", "-10d"), ("mop", "-2d")]
+    {
+        sandbox.loago(&["adjust", task, delta]).assert().success();
+    }
+    assert_eq!(
+        sandbox.stdout(&["view", "--older-than", "5d", "-n"]),
+        "dust\nvacuum\n"
+    );
+    assert_eq!(
+        sandbox.stdout(&["view", "dust", "mop", "--older-than", "3d", "-n"]),
+        "dust\n"
+    );
+    assert_eq!(sandbox.stdout(&["view", "--older-than", "11", "-n"]), "");
+}
+
+#[test]
+fn view_json() {
+    let sandbox = Sandbox::new();
+    sandbox.loago(&["do", "dust", "mop"]).assert().success();
+    sandbox
+        .loago(&["adjust", "dust", "-3d2h"])
+        .assert()
+        .success();
+    let json = |args: &[&str]| -> Value {
+        serde_json::from_str(&sandbox.stdout(args)).unwrap()
+    };
+    let plain = json(&["view", "--json"]);
+    assert_eq!(plain, json(&["view", "--format", "json"]));
+    assert_eq!(plain[0]["task"], "mop");
+    assert_eq!(plain[1]["task"], "dust");
+    assert_eq!(plain[1]["days"], 3);
+    assert!(plain[1].get("hours").is_none());
+    let minutes = json(&["view", "--json", "--minutes"]);
+    assert_eq!(minutes[1]["hours"], 2);
+    assert_eq!(minutes[1]["minutes"], 0);
+    let csv = sandbox.stdout(&["view", "--json", "--format", "csv"]);
+    assert!(csv.starts_with("task,days,last_done\n"));
+    assert_eq!(sandbox.stdout(&["--json", "view", "-n"]), "mop\ndust\n");
+}
+
+#[test]
+fn view_sort() {
+    let sandbox = Sandbox::new();
+    sandbox
+        .loago(&["do", "dust", "mop", "sweep", "vacuum"])
+        .assert()
+        .success();
+    // Mopping and vacuuming were done at the same time.
+    for (task, delta) in [("dust", "-5d"), ("mop", "-2d"), ("vacuum", "-2d")] {
+        sandbox.loago(&["adjust", task, delta]).assert().success();
+    }
+    let sorted = |args: &[&str]| {
+        let mut view = vec!["view", "-n"];
+        view.extend(args);
+        sandbox.stdout(&view)
+    };
+    assert_eq!(sorted(&[]), "sweep\nmop\nvacuum\ndust\n");
+    assert_eq!(sorted(&["--sort", "recent"]), sorted(&[]));
+    assert_eq!(sorted(&["--sort", "age"]), sorted(&[]));
+    assert_eq!(sorted(&["--reverse"]), "dust\nvacuum\nmop\nsweep\n");
+    assert_eq!(sorted(&["--sort", "stale"]), "dust\nmop\nvacuum\nsweep\n");
+    assert_eq!(sorted(&["--sort", "name"]), "dust\nmop\nsweep\nvacuum\n");
+    assert_eq!(
+        sorted(&["--sort", "name", "--reverse"]),
+        "vacuum\nsweep\nmop\ndust\n"
+    );
+}
+
+#[test]
+fn do_every() {
+    let sandbox = Sandbox::new();
+    sandbox.loago(&["do", "dust"]).assert().success();
+    sandbox
+        .loago(&["adjust", "dust", "-10d"])
+        .assert()
+        .success();
+    sandbox
+        .loago(&["do", "vacuum", "--every", "7"])
+        .assert()
+        .success();
+    sandbox
+        .loago(&["do", "kettle", "--never", "--every", "30d"])
+        .assert()
+        .success();
+    let saved = sandbox.saved();
+    assert_eq!(saved["tasks"]["vacuum"]["interval"], "7d");
+    assert_eq!(saved["tasks"]["kettle"]["interval"], "30d");
+    assert!(saved["tasks"]["dust"].get("interval").is_none());
+    assert_eq!(
+        sandbox.stdout(&["view", "--only-overdue", "--names-only"]),
+        "kettle\n"
+    );
+    sandbox
+        .loago(&["do", "--all", "--every", "7d"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn config_view_defaults() {
+    let sandbox = Sandbox::new();
+    sandbox
+        .loago(&["do", "dust", "mop", "sweep"])
+        .assert()
+        .success();
+    for (task, delta) in [("dust", "-40d"), ("mop", "-2d")] {
+        sandbox.loago(&["adjust", task, delta]).assert().success();
+    }
+    fs::write(
+        sandbox.dir.path().join("config.toml"),
+        "sort = \"name\"\nreverse = true\nred_after = \"30d\"\n",
+    )
+    .unwrap();
+    let view = |args: &[&str]| {
+        let mut view = vec!["view", "-n"];
+        view.extend(args);
+        sandbox.stdout(&view)
+    };
+    assert_eq!(view(&[]), "sweep\nmop\ndust\n");
+    assert_eq!(view(&["--no-reverse"]), "dust\nmop\nsweep\n");
+    assert_eq!(view(&["--sort", "recent"]), "dust\nmop\nsweep\n");
+    assert_eq!(
+        view(&["--sort", "recent", "--no-reverse"]),
+        "sweep\nmop\ndust\n"
+    );
+    let colored = sandbox.stdout(&["--color", "always", "view", "dust", "mop"]);
+    assert!(colored.contains("\u{1b}[31m40"), "{colored}");
+    assert!(!colored.contains("\u{1b}[31m2"), "{colored}");
+}
+
+#[test]
+fn view_truncate() {
+    let sandbox = Sandbox::new();
+    sandbox.loago(&["do", "b", "vacuum"]).assert().success();
+    assert_eq!(
+        sandbox.stdout(&["view", "--truncate", "3"]),
+        "b   — 0\nva… — 0\n"
+    );
+    assert_eq!(
+        sandbox.stdout(&["view", "--truncate", "1"]),
+        "b — 0\n… — 0\n"
+    );
+    sandbox
+        .loago(&["view", "--truncate", "0"])
+        .assert()
+        .code(2)
+        .stdout("");
+}